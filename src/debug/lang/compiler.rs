@@ -150,4 +150,18 @@ pub fn print_errors(modules: &Modules) {
             );
         }    
     }
-}
\ No newline at end of file
+}
+
+//================
+//  print_warnings()
+//================
+pub fn print_warnings(modules: &Modules) { 
+    for data in modules.values() {
+        if !data.warnings.is_empty() {
+            error::print_errors(
+                &data.warnings, 
+                &data.script
+            );
+        }    
+    }
+}
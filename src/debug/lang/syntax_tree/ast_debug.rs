@@ -60,24 +60,36 @@ impl fmt::Debug for Expr {
             | Self::Char(t) 
             | Self::Str(t)
             | Self::Ref(t) => write!(f, "{:?}", t.value ),
+            Self::StrSlice(t) => write!(f, "&{:?}", t.value ),
+            Self::GenericRef(e) => write!(f, "{:?}::<{:?}>", e.id.value, e.args),
             Self::List(e) =>  write!(f, "{:?}", e),
+            Self::Map(e) =>  write!(f, "{:?}", e),
             Self::Tuple(e) =>  write!(f, "{:?}", e),
             Self::StructLiteral(e) => write!(f, "{:?}", e),
             Self::BinOp(e) => write!(f, "{:?}", e),
             Self::PreUniOp(e) => write!(f, "{:?}", e),
             Self::PostUniOp(e) => write!(f, "{:?}", e),
+            Self::Cast(e) => write!(f, "({:?} as {:?})", e.expr, e._type),
+            Self::Range(e) => write!(f, "{:?}", e),
+            Self::LetIn(e) => write!(f, "{:?}", e),
             Self::Fn(e) => write!(f, "{:?}", e),
             Self::Match(e) => write!(f, "{:?}", e),
             Self::For(e) => write!(f, "{:?}", e),
             Self::While(e) => write!(f, "{:?}", e),
+            Self::Loop(e) => write!(f, "{:?}", e),
+            Self::Break(e) => write!(f, "{:?}", e),
+            Self::Continue(e) => write!(f, "{:?}", e),
             Self::If(e) => write!(f, "{:?}", e),
             Self::Code(e) => write!(f, "{:?}", e),
-            Self::Ret(e) => write!(f, "return {:?}", e),
 
             Self::Ok(e) => write!(f, "Ok({:?})", e),
             Self::Err(e) => write!(f, "Err({:?})", e),
             Self::Some(e) => write!(f, "Some({:?})", e),
             Self::None => write!(f, "None"),            
+            Self::Borrow(e) => write!(f, "&{:?}", e),
+            Self::Clone(e) => write!(f, "{:?}.clone()", e),
+            Self::Owned(e) => write!(f, "{:?}.to_string()", e),
+            Self::Block(e) => write!(f, "{:?}", e),
 
         }
     }
@@ -100,6 +112,14 @@ impl fmt::Debug for Pattern {
             Pattern::Tuple(p) => p.fmt(f),
             Pattern::Struct(p) => p.fmt(f),
             Pattern::Enum(p) => p.fmt(f),
+            Pattern::Range(p) => p.fmt(f),
+            Pattern::Or(p) => write!(f, "Or{:?}", p),
+            Pattern::Binding(p) => write!(f, "{:?} @ {:?}", p.id.value, p.pattern),
+            Pattern::Rest => write!(f, ".."),
+            Pattern::Ok(p) => write!(f, "Ok({:?})", p),
+            Pattern::Err(p) => write!(f, "Err({:?})", p),
+            Pattern::Some(p) => write!(f, "Some({:?})", p),
+            Pattern::None => write!(f, "None"),
             Pattern::Wildcard =>  writeln!(f, "_")
         }
     }
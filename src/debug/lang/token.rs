@@ -68,6 +68,7 @@ impl fmt::Debug for TokenValue {
             Self::DoubleDot
             | Self::PrefixDoubleDot 
             | Self::PostfixDoubleDot => "..".to_string(),
+            Self::DoubleDotEqual => "..=".to_string(),
             Self::Dollar => "$".to_string(),
             Self::At => "@".to_string(),
             Self::OpenBracket => "[".to_string(),
@@ -95,9 +96,17 @@ impl fmt::Debug for TokenValue {
             Self::Let => "let".to_string(),
             Self::In => "in".to_string(),
             Self::Where => "where".to_string(),
+            Self::As => "as".to_string(),
+            Self::Fn => "fn".to_string(),
+            Self::Pub => "pub".to_string(),
+            Self::Internal => "internal".to_string(),
+            Self::Mut => "mut".to_string(),
             Self::Match => "match".to_string(),
             Self::For => "for".to_string(),
             Self::While => "while".to_string(),
+            Self::Loop => "loop".to_string(),
+            Self::Break => "break".to_string(),
+            Self::Continue => "continue".to_string(),
             Self::If => "if".to_string(),
             Self::Else => "else".to_string(),
             Self::Use=> "use".to_string(),
@@ -10,6 +10,12 @@ pub mod parser;
 pub mod resolver;
 pub mod inference;
 pub mod type_checker;
+pub mod exhaustiveness;
+pub mod mutability;
+pub mod ownership;
+pub mod hir;
+pub mod passes;
+pub mod attrs;
 pub mod compiler;
 pub mod error;
 
@@ -22,25 +28,45 @@ use serde::Serialize;
 //================
 const SEEN_EN_EXT: &str = "seen";
 const SEEN_AR_EXT: &str = "س";
+const SEEN_FA_EXT: &str = "سین";
 
 //================
 //  Lang
 //================
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub enum Lang {
     Ar,
-    En
+    En,
+    Fa
 }
 
 impl Lang {
+    //---------------------
+    //  all()
+    //---------------------    
+    pub fn all() -> Vec<Lang> {
+        vec![Lang::Ar, Lang::En, Lang::Fa]
+    }
+
     //---------------------
     //  from_str()
     //---------------------    
     pub fn from_str(v: &str) -> Lang {
+        match Self::try_from_str(v) {
+            Some(lang) => lang,
+            None => panic!("invalid language string!")
+        }
+    }
+
+    //---------------------
+    //  try_from_str()
+    //---------------------    
+    pub fn try_from_str(v: &str) -> Option<Lang> {
         match v {
-            "ar" => Lang::Ar,
-            "en" => Lang::En,
-            _ => panic!("invalid language string!")
+            "ar" => Some(Lang::Ar),
+            "en" => Some(Lang::En),
+            "fa" => Some(Lang::Fa),
+            _ => None
         }
     }
 
@@ -52,6 +78,7 @@ impl Lang {
         match &self {
             Lang::Ar => "ar" ,
             Lang::En => "en",
+            Lang::Fa => "fa",
         }
     }
 
@@ -61,7 +88,42 @@ impl Lang {
     pub fn ext(&self) -> &'static str {
         match self {
             Lang::Ar => SEEN_AR_EXT,
-            Lang::En => SEEN_EN_EXT
+            Lang::En => SEEN_EN_EXT,
+            Lang::Fa => SEEN_FA_EXT
+        }
+    }
+
+    //---------------------
+    //  is_rtl()
+    //---------------------    
+    pub fn is_rtl(&self) -> bool {
+        match self {
+            Lang::Ar | Lang::Fa => true,
+            Lang::En => false
+        }
+    }
+
+    //---------------------
+    //  native_digits()
+    //---------------------    
+    // the first and last digits of the language's own numerals, western digits are accepted in any language
+    pub fn native_digits(&self) -> Option<(char, char)> {
+        match self {
+            Lang::Ar => Some(('٠', '٩')),
+            Lang::Fa => Some(('۰', '۹')),
+            Lang::En => None
+        }
+    }
+
+    //---------------------
+    //  decimal_sep()
+    //---------------------    
+    // separates the fraction when writing native digits
+    pub fn decimal_sep(&self) -> char {
+        match self {
+            Lang::Ar => ',',
+            Lang::Fa => '٫',
+            Lang::En => '.'
         }
     }
 
@@ -69,15 +131,22 @@ impl Lang {
     //  lang_from_ext()
     //---------------------    
     pub fn lang_from_ext(path: &str) -> Self {
-        if path.ends_with(SEEN_EN_EXT) {
-            Self::En
-        } else if path.ends_with(SEEN_AR_EXT) {
-            Self::Ar
-        } else {
-            panic!("unknown seen file format!");
+        match Self::try_from_ext(path) {
+            Some(lang) => lang,
+            None => panic!("unknown seen file format!")
         }
     }
 
+    //---------------------
+    //  try_from_ext()
+    //---------------------    
+    pub fn try_from_ext(path: &str) -> Option<Self> {
+        let ext = std::path::Path::new(path).extension()?.to_str()?;
+        Self::all()
+            .into_iter()
+            .find(|lang| lang.ext() == ext)
+    }
+
     //---------------------
     //  is_ar_ext()
     //---------------------        
@@ -98,6 +167,7 @@ impl fmt::Display for Lang {
         match self {
             Self::Ar => write!(f, "ar"), 
             Self::En => write!(f, "en"), 
+            Self::Fa => write!(f, "fa"), 
         }
     }
 }
@@ -0,0 +1,347 @@
+use super::{
+    Lang,
+    syntax_tree::ast::*,
+    error::{
+        Error,
+    },
+};
+
+use crate::{
+    error,
+    transl::lexicon::Lexicon,
+    target::rust::rs_gen::Rust
+};
+
+//================
+//   AttrHandler
+//================
+// what the compiler does with one attribute, a handler is found through the lexicon concept it
+//  names so `@derive`, `@اشتق` and `@مشتق` all reach the same handler
+pub trait AttrHandler: Send + Sync {
+    //---------------------
+    //  concept()
+    //---------------------
+    // e.g. `attr.derive`
+    fn concept(&self) -> &'static str;
+
+    //---------------------
+    //  apply()
+    //---------------------
+    // checks the arguments and the element the attribute is written on, and can rewrite that element
+    fn apply(
+        &self,
+        attr: &Attr,
+        el: &mut ModElement,
+        lexicon: &Lexicon
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    //---------------------
+    //  rs_attrs()
+    //---------------------
+    // the rust attributes written above the generated item, e.g. `#[derive(PartialEq)]`
+    fn rs_attrs(
+        &self,
+        attr: &Attr,
+        lexicon: &Lexicon
+    ) -> Vec<String> {
+        vec![]
+    }
+
+    //---------------------
+    //  rs_fn()
+    //---------------------
+    // writes the whole rust item of the function the attribute is written on, in place of the
+    //  generator, returns whether it did
+    fn rs_fn(
+        &self,
+        attr: &Attr,
+        _fn: &Fn,
+        rs: &mut Rust
+    ) -> bool {
+        false
+    }
+}
+
+//================
+//   AttrRegistry
+//================
+pub struct AttrRegistry {
+    handlers: Vec<Box<dyn AttrHandler>>
+}
+
+impl AttrRegistry {
+    //---------------------
+    //  new()
+    //---------------------
+    pub fn new() -> Self {
+        Self {
+            handlers: vec![]
+        }
+    }
+
+    //---------------------
+    //  builtin()
+    //---------------------
+    // the handlers seen ships with, more can be registered on it before it is given to the compiler
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(WebServer));
+        registry.register(Box::new(Derive));
+        registry.register(Box::new(Test));
+        registry.register(Box::new(ReceiverAttr{ concept: "attr.ref", receiver: Receiver::Ref }));
+        registry.register(Box::new(ReceiverAttr{ concept: "attr.mut_ref", receiver: Receiver::MutRef }));
+        registry.register(Box::new(ReceiverAttr{ concept: "attr.own", receiver: Receiver::Owned }));
+        registry
+    }
+
+    //---------------------
+    //  register()
+    //---------------------
+    // replaces the handler already registered for the same concept
+    pub fn register(&mut self, handler: Box<dyn AttrHandler>) {
+        self.handlers.retain(|h| h.concept() != handler.concept());
+        self.handlers.push(handler);
+    }
+
+    //---------------------
+    //  handler()
+    //---------------------
+    pub fn handler(
+        &self,
+        attr: &Attr,
+        lexicon: &Lexicon
+    ) -> Option<&dyn AttrHandler> {
+        // an attribute that is not in the lexicon is found by its own name, e.g. `@route` by `attr.route`
+        let name = attr.name().value.to_string();
+        let concept = match lexicon.concept_any("attr", &name) {
+            Some(concept) => concept.to_string(),
+            None => format!("attr.{}", name)
+        };
+        self.handlers
+            .iter()
+            .find(|h| h.concept() == concept)
+            .map(|h| h.as_ref())
+    }
+
+    //---------------------
+    //  apply()
+    //---------------------
+    // runs the handler of every attribute in the module, an attribute without a handler is an error
+    pub fn apply(
+        &self,
+        ast: &mut Vec<ModElement>,
+        lexicon: &Lexicon
+    ) -> Vec<Error> {
+        let mut errors = vec![];
+        for el in ast.iter_mut() {
+            let attrs = match el {
+                ModElement::MainFn(_fn)
+                | ModElement::Fn(_fn) => _fn.attrs.clone().unwrap_or(vec![]),
+                ModElement::Struct(_struct) => _struct.attrs.clone(),
+                ModElement::StructImpl(struct_impl) => struct_impl.fns
+                    .iter()
+                    .flat_map(|_fn| _fn.attrs.clone().unwrap_or(vec![]))
+                    .collect(),
+                _ => continue
+            };
+            for attr in &attrs {
+                match self.handler(attr, lexicon) {
+                    None => errors.push(
+                        error!(format!("unknown attribute `@{}`", attr.name().value), attr.name())
+                    ),
+                    Some(handler) => {
+                        if let Err(err) = handler.apply(attr, el, lexicon) {
+                            errors.push(err);
+                        }
+                    }
+                }
+            }
+        }
+        errors
+    }
+}
+
+//================
+//   WebServer
+//================
+// `@web_server` on the main function, the rust generator serves the returned data
+struct WebServer;
+
+impl AttrHandler for WebServer {
+    fn concept(&self) -> &'static str { "attr.web_server" }
+
+    fn apply(
+        &self,
+        attr: &Attr,
+        el: &mut ModElement,
+        lexicon: &Lexicon
+    ) -> Result<(), Error> {
+        no_args(attr)?;
+        match el {
+            ModElement::MainFn(_) => Ok(()),
+            _ => Err(error!(format!("`@{}` can only be written on the main function", attr.name().value), attr.name()))
+        }
+    }
+
+    // the main function returns the data of the site, it is served instead of running the body
+    fn rs_fn(
+        &self,
+        attr: &Attr,
+        _fn: &Fn,
+        rs: &mut Rust
+    ) -> bool {
+        let data = match _fn.block.last() {
+            Some(BlockElement::Ret(Ret{expr: Some(expr), ..})) => &**expr,
+            Some(BlockElement::Expr(expr)) => expr,
+            _ => todo!() // TODO
+        };
+        match data {
+            Expr::StructLiteral(data) => {
+                rs.web_server(data);
+                true
+            },
+            x => todo!("{:?}", x)   // FIXME
+        }
+    }
+}
+
+//================
+//   Derive
+//================
+// `@derive(Eq, Default)`, the traits can be named in the source language
+struct Derive;
+
+impl AttrHandler for Derive {
+    fn concept(&self) -> &'static str { "attr.derive" }
+
+    fn apply(
+        &self,
+        attr: &Attr,
+        el: &mut ModElement,
+        lexicon: &Lexicon
+    ) -> Result<(), Error> {
+        let _struct = match el {
+            ModElement::Struct(_struct) => _struct,
+            _ => return Err(error!(format!("`@{}` can only be written on a struct", attr.name().value), attr.name()))
+        };
+        // field defaults are written as an `impl Default`, see Rust::default_impl()
+        let has_defaults = _struct.fields
+            .as_ref()
+            .is_some_and(|fields| fields.iter().any(|f| f.default.is_some()));
+        for arg in attr.args() {
+            match &arg.expr {
+                Expr::Ref(t) if arg.name.is_none() => {
+                    let concept = lexicon.concept_any("trait", &t.value.to_string());
+                    if has_defaults && concept == Some("trait.default") {
+                        return Err(error!(format!("`{}` can not be derived for a struct with field defaults", t.value), t.clone()))
+                    }
+                },
+                _ => return Err(error!(format!("`@{}` expects the names of traits", attr.name().value), attr.name()))
+            }
+        }
+        Ok(())
+    }
+
+    fn rs_attrs(
+        &self,
+        attr: &Attr,
+        lexicon: &Lexicon
+    ) -> Vec<String> {
+        let traits = attr.args()
+            .iter()
+            .filter_map(|arg| match &arg.expr {
+                Expr::Ref(t) => {
+                    let name = t.value.to_string();
+                    match lexicon.concept_any("trait", &name) {
+                        Some(concept) => Some(lexicon.word(&Lang::En, concept)),
+                        None => Some(name)
+                    }
+                },
+                _ => None
+            })
+            // FIXME: every struct derives these two for now, see Rust::_struct()
+            .filter(|name| name != "Debug" && name != "Clone")
+            .collect::<Vec<_>>();
+
+        if traits.is_empty() {
+            vec![]
+        } else {
+            vec![format!("#[derive({})]", traits.join(", "))]
+        }
+    }
+}
+
+//================
+//   Test
+//================
+// `@test` on a function without params, run by `cargo test`
+struct Test;
+
+impl AttrHandler for Test {
+    fn concept(&self) -> &'static str { "attr.test" }
+
+    fn apply(
+        &self,
+        attr: &Attr,
+        el: &mut ModElement,
+        lexicon: &Lexicon
+    ) -> Result<(), Error> {
+        no_args(attr)?;
+        match el {
+            ModElement::Fn(_fn) if _fn.params.is_empty() => Ok(()),
+            _ => Err(error!(format!("`@{}` can only be written on a function without params", attr.name().value), attr.name()))
+        }
+    }
+
+    fn rs_attrs(
+        &self,
+        attr: &Attr,
+        lexicon: &Lexicon
+    ) -> Vec<String> {
+        vec!["#[test]".to_string()]
+    }
+}
+
+//================
+//   ReceiverAttr
+//================
+// `@ref`, `@mut_ref` and `@own` on a method, how it takes `self` instead of what the mutability pass infers
+struct ReceiverAttr {
+    concept: &'static str,
+    receiver: Receiver
+}
+
+impl AttrHandler for ReceiverAttr {
+    fn concept(&self) -> &'static str { self.concept }
+
+    fn apply(
+        &self,
+        attr: &Attr,
+        el: &mut ModElement,
+        lexicon: &Lexicon
+    ) -> Result<(), Error> {
+        no_args(attr)?;
+        match el {
+            ModElement::StructImpl(struct_impl) if struct_impl.fns.iter().all(|f| f.is_method) => {
+                for _fn in struct_impl.fns.iter_mut() {
+                    _fn.receiver = Some(self.receiver);
+                }
+                Ok(())
+            },
+            _ => Err(error!(format!("`@{}` can only be written on a method", attr.name().value), attr.name()))
+        }
+    }
+}
+
+//================
+//   no_args()
+//================
+fn no_args(attr: &Attr) -> Result<(), Error> {
+    if attr.args().is_empty() {
+        Ok(())
+    } else {
+        Err(error!(format!("`@{}` takes no arguments", attr.name().value), attr.name()))
+    }
+}
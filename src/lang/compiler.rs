@@ -2,6 +2,7 @@ use std::{
     io,
     collections::HashMap, 
     path::{
+        Path,
         PathBuf, 
     }, 
 };   
@@ -11,11 +12,18 @@ use crate::{
         build::BuildDir,
         rust::{
             cargo_toml::CargoToml,
-            rs_gen::Rust
+            rs_gen::{
+                Rust,
+                ModStructs
+            }
         }, 
 
     }, 
-    transl::transl::Transl, project::conf::Conf
+    transl::{
+        transl::Transl,
+        lexicon::Lexicon
+    }, 
+    project::conf::Conf
 };
 
 use crate::debug::lang::compiler::*;
@@ -34,11 +42,25 @@ use super::{
     symtab::SymTab,
     parser::Parser,
     resolver::{
+        self,
         ResTab,
-        Resolver
+        Resolver,
+        Exports
     },
     inference::Inference,
     type_checker::TypeChecker,
+    exhaustiveness::Exhaustiveness,
+    mutability::Mutability,
+    ownership::Ownership,
+    hir::{
+        Hir,
+        HirLowering
+    },
+    passes::{
+        PassOptions,
+        Pipeline
+    },
+    attrs::AttrRegistry,
     error::{
         Error
     }
@@ -60,7 +82,9 @@ pub struct Data {
     pub ast : Option<Vec<ModElement>>,
     pub symtab: Option<SymTab>,
     pub restab: Option<ResTab>,
+    pub hir: Option<Hir>,
     pub errors: Vec<Error>,
+    pub warnings: Vec<Error>,
 
 }
 
@@ -79,7 +103,9 @@ impl Data {
             ast: None,
             symtab: None,
             restab: None,
-            errors: vec![]
+            hir: None,
+            errors: vec![],
+            warnings: vec![]
         }
     }
 }
@@ -94,8 +120,8 @@ pub fn to_ast(path: String) -> Vec<ModElement> {
     let lang = Lang::lang_from_ext(&path);
     let data = Data::new(lang, script);
     modules.insert(path.clone(), data );    
-    let modules = scan(modules);
-    let mut modules = parse(modules);    
+    let modules = scan(modules, false, Lexicon::embedded());
+    let mut modules = parse(modules, Lexicon::embedded());    
     for  data in modules.values_mut() {
         return data.ast.take().unwrap()
     }
@@ -115,20 +141,45 @@ pub fn compile(
     // proj_dir: &ProjDir,
     out_dir: Option<String>,
     paths: Vec<String>,  // FIXME, switch to PathBuf
-    main_mods: Vec<String>
+    main_mods: Vec<String>,
+    attrs: &AttrRegistry,
+    pass_options: &PassOptions
 ) -> Result<(), io::Error> {
 
+    let seen_conf = Conf::new(home);
+
 	let mut modules = HashMap::from([]);
 	for path in paths {
+        let lang = match Lang::try_from_ext(&path) {
+            Some(lang) => lang,
+            None => return Err(io::Error::new(io::ErrorKind::Other, format!("unknown seen file format: {}", path)))
+        };
         let script = Script::from_file(&path);
-        let lang = Lang::lang_from_ext(&path);
 
         let data = Data::new(lang, script);
 		modules.insert(path.clone(), data );
 	}
 
-    let mut modules = scan(modules);
-    let mut modules = parse(modules);
+    let modules = scan(modules, seen_conf.allow_bidi(), &seen_conf.lexicon);
+    abort_on_errors(&modules)?;
+    let modules = parse(modules, &seen_conf.lexicon);
+    abort_on_errors(&modules)?;
+    let modules = apply_attrs(modules, attrs, &seen_conf.lexicon);
+    abort_on_errors(&modules)?;
+    let modules = check_loops(modules);
+    abort_on_errors(&modules)?;
+    let modules = check_visibility(modules, &seen_conf.lexicon);
+    abort_on_errors(&modules)?;
+    let modules = check_matches(modules, &seen_conf.lexicon);
+    abort_on_errors(&modules)?;
+    let modules = check_literals(modules, &seen_conf.lexicon);
+    abort_on_errors(&modules)?;
+    let modules = infer_mutability(modules, &seen_conf.lexicon);
+    abort_on_errors(&modules)?;
+    let modules = lower_ownership(modules, &seen_conf.lexicon);
+    abort_on_errors(&modules)?;
+    let modules = lower_hir(modules, &seen_conf.lexicon);
+    let mut modules = run_passes(modules, pass_options, &seen_conf.lexicon)?;
     // let modules = resolve(modules);  // FIXME, turned off, not planned for first release
     // let modules = type_infer(modules); 
     // let modules = type_check(modules); // FIXME, turned off, not planned for first release
@@ -137,19 +188,34 @@ pub fn compile(
     } else {
         None
     };
-    generate(&lang, &transl, &home, &proj_name, out_dir, &mut modules, &main_mods);
+    generate(&lang, &transl, &seen_conf, attrs, &proj_name, out_dir, &mut modules, &main_mods);
 
     Ok(())
 }
 
+//================
+//  abort_on_errors()
+//================
+fn abort_on_errors(modules: &Modules) -> Result<(), io::Error> {
+    if modules.values().any(|data| !data.errors.is_empty()) {
+        Err(io::Error::new(io::ErrorKind::Other, "aborting due to previous errors"))
+    } else {
+        Ok(())
+    }
+}
+
 //================
 //  scan()
 //================
 fn scan (
     mut modules: Modules,
+    allow_bidi: bool,
+    lexicon: &Lexicon
 ) -> Modules {
 
     let mut lexer = Lexer::new();    
+    lexer.allow_bidi(allow_bidi);
+    lexer.lexicon(lexicon);
 
     for data in modules.values_mut() {
         let (tokens, errors)  = lexer.tokens(&data.lang, &data.script);
@@ -169,15 +235,18 @@ fn scan (
 //================
 fn parse (
     mut modules: Modules,
+    lexicon: &Lexicon
 ) -> Modules {
     let mut parser = Parser::new();
+    parser.lexicon(lexicon);
 
     for data in modules.values_mut() {
+        parser.lang(&data.lang);
         let tokens = data.tokens.as_mut().unwrap();
         let (ast, symtab, errors) = parser.parse(tokens);
         data.ast = Some(ast);
         data.symtab = Some(symtab);
-        data.errors = errors;
+        data.errors.extend(errors);
     }
 
     log::debug!("\n{}", debug_ast(&modules));    
@@ -252,13 +321,217 @@ fn type_check (
     modules
 }
 
+//================
+//  check_loops()
+//================
+fn check_loops (
+    mut modules: Modules,
+) -> Modules {
+    let mut resolver = Resolver::new();
+
+    for data in modules.values_mut() {
+        let ast = data.ast.as_ref().unwrap();
+        let errors = resolver.check_loops(ast);
+        data.errors.extend(errors);
+    }
+
+    print_errors(&modules);
+
+    modules
+}
+
+//================
+//  check_visibility()
+//================
+// an imported module is found by the path given to `import()`, relative to the importing module
+fn check_visibility (
+    mut modules: Modules,
+    lexicon: &Lexicon
+) -> Modules {
+    let mut resolver = Resolver::new();
+
+    let mod_exports: HashMap<ScriptPath, Exports> = modules
+        .iter()
+        .map(|(path, data)| (path.clone(), resolver::exports(data.ast.as_ref().unwrap())))
+        .collect();
+
+    for (path, data) in modules.iter_mut() {
+        let ast = data.ast.as_ref().unwrap();
+        let mod_imports = resolver::imports(ast, &data.lang, lexicon)
+            .into_iter()
+            .filter_map(|(name, file)| {
+                let file = Path::new(path).with_file_name(file);
+                mod_exports
+                    .iter()
+                    .find(|(p, _)| Path::new(p) == file)
+                    .map(|(_, exports)| (name, exports))
+            })
+            .collect::<HashMap<_, _>>();
+        let errors = resolver.check_visibility(ast, &mod_imports);
+        data.errors.extend(errors);
+    }
+
+    print_errors(&modules);
+
+    modules
+}
+
+//================
+//  apply_attrs()
+//================
+fn apply_attrs (
+    mut modules: Modules,
+    attrs: &AttrRegistry,
+    lexicon: &Lexicon
+) -> Modules {
+    for data in modules.values_mut() {
+        let ast = data.ast.as_mut().unwrap();
+        let errors = attrs.apply(ast, lexicon);
+        data.errors.extend(errors);
+    }
+
+    print_errors(&modules);
+
+    modules
+}
+
+//================
+//  check_matches()
+//================
+fn check_matches (
+    mut modules: Modules,
+    lexicon: &Lexicon
+) -> Modules {
+    let mut exhaustiveness = Exhaustiveness::new();
+    exhaustiveness.lexicon(lexicon);
+
+    for data in modules.values_mut() {
+        let ast = data.ast.as_ref().unwrap();
+        let errors = exhaustiveness.check(ast);
+        data.errors.extend(errors);
+    }
+
+    print_errors(&modules);
+
+    modules
+}
+
+//================
+//  check_literals()
+//================
+fn check_literals (
+    mut modules: Modules,
+    lexicon: &Lexicon
+) -> Modules {
+    let mut type_checker = TypeChecker::new();
+    type_checker.lexicon(lexicon);
+
+    for data in modules.values_mut() {
+        let ast = data.ast.as_ref().unwrap();
+        let errors = type_checker.check_literals(ast);
+        data.errors.extend(errors);
+    }
+
+    print_errors(&modules);
+
+    modules
+}
+
+//================
+//  infer_mutability()
+//================
+fn infer_mutability (
+    mut modules: Modules,
+    lexicon: &Lexicon
+) -> Modules {
+    let mut mutability = Mutability::new();
+    mutability.lexicon(lexicon);
+
+    for data in modules.values_mut() {
+        let ast = data.ast.as_mut().unwrap();
+        let errors = mutability.check(ast);
+        data.errors.extend(errors);
+    }
+
+    print_errors(&modules);
+
+    modules
+}
+
+//================
+//  lower_ownership()
+//================
+fn lower_ownership (
+    mut modules: Modules,
+    lexicon: &Lexicon
+) -> Modules {
+    let mut ownership = Ownership::new();
+    ownership.lexicon(lexicon);
+
+    for data in modules.values_mut() {
+        let ast = data.ast.as_mut().unwrap();
+        let errors = ownership.lower(ast);
+        data.errors.extend(errors);
+    }
+
+    print_errors(&modules);
+
+    modules
+}
+
+//================
+//  lower_hir()
+//================
+// the last stage before the backends, it can not fail
+fn lower_hir (
+    mut modules: Modules,
+    lexicon: &Lexicon
+) -> Modules {
+    let mut lowering = HirLowering::new();
+    lowering.lexicon(lexicon);
+
+    for (path, data) in modules.iter_mut() {
+        let ast = data.ast.take().unwrap();
+        data.hir = Some(lowering.lower(path, &data.lang, ast));
+    }
+
+    modules
+}
+
+//================
+//  run_passes()
+//================
+// the middle-end, only warns so it never stops the compilation, an unknown pass name does
+fn run_passes (
+    mut modules: Modules,
+    options: &PassOptions,
+    lexicon: &Lexicon
+) -> Result<Modules, io::Error> {
+    let mut pipeline = Pipeline::new(options.clone());
+    pipeline.lexicon(lexicon);
+    if let Err(msg) = pipeline.check_options() {
+        return Err(io::Error::new(io::ErrorKind::Other, msg))
+    }
+
+    for (path, data) in modules.iter_mut() {
+        let hir = data.hir.as_mut().unwrap();
+        let warnings = pipeline.run(path, hir);
+        data.warnings.extend(warnings);
+    }
+
+    print_warnings(&modules);
+
+    Ok(modules)
+}
+
 //================
 //  generate()
 //================
 fn generate (
     lang: &Lang,
     transl: &Transl,
-    home: &PathBuf,
+    seen_conf: &Conf,
+    attrs: &AttrRegistry,
     name: &String,
     out_dir: Option<PathBuf>,
     modules: &mut Modules,
@@ -277,30 +550,26 @@ fn generate (
     // let seen_conf_path = format!("{}", seen_conf_path.display());
     // let seen_conf_ast = to_ast(seen_conf_path.clone()); // modules.get(&seen_conf_path).expect(format!("seen.conf is missing: looking for`{}`, available files: `{:?}`", seen_conf_path, modules.keys()).as_str());
 
-    let seen_conf = Conf::new(home);
-
     let mut cargo_toml = CargoToml::new(
         &build_dir.name, 
         &build_dir.home,
-        &seen_conf   
+        seen_conf   
     );
 
+    let mod_structs = mod_structs(modules);
+
     for (path,module) in modules {
-        let file_name = std::path::PathBuf::from(path.clone());
-        let file_name = file_name.file_stem().expect("expect file stem");
-        let file_name = format!("{}", file_name.to_str().expect("expect str"));
-        
-        let ast= module.ast.as_mut().unwrap();
+        let hir = module.hir.as_ref().unwrap();
 
         let path = Rust::new(
             &mut build_dir, 
-            &mut cargo_toml
+            &mut cargo_toml,
+            &seen_conf.lexicon,
+            attrs,
+            &mod_structs
         ).generate(
-            file_name,
             path, 
-            // module.lang.ext(), 
-            &module.lang,
-            ast,
+            hir,
             &main_mods
         );
 
@@ -310,4 +579,31 @@ fn generate (
     
 }
 
+//================
+//  mod_structs()
+//================
+fn mod_structs(modules: &Modules) -> ModStructs {
+    modules
+        .iter()
+        .map(|(path, module)| {
+            let structs = module.hir
+                .iter()
+                .flat_map(|hir| hir.items.iter())
+                .filter_map(|el| match el {
+                    ModElement::Struct(_struct) => Some((_struct.name.to_string(), _struct.fields.clone().unwrap_or(vec![]))),
+                    _ => None
+                })
+                .collect();
+            (file_stem(path), structs)
+        })
+        .collect()
+}
 
+//================
+//  file_stem()
+//================
+fn file_stem(path: &String) -> String {
+    let file_name = std::path::PathBuf::from(path.clone());
+    let file_name = file_name.file_stem().expect("expect file stem");
+    format!("{}", file_name.to_str().expect("expect str"))
+}
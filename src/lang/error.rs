@@ -30,6 +30,20 @@ macro_rules! error {
     };
 }
 
+//======================
+//  warning!()
+//======================
+#[macro_export]
+macro_rules! warning {
+    ($msg:expr, $t:expr) => {
+        Error::warning(      
+            $t.location.clone(),
+            $t.location.clone(),
+            $msg
+        )
+    };
+}
+
 //================
 //   Error
 //================
@@ -38,6 +52,7 @@ pub struct Error {
     start_location: Location,
     end_location: Location,
     message: String,
+    is_warning: bool            // reported, but the compilation goes on
 }
 
 impl Error {
@@ -53,8 +68,30 @@ impl Error {
             start_location: location,
             end_location: end_location,
             message: msg,
+            is_warning: false
         }
     }
+
+    //---------------------
+    //  warning()
+    //---------------------
+    pub fn warning(
+        location: Location, 
+        end_location: Location, 
+        msg: String,
+    ) -> Self {
+        Error {
+            is_warning: true,
+            ..Self::new(location, end_location, msg)
+        }
+    }
+
+    //---------------------
+    //  is_warning()
+    //---------------------
+    pub fn is_warning(&self) -> bool {
+        self.is_warning
+    }
 }
 
 //================
@@ -63,7 +100,7 @@ impl Error {
 //================
 impl fmt::Display for Error {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(fmt, "error: {}", self.message)
+        writeln!(fmt, "{}: {}", severity(self), self.message)
     }
 }
 
@@ -74,9 +111,21 @@ impl fmt::Display for Error {
 pub fn print_errors(
     errors: &Vec<Error>,
     script: &Script
+) {
+    print_errors_labeled(errors, script, |error| severity(error).to_string());
+}
+
+//================
+//  print_errors_labeled()
+//================
+// `label` names the severity of each error, e.g. in the language of the project
+pub fn print_errors_labeled(
+    errors: &Vec<Error>,
+    script: &Script,
+    label: impl Fn(&Error) -> String
 ) {
     for error in errors {
-        eprint!("error: {}", error.message);
+        eprint!("{}: {}", label(error), error.message);
         eprint!("{}", INDENT_ERROR);
         eprint!("{}:", script.path);
         eprint!("{}", error.start_location);       
@@ -86,6 +135,13 @@ pub fn print_errors(
 }
 
 
+//================
+//  severity()
+//================
+fn severity(error: &Error) -> &'static str {
+    if error.is_warning { "warning" } else { "error" }
+}
+
 //================
 //  snippet()
 //================
@@ -0,0 +1,794 @@
+use std::collections::{
+    HashMap,
+    BTreeSet
+};
+
+use super::{
+    token::Token,
+    syntax_tree::ast::*,
+    error::{
+        Error,
+    },
+};
+
+use crate::{
+    error,
+    transl::lexicon::Lexicon,
+    util::ar::to_western_num
+};
+
+//================
+//   Pat
+//================
+// a pattern reduced to what the analysis needs, bindings are wildcards
+#[derive(Clone, Debug)]
+enum Pat {
+    Wild,
+    Ctor(Ctor, Vec<Pat>),
+    Or(Vec<Pat>)
+}
+
+//================
+//   Ctor
+//================
+#[derive(Clone, Debug, PartialEq)]
+enum Ctor {
+    Bool(bool),
+    Variant(String, usize),     // name, number of values, Some/None/Ok/Err included
+    Tuple(usize),
+    List(usize, bool),          // length, `true` if the list can be longer: `[a, b, ..]`
+    Int(i128, i128),            // an inclusive range, a literal is a range of one value
+    Lit(String),                // str, char and float literals
+    Opaque(usize),              // a pattern the analysis does not look into, it only covers itself
+}
+
+impl Ctor {
+    //---------------------
+    //  arity()
+    //---------------------
+    fn arity(&self) -> usize {
+        match self {
+            Self::Variant(_, arity)
+            | Self::Tuple(arity)
+            | Self::List(arity, _) => *arity,
+            _ => 0
+        }
+    }
+
+    //---------------------
+    //  covers()
+    //---------------------
+    // whether every value matched by `other` is matched by `self`, `other` is always
+    //  one of the constructors produced by split()
+    fn covers(&self, other: &Ctor) -> bool {
+        match (self, other) {
+            (Self::Variant(a, _), Self::Variant(b, _)) => a == b,
+            (Self::Int(lo, hi), Self::Int(x, y)) => lo <= x && y <= hi,
+            (Self::List(len, false), Self::List(n, rest)) => !rest && len == n,
+            (Self::List(len, true), Self::List(n, _)) => n >= len,
+            (a, b) => a == b
+        }
+    }
+}
+
+//================
+//   Domain
+//================
+// the values a column of patterns can take, guessed from its constructors since the types
+//  are not known yet
+enum Domain {
+    Bool,
+    Variants(Vec<(String, usize)>),
+    Tuple(usize),
+    List,
+    Int,
+    Infinite,
+    Unknown
+}
+
+//================
+//   Exhaustiveness
+//================
+// reports the cases a `match` does not cover and the arms that can never be reached,
+//  following "Warnings for pattern matching" (Maranget, 2007)
+pub struct Exhaustiveness<'a> {
+    lexicon: &'a Lexicon,
+    enums: HashMap<String, Vec<String>>,
+    opaque_count: usize,
+    unknown: bool,
+}
+
+impl<'a> Exhaustiveness<'a> {
+    //---------------------
+    //  new()
+    //---------------------
+    pub fn new() -> Self {
+        Self {
+            lexicon: Lexicon::embedded(),
+            enums: HashMap::new(),
+            opaque_count: 0,
+            unknown: false
+        }
+    }
+
+    //---------------------
+    //  lexicon()
+    //---------------------
+    pub fn lexicon(&mut self, lexicon: &'a Lexicon) {
+        self.lexicon = lexicon;
+    }
+
+    //---------------------
+    //  check()
+    //---------------------
+    pub fn check(
+        &mut self,
+        ast: &Vec<ModElement>,
+    ) -> Vec<Error> {
+        self.enums = ast.iter()
+            .filter_map(|el| match el {
+                ModElement::Enum(_enum) => Some((
+                    _enum.name.value.to_string(),
+                    _enum.variants
+                        .iter()
+                        .map(|v| v.name.value.to_string())
+                        .collect()
+                )),
+                _ => None
+            })
+            .collect();
+
+        let mut errors = vec![];
+        for el in ast {
+            match el {
+                ModElement::Decl(decl) => self.decl(decl, &mut errors),
+                ModElement::MainFn(_fn)
+                | ModElement::Fn(_fn) => self._fn(_fn, &mut errors),
+                ModElement::StructImpl(struct_impl) => {
+                    for _fn in &struct_impl.fns {
+                        self._fn(_fn, &mut errors);
+                    }
+                },
+                _ => ()
+            }
+        }
+        errors
+    }
+}
+
+//================
+//   _fn()
+//================
+impl<'a> Exhaustiveness<'a> {
+    fn _fn(
+        &mut self,
+        _fn: &Fn,
+        errors: &mut Vec<Error>
+    ) {
+        for decl in &_fn.where_decls {
+            self.decl(decl, errors);
+        }
+        self.block(&_fn.block, errors);
+    }
+}
+
+//================
+//   block()
+//================
+impl<'a> Exhaustiveness<'a> {
+    fn block(
+        &mut self,
+        block: &Vec<BlockElement>,
+        errors: &mut Vec<Error>
+    ) {
+        for el in block {
+            match el {
+                BlockElement::Decl(decl) => self.decl(decl, errors),
+                BlockElement::Expr(expr) => self.expr(expr, errors),
+                BlockElement::Ret(Ret{expr: Some(expr), ..}) => self.expr(expr, errors),
+                BlockElement::Ret(_)
+                | BlockElement::MainArgs => ()
+            }
+        }
+    }
+}
+
+//================
+//   decl()
+//================
+impl<'a> Exhaustiveness<'a> {
+    fn decl(
+        &mut self,
+        decl: &Decl,
+        errors: &mut Vec<Error>
+    ) {
+        if let Some(expr) = &decl.expr {
+            self.expr(expr, errors);
+        }
+    }
+}
+
+//================
+//   expr()
+//================
+// looks for the matches nested in expressions
+impl<'a> Exhaustiveness<'a> {
+    fn expr(
+        &mut self,
+        expr: &Expr,
+        errors: &mut Vec<Error>
+    ) {
+        match expr {
+            Expr::Match(_match) => {
+                self.expr(&_match.expr, errors);
+                for arm in &_match.arms {
+                    self.block(&arm.block, errors);
+                }
+                self._match(_match, errors);
+            },
+            Expr::Fn(_fn) => self._fn(_fn, errors),
+            Expr::LetIn(let_in) => {
+                self.decl(&let_in.decl, errors);
+                self.expr(&let_in.expr, errors);
+            },
+            Expr::For(_for) => self.block(&_for.block, errors),
+            Expr::While(_while) => self.block(&_while.block, errors),
+            Expr::Loop(_loop) => self.block(&_loop.block, errors),
+            Expr::If(_if) => {
+                for branch in &_if.branches {
+                    self.block(&branch.block, errors);
+                }
+            },
+            Expr::BinOp(bin_op) => {
+                self.expr(&bin_op.l_opr, errors);
+                self.expr(&bin_op.r_opr, errors);
+            },
+            Expr::List(List{items})
+            | Expr::Tuple(Tuple{items}) => {
+                for item in items {
+                    self.expr(item, errors);
+                }
+            },
+            Expr::Ok(expr)
+            | Expr::Err(expr)
+            | Expr::Some(expr) => self.expr(expr, errors),
+            _ => ()
+        }
+    }
+}
+
+//================
+//   _match()
+//================
+impl<'a> Exhaustiveness<'a> {
+    fn _match(
+        &mut self,
+        _match: &Match,
+        errors: &mut Vec<Error>
+    ) {
+        self.unknown = false;
+
+        // a guarded arm can be reached but does not cover its pattern
+        let mut rows = vec![];
+        for arm in &_match.arms {
+            let row = vec![self.pat(&arm.pattern)];
+            if self.useful(&rows, &row).is_none() {
+                errors.push(
+                    error!(
+                        "unreachable arm, the arms before it already match every value it matches".to_string(),
+                        &arm.token
+                    )
+                );
+            }
+            if arm.guard.is_none() {
+                rows.push(row);
+            }
+        }
+
+        if let Some(witness) = self.useful(&rows, &vec![Pat::Wild]) {
+            // an enum that is not declared in the module, nothing is known about its other variants
+            if self.unknown { return }
+            let missing = witness.iter()
+                .map(|p| display(p))
+                .collect::<Vec<_>>()
+                .join(", ");
+            errors.push(
+                error!(
+                    format!("non-exhaustive match, `{}` is not covered, add an arm for it or a `_` arm", missing),
+                    &_match.token
+                )
+            );
+        }
+    }
+}
+
+//================
+//   pat()
+//================
+impl<'a> Exhaustiveness<'a> {
+    fn pat(
+        &mut self,
+        pattern: &Pattern,
+    ) -> Pat {
+        match pattern {
+            Pattern::Id(_)
+            | Pattern::Wildcard
+            | Pattern::Rest => Pat::Wild,
+            Pattern::Binding(binding) => self.pat(&binding.pattern),
+            Pattern::Or(patterns) => Pat::Or(
+                patterns.iter().map(|p| self.pat(p)).collect()
+            ),
+            Pattern::PrimitiveLiteral(expr) => Pat::Ctor(self.literal(expr), vec![]),
+            Pattern::Range(range) => Pat::Ctor(self.range(range), vec![]),
+            Pattern::Tuple(tuple) => Pat::Ctor(
+                Ctor::Tuple(tuple.items.len()),
+                tuple.items.iter().map(|p| self.pat(p)).collect()
+            ),
+            Pattern::List(list) => self.list(&list.items),
+            Pattern::Enum(enum_pat) => self.variant(
+                &enum_pat.variant.name.value.to_string(),
+                &enum_pat.variant.pattern
+            ),
+            Pattern::Some(p) => self.variant("Some", &Some(p.clone())),
+            Pattern::Ok(p) => self.variant("Ok", &Some(p.clone())),
+            Pattern::Err(p) => self.variant("Err", &Some(p.clone())),
+            Pattern::None => self.variant("None", &None),
+            Pattern::Struct(_) => self.opaque(),
+        }
+    }
+
+    //---------------------
+    //  variant()
+    //---------------------
+    fn variant(
+        &mut self,
+        name: &str,
+        pattern: &Option<Box<Pattern>>
+    ) -> Pat {
+        match pattern {
+            Some(p) => Pat::Ctor(Ctor::Variant(name.to_string(), 1), vec![self.pat(p)]),
+            None => Pat::Ctor(Ctor::Variant(name.to_string(), 0), vec![])
+        }
+    }
+
+    //---------------------
+    //  list()
+    //---------------------
+    // `[a, .., z]` is a list of at least two values as long as `z` matches anything
+    fn list(
+        &mut self,
+        items: &Vec<Pattern>,
+    ) -> Pat {
+        let rest = items.iter().position(|p| matches!(p, Pattern::Rest));
+        let pats = items.iter()
+            .filter(|p| !matches!(p, Pattern::Rest))
+            .map(|p| self.pat(p))
+            .collect::<Vec<_>>();
+        match rest {
+            None => Pat::Ctor(Ctor::List(pats.len(), false), pats),
+            Some(i) => {
+                let suffix = &pats[i..];
+                if suffix.iter().all(|p| matches!(p, Pat::Wild)) {
+                    Pat::Ctor(Ctor::List(pats.len(), true), pats)
+                } else {
+                    self.opaque()
+                }
+            }
+        }
+    }
+
+    //---------------------
+    //  literal()
+    //---------------------
+    fn literal(
+        &mut self,
+        expr: &Expr,
+    ) -> Ctor {
+        match expr {
+            Expr::Bool(t) => {
+                let v = t.value.to_string();
+                Ctor::Bool(self.lexicon.concept_any("kw", &v) == Some("kw.true"))
+            },
+            Expr::Int(t) => match int(t) {
+                Some(v) => Ctor::Int(v, v),
+                None => Ctor::Lit(t.value.to_string())
+            },
+            Expr::Char(t) => Ctor::Lit(format!("'{}'", t.value)),
+            Expr::Str(t) => Ctor::Lit(format!("\"{}\"", t.value)),
+            Expr::Float(t) => Ctor::Lit(t.value.to_string()),
+            _ => self.opaque_ctor()
+        }
+    }
+
+    //---------------------
+    //  range()
+    //---------------------
+    // only integer ranges are split, other ranges only cover themselves
+    fn range(
+        &mut self,
+        range: &Range,
+    ) -> Ctor {
+        let bound = |e: &Option<Box<Expr>>| match e.as_deref() {
+            None => Some(None),
+            Some(Expr::Int(t)) => int(t).map(Some),
+            Some(_) => None
+        };
+        match (bound(&range.start), bound(&range.end)) {
+            (Some(start), Some(end)) => {
+                let lo = start.unwrap_or(i128::MIN);
+                let hi = match end {
+                    None => i128::MAX,
+                    Some(end) if range.inclusive => end,
+                    Some(end) => end.saturating_sub(1)
+                };
+                if lo > hi { self.opaque_ctor() } else { Ctor::Int(lo, hi) }
+            },
+            _ => self.opaque_ctor()
+        }
+    }
+
+    //---------------------
+    //  opaque()
+    //---------------------
+    fn opaque(&mut self) -> Pat {
+        Pat::Ctor(self.opaque_ctor(), vec![])
+    }
+
+    //---------------------
+    //  opaque_ctor()
+    //---------------------
+    fn opaque_ctor(&mut self) -> Ctor {
+        self.opaque_count += 1;
+        Ctor::Opaque(self.opaque_count)
+    }
+}
+
+//================
+//   useful()
+//================
+// returns the values matched by `row` and by none of the `rows`, one pattern per column
+impl<'a> Exhaustiveness<'a> {
+    fn useful(
+        &mut self,
+        rows: &Vec<Vec<Pat>>,
+        row: &Vec<Pat>
+    ) -> Option<Vec<Pat>> {
+        let (head, tail) = match row.split_first() {
+            None => return if rows.is_empty() { Some(vec![]) } else { None },
+            Some((head, tail)) => (head, tail)
+        };
+        let column = column(rows, head);
+
+        match head {
+            Pat::Or(alts) => {
+                for alt in alts {
+                    let mut row = vec![alt.clone()];
+                    row.extend_from_slice(tail);
+                    if let Some(witness) = self.useful(rows, &row) {
+                        return Some(witness)
+                    }
+                }
+                None
+            },
+            Pat::Ctor(ctor, _) => {
+                let ctors = split(ctor, &column);
+                self.useful_ctors(rows, row, &ctors)
+            },
+            Pat::Wild => {
+                let all = self.all_ctors(&column);
+                let complete = match &all {
+                    Some(all) => all.iter().all(|c| column.iter().any(|h| h.covers(c))),
+                    None => false
+                };
+                if complete {
+                    return self.useful_ctors(rows, row, &all.unwrap())
+                }
+
+                let defaults = rows.iter()
+                    .flat_map(|r| default(r))
+                    .collect();
+                let witness = self.useful(&defaults, &tail.to_vec())?;
+                let missing = match all {
+                    Some(all) => {
+                        match all.into_iter().find(|c| !column.iter().any(|h| h.covers(c))) {
+                            Some(c) => {
+                                let args = vec![Pat::Wild; c.arity()];
+                                Pat::Ctor(c, args)
+                            },
+                            None => Pat::Wild
+                        }
+                    },
+                    None => Pat::Wild
+                };
+                let mut res = vec![missing];
+                res.extend(witness);
+                Some(res)
+            }
+        }
+    }
+
+    //---------------------
+    //  useful_ctors()
+    //---------------------
+    fn useful_ctors(
+        &mut self,
+        rows: &Vec<Vec<Pat>>,
+        row: &Vec<Pat>,
+        ctors: &Vec<Ctor>
+    ) -> Option<Vec<Pat>> {
+        for ctor in ctors {
+            let specialized = rows.iter()
+                .flat_map(|r| specialize(r, ctor))
+                .collect();
+            for row in specialize(row, ctor) {
+                if let Some(witness) = self.useful(&specialized, &row) {
+                    let (args, rest) = witness.split_at(ctor.arity());
+                    let mut res = vec![Pat::Ctor(ctor.clone(), args.to_vec())];
+                    res.extend_from_slice(rest);
+                    return Some(res)
+                }
+            }
+        }
+        None
+    }
+
+    //---------------------
+    //  all_ctors()
+    //---------------------
+    // `None` when the values can not be listed
+    fn all_ctors(
+        &mut self,
+        column: &Vec<Ctor>,
+    ) -> Option<Vec<Ctor>> {
+        match self.domain(column) {
+            Domain::Bool => Some(vec![Ctor::Bool(true), Ctor::Bool(false)]),
+            Domain::Variants(variants) => Some(
+                variants.into_iter()
+                    .map(|(name, arity)| Ctor::Variant(name, arity))
+                    .collect()
+            ),
+            Domain::Tuple(arity) => Some(vec![Ctor::Tuple(arity)]),
+            Domain::List => Some(split(&Ctor::List(0, true), column)),
+            Domain::Int => Some(split(&Ctor::Int(i128::MIN, i128::MAX), column)),
+            Domain::Infinite => None,
+            Domain::Unknown => {
+                self.unknown = true;
+                None
+            }
+        }
+    }
+
+    //---------------------
+    //  domain()
+    //---------------------
+    fn domain(
+        &self,
+        column: &Vec<Ctor>,
+    ) -> Domain {
+        let ctor = match column.iter().find(|c| !matches!(c, Ctor::Opaque(_))) {
+            Some(ctor) => ctor,
+            None if column.is_empty() => return Domain::Infinite,
+            None => return Domain::Unknown
+        };
+        match ctor {
+            Ctor::Bool(_) => Domain::Bool,
+            Ctor::Tuple(arity) => Domain::Tuple(*arity),
+            Ctor::List(..) => Domain::List,
+            Ctor::Int(..) => Domain::Int,
+            Ctor::Lit(_) => Domain::Infinite,
+            Ctor::Opaque(_) => Domain::Unknown,
+            Ctor::Variant(name, _) => {
+                let arity = |name: &str| column.iter()
+                    .find_map(|c| match c {
+                        Ctor::Variant(n, arity) if n == name => Some(*arity),
+                        _ => None
+                    })
+                    .unwrap_or(0);
+                let variants: Vec<String> = match name.as_str() {
+                    "Some" | "None" => vec!["Some".to_string(), "None".to_string()],
+                    "Ok" | "Err" => vec!["Ok".to_string(), "Err".to_string()],
+                    _ => match self.enums.values().find(|variants| variants.contains(name)) {
+                        Some(variants) => variants.clone(),
+                        None => return Domain::Unknown
+                    }
+                };
+                Domain::Variants(
+                    variants.into_iter()
+                        .map(|name| {
+                            let arity = match name.as_str() {
+                                "Some" | "Ok" | "Err" => 1,
+                                "None" => 0,
+                                _ => arity(&name)
+                            };
+                            (name, arity)
+                        })
+                        .collect()
+                )
+            }
+        }
+    }
+}
+
+//================
+//   column()
+//================
+// the constructors at the head of the rows, and of `head` itself
+fn column(
+    rows: &Vec<Vec<Pat>>,
+    head: &Pat
+) -> Vec<Ctor> {
+    let mut ctors = vec![];
+    for row in rows {
+        head_ctors(&row[0], &mut ctors);
+    }
+    head_ctors(head, &mut ctors);
+    ctors
+}
+
+//================
+//   head_ctors()
+//================
+fn head_ctors(
+    pat: &Pat,
+    ctors: &mut Vec<Ctor>
+) {
+    match pat {
+        Pat::Wild => (),
+        Pat::Ctor(ctor, _) => ctors.push(ctor.clone()),
+        Pat::Or(alts) => {
+            for alt in alts {
+                head_ctors(alt, ctors);
+            }
+        }
+    }
+}
+
+//================
+//   split()
+//================
+// splits `ctor` into constructors that are either fully covered or not covered at all
+//  by each constructor of the column
+fn split(
+    ctor: &Ctor,
+    column: &Vec<Ctor>
+) -> Vec<Ctor> {
+    match ctor {
+        Ctor::Int(lo, hi) => {
+            let mut starts = BTreeSet::from([*lo]);
+            for c in column {
+                if let Ctor::Int(x, y) = c {
+                    starts.insert(*x);
+                    if *y < i128::MAX {
+                        starts.insert(*y + 1);
+                    }
+                }
+            }
+            let starts = starts.into_iter()
+                .filter(|s| lo <= s && s <= hi)
+                .collect::<Vec<_>>();
+            starts.iter()
+                .enumerate()
+                .map(|(i, start)| {
+                    let end = match starts.get(i + 1) {
+                        Some(next) => next - 1,
+                        None => *hi
+                    };
+                    Ctor::Int(*start, end)
+                })
+                .collect()
+        },
+        // lists longer than every pattern without `..` and than every prefix all behave the same
+        Ctor::List(len, true) => {
+            let max = column.iter()
+                .map(|c| match c {
+                    Ctor::List(n, false) => n + 1,
+                    Ctor::List(n, true) => *n,
+                    _ => 0
+                })
+                .max()
+                .unwrap_or(0)
+                .max(*len);
+            let mut ctors = (*len..max)
+                .map(|n| Ctor::List(n, false))
+                .collect::<Vec<_>>();
+            ctors.push(Ctor::List(max, true));
+            ctors
+        },
+        ctor => vec![ctor.clone()]
+    }
+}
+
+//================
+//   specialize()
+//================
+// the rows that match `ctor`, with the head replaced by its values
+fn specialize(
+    row: &Vec<Pat>,
+    ctor: &Ctor
+) -> Vec<Vec<Pat>> {
+    let tail = &row[1..];
+    match &row[0] {
+        Pat::Wild => {
+            let mut res = vec![Pat::Wild; ctor.arity()];
+            res.extend_from_slice(tail);
+            vec![res]
+        },
+        Pat::Ctor(head, args) => {
+            if !head.covers(ctor) { return vec![] }
+            let mut res = args.clone();
+            // `[a, ..]` against a longer list
+            res.resize(ctor.arity(), Pat::Wild);
+            res.extend_from_slice(tail);
+            vec![res]
+        },
+        Pat::Or(alts) => {
+            alts.iter()
+                .flat_map(|alt| {
+                    let mut row = vec![alt.clone()];
+                    row.extend_from_slice(tail);
+                    specialize(&row, ctor)
+                })
+                .collect()
+        }
+    }
+}
+
+//================
+//   default()
+//================
+// the rows that match any value of the head column
+fn default(
+    row: &Vec<Pat>
+) -> Vec<Vec<Pat>> {
+    let tail = &row[1..];
+    match &row[0] {
+        Pat::Wild => vec![tail.to_vec()],
+        Pat::Ctor(..) => vec![],
+        Pat::Or(alts) => {
+            alts.iter()
+                .flat_map(|alt| {
+                    let mut row = vec![alt.clone()];
+                    row.extend_from_slice(tail);
+                    default(&row)
+                })
+                .collect()
+        }
+    }
+}
+
+//================
+//   int()
+//================
+fn int(t: &Token) -> Option<i128> {
+    to_western_num(&t.value.to_string()).parse::<i128>().ok()
+}
+
+//================
+//   display()
+//================
+// a missing case written as a pattern
+fn display(pat: &Pat) -> String {
+    match pat {
+        Pat::Wild => "_".to_string(),
+        Pat::Or(alts) => alts.iter().map(display).collect::<Vec<_>>().join(" | "),
+        Pat::Ctor(ctor, args) => {
+            let args = args.iter().map(display).collect::<Vec<_>>();
+            match ctor {
+                Ctor::Bool(b) => b.to_string(),
+                Ctor::Variant(name, 0) => name.to_string(),
+                Ctor::Variant(name, _) => format!("{}({})", name, args.join(", ")),
+                Ctor::Tuple(_) => format!("({})", args.join(", ")),
+                Ctor::List(_, false) => format!("[{}]", args.join(", ")),
+                Ctor::List(0, true) => "[..]".to_string(),
+                Ctor::List(_, true) => format!("[{}, ..]", args.join(", ")),
+                Ctor::Int(i128::MIN, i128::MAX) => "_".to_string(),
+                Ctor::Int(lo, hi) if lo == hi => lo.to_string(),
+                Ctor::Int(i128::MIN, hi) => format!("..={}", hi),
+                Ctor::Int(lo, i128::MAX) => format!("{}..", lo),
+                Ctor::Int(lo, hi) => format!("{}..={}", lo, hi),
+                Ctor::Lit(_)
+                | Ctor::Opaque(_) => "_".to_string(),
+            }
+        }
+    }
+}
@@ -0,0 +1,657 @@
+use std::{
+    collections::HashSet,
+    path::Path
+};
+
+use super::{
+    Lang,
+    token::{
+        Token,
+        TokenValue
+    },
+    syntax_tree::ast::*,
+};
+
+use crate::{
+    transl::lexicon::Lexicon,
+    util::ar::to_western_num
+};
+
+//================
+//   Constants
+//================
+// the names of the HIR are the english words of the lexicon, whatever language the module is written in
+pub const NEUTRAL_LANG: Lang = Lang::En;
+const RS_EXT: &str = "rs";
+
+//================
+//   Hir
+//================
+// a module as the backends read it, after the checks and the lowering passes. keywords, builtins, std
+//  types and traits, attributes and numbers are spelled the same way for every source language, so a
+//  backend never looks at the language the module was written in. it keeps the nodes of the AST for
+//  now, only the names, the numbers and the imports are lowered
+// FIXME: inference is still turned off, the only types known are the written ones and those of literals
+#[derive(Debug)]
+pub struct Hir {
+    pub name: String,               // the file stem, `main` for the main module
+    pub lang: Lang,                 // the source language, only for the text the program shows to its users
+    pub items: Vec<ModElement>,
+    pub imports: Vec<Import>
+}
+
+impl Hir {
+    //---------------------
+    //  is_import()
+    //---------------------
+    pub fn is_import(&self, name: &str) -> bool {
+        self.imports.iter().any(|import| import.name == name)
+    }
+}
+
+//================
+//   Import
+//================
+// `m := import("m.seen")`, a module of the same directory
+#[derive(Debug)]
+pub struct Import {
+    pub name: String,
+    pub path: String                // the file without its extension
+}
+
+//================
+//   Builtin
+//================
+// the functions the backends write in their own way instead of calling them
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Builtin {
+    Println,
+    Print,
+    Import,
+    WebView,
+    MobileView,
+    GuiView,
+    Map,
+    Filter
+}
+
+impl Builtin {
+    //---------------------
+    //  all()
+    //---------------------
+    pub fn all() -> Vec<Builtin> {
+        vec![
+            Builtin::Println, Builtin::Print, Builtin::Import, Builtin::WebView,
+            Builtin::MobileView, Builtin::GuiView, Builtin::Map, Builtin::Filter
+        ]
+    }
+
+    //---------------------
+    //  concept()
+    //---------------------
+    pub fn concept(&self) -> &'static str {
+        match self {
+            Builtin::Println => "fn.println",
+            Builtin::Print => "fn.print",
+            Builtin::Import => "fn.import",
+            Builtin::WebView => "fn.web_view",
+            Builtin::MobileView => "fn.mobile_view",
+            Builtin::GuiView => "fn.gui_view",
+            Builtin::Map => "fn.map",
+            Builtin::Filter => "fn.filter"
+        }
+    }
+
+    //---------------------
+    //  from_name()
+    //---------------------
+    // a name of the HIR, e.g. `println`
+    pub fn from_name(name: &str) -> Option<Builtin> {
+        Self::all()
+            .into_iter()
+            .find(|builtin| builtin.concept().strip_prefix("fn.") == Some(name))
+    }
+}
+
+//================
+//   HirLowering
+//================
+pub struct HirLowering<'a> {
+    lexicon: &'a Lexicon,
+    lang: Lang,
+    fns: HashSet<String>,       // the functions declared in the module, a call to one of them keeps its name
+    methods: HashSet<String>,   // the methods declared in the module
+    types: HashSet<String>      // the structs, enums and traits declared in the module
+}
+
+impl<'a> HirLowering<'a> {
+    //---------------------
+    //  new()
+    //---------------------
+    pub fn new() -> Self {
+        Self {
+            lexicon: Lexicon::embedded(),
+            lang: NEUTRAL_LANG,
+            fns: HashSet::new(),
+            methods: HashSet::new(),
+            types: HashSet::new()
+        }
+    }
+
+    //---------------------
+    //  lexicon()
+    //---------------------
+    pub fn lexicon(&mut self, lexicon: &'a Lexicon) {
+        self.lexicon = lexicon;
+    }
+
+    //---------------------
+    //  lower()
+    //---------------------
+    // `path` is the path of the module, its file stem names the generated module
+    pub fn lower(
+        &mut self,
+        path: &str,
+        lang: &Lang,
+        mut items: Vec<ModElement>
+    ) -> Hir {
+        self.lang = lang.clone();
+        self.decls(&items);
+
+        let stem = Path::new(path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("")
+            .to_string();
+        let name = if self.lexicon.is("file.main", lang, &stem) {
+            self.lexicon.word(&NEUTRAL_LANG, "file.main")
+        } else {
+            stem
+        };
+
+        for el in items.iter_mut() {
+            self.item(el);
+        }
+
+        let mut imports = vec![];
+        items.retain(|el| match import(el) {
+            Some(import) => {
+                imports.push(import);
+                false
+            },
+            None => true
+        });
+
+        Hir {
+            name,
+            lang: lang.clone(),
+            items,
+            imports
+        }
+    }
+}
+
+//================
+//   import()
+//================
+// an import is only written at the top of a module, its argument names a file of the same directory
+fn import(el: &ModElement) -> Option<Import> {
+    let ModElement::Decl(Decl{pattern: Pattern::Id(IdPattern{id}), expr: Some(Expr::BinOp(call)), ..}) = el else { return None };
+    let (Expr::Ref(callee), Expr::Tuple(args)) = (&*call.l_opr, &*call.r_opr) else { return None };
+    if Builtin::from_name(&callee.value.to_string()) != Some(Builtin::Import) { return None }
+    let Some(Expr::Str(file)) = args.items.first() else { return None };
+
+    let file = file.value.to_string();
+    let path = Lang::all()
+        .iter()
+        .map(|lang| lang.ext())
+        .chain([RS_EXT])
+        .find_map(|ext| file.strip_suffix(&format!(".{}", ext)))
+        .unwrap_or(&file)
+        .to_string();
+    Some(Import{ name: id.value.to_string(), path })
+}
+
+//================
+//   decls()
+//================
+// the names the module declares, a function of the module called `اطبع` is not the builtin `print`
+impl<'a> HirLowering<'a> {
+    fn decls(
+        &mut self,
+        items: &Vec<ModElement>
+    ) {
+        self.fns.clear();
+        self.methods.clear();
+        self.types.clear();
+        for el in items.iter() {
+            match el {
+                ModElement::Fn(Fn{name: Some(name), ..}) => {
+                    self.fns.insert(name.value.to_string());
+                },
+                ModElement::StructImpl(StructImpl{fns, ..})
+                | ModElement::EnumImpl(EnumImpl{fns, ..}) => {
+                    let names = fns.iter().filter_map(|_fn| _fn.name.as_ref());
+                    self.methods.extend(names.map(|name| name.value.to_string()));
+                },
+                ModElement::Struct(Struct{name, ..})
+                | ModElement::Enum(Enum{name, ..})
+                | ModElement::Trait(Trait{name, ..}) => {
+                    self.types.insert(name.value.to_string());
+                },
+                _ => ()
+            }
+        }
+    }
+}
+
+//================
+//   item()
+//================
+impl<'a> HirLowering<'a> {
+    fn item(
+        &mut self,
+        el: &mut ModElement
+    ) {
+        match el {
+            ModElement::Decl(decl) => self.decl(decl),
+            ModElement::MainFn(_fn)
+            | ModElement::Fn(_fn) => self._fn(_fn),
+            ModElement::Struct(_struct) => {
+                for attr in _struct.attrs.iter_mut() {
+                    self.attr(attr);
+                }
+                self.generics(&mut _struct.generics);
+                for field in _struct.fields.iter_mut().flatten() {
+                    self._type(&mut field._type);
+                    if let Some(default) = &mut field.default {
+                        self.expr(default);
+                    }
+                }
+            },
+            ModElement::StructImpl(struct_impl) => {
+                for _fn in struct_impl.fns.iter_mut() {
+                    self._fn(_fn);
+                }
+            },
+            ModElement::EnumImpl(enum_impl) => {
+                for _fn in enum_impl.fns.iter_mut() {
+                    self._fn(_fn);
+                }
+            },
+            ModElement::Trait(_)
+            | ModElement::Enum(_) => ()
+        }
+    }
+}
+
+//================
+//   _fn()
+//================
+impl<'a> HirLowering<'a> {
+    fn _fn(
+        &mut self,
+        _fn: &mut Fn
+    ) {
+        for attr in _fn.attrs.iter_mut().flatten() {
+            self.attr(attr);
+        }
+        self.generics(&mut _fn.generics);
+        for param in _fn.params.iter_mut() {
+            self.pattern(&mut param.pat);
+            if let Some(_type) = &mut param._type {
+                self._type(_type);
+            }
+        }
+        if let Some(ret_type) = &mut _fn.ret_type {
+            self._type(ret_type);
+        }
+        for decl in _fn.where_decls.iter_mut() {
+            self.decl(decl);
+        }
+        self.block(&mut _fn.block);
+    }
+}
+
+//================
+//   generics()
+//================
+impl<'a> HirLowering<'a> {
+    fn generics(
+        &mut self,
+        generics: &mut Vec<GenericParam>
+    ) {
+        for param in generics.iter_mut() {
+            for bound in param.bounds.iter_mut() {
+                self._type(bound);
+            }
+        }
+    }
+}
+
+//================
+//   attr()
+//================
+// `@اشتق(قابل_للمساواة)` is `@derive(Eq)`
+impl<'a> HirLowering<'a> {
+    fn attr(
+        &mut self,
+        attr: &mut Attr
+    ) {
+        match &mut attr.expr {
+            AttrExpr::Ref(name) => self.rename("attr", name),
+            AttrExpr::Call(call) => {
+                self.rename("attr", &mut call.name);
+                for arg in call.args.iter_mut() {
+                    match &mut arg.expr {
+                        Expr::Ref(t) => self.rename("trait", t),
+                        expr => self.expr(expr)
+                    }
+                }
+            }
+        }
+    }
+}
+
+//================
+//   block()
+//================
+impl<'a> HirLowering<'a> {
+    fn block(
+        &mut self,
+        block: &mut Vec<BlockElement>
+    ) {
+        for el in block.iter_mut() {
+            match el {
+                BlockElement::Decl(decl) => self.decl(decl),
+                BlockElement::Expr(expr) => self.expr(expr),
+                BlockElement::Ret(Ret{expr: Some(expr), ..}) => self.expr(expr),
+                BlockElement::Ret(_)
+                | BlockElement::MainArgs => ()
+            }
+        }
+    }
+}
+
+//================
+//   decl()
+//================
+impl<'a> HirLowering<'a> {
+    fn decl(
+        &mut self,
+        decl: &mut Decl
+    ) {
+        self.pattern(&mut decl.pattern);
+        if let Some(_type) = &mut decl._type {
+            self._type(_type);
+        }
+        if let Some(expr) = &mut decl.expr {
+            self.expr(expr);
+        }
+    }
+}
+
+//================
+//   expr()
+//================
+impl<'a> HirLowering<'a> {
+    fn expr(
+        &mut self,
+        expr: &mut Expr
+    ) {
+        match expr {
+            Expr::Bool(t) => self.rename("kw", t),
+            Expr::Int(t)
+            | Expr::Float(t) => {
+                let v = to_western_num(&t.value.to_string());
+                t.value = match t.value {
+                    TokenValue::Int(_) => TokenValue::Int(v),
+                    TokenValue::Float(_) => TokenValue::Float(v),
+                    _ => TokenValue::Id(v)
+                };
+            },
+            Expr::Ref(t) => {
+                if self.lexicon.is("kw.self", &self.lang, &t.value.to_string()) {
+                    self.rename("kw", t);
+                }
+            },
+            Expr::GenericRef(generic_ref) => {
+                for arg in generic_ref.args.iter_mut() {
+                    self._type(arg);
+                }
+            },
+            Expr::BinOp(bin_op) if bin_op.is_access() => {
+                self.expr(&mut bin_op.l_opr);
+                self.segments(&mut bin_op.r_opr);
+            },
+            Expr::BinOp(bin_op) => {
+                // the name of a called builtin
+                if let (Expr::Ref(callee), Expr::Tuple(_)) = (&mut *bin_op.l_opr, &*bin_op.r_opr) {
+                    if !self.fns.contains(&callee.value.to_string()) {
+                        self.rename("fn", callee);
+                    }
+                }
+                self.expr(&mut bin_op.l_opr);
+                self.expr(&mut bin_op.r_opr);
+            },
+            Expr::Fn(_fn) => self._fn(_fn),
+            Expr::LetIn(let_in) => {
+                self.decl(&mut let_in.decl);
+                self.expr(&mut let_in.expr);
+            },
+            Expr::Cast(cast) => {
+                self.expr(&mut cast.expr);
+                self._type(&mut cast._type);
+            },
+            Expr::For(_for) => {
+                self.pattern(&mut _for.in_expr.pattern);
+                self.expr(&mut _for.in_expr.expr);
+                self.block(&mut _for.block);
+            },
+            Expr::While(_while) => {
+                self.expr(&mut _while.expr);
+                self.block(&mut _while.block);
+            },
+            Expr::Loop(_loop) => self.block(&mut _loop.block),
+            Expr::Break(_break) => {
+                if let Some(expr) = &mut _break.expr {
+                    self.expr(expr);
+                }
+            },
+            Expr::Match(_match) => {
+                self.expr(&mut _match.expr);
+                for arm in _match.arms.iter_mut() {
+                    self.pattern(&mut arm.pattern);
+                    if let Some(guard) = &mut arm.guard {
+                        self.expr(guard);
+                    }
+                    self.block(&mut arm.block);
+                }
+            },
+            Expr::If(_if) => {
+                for branch in _if.branches.iter_mut() {
+                    if let Some(expr) = &mut branch.expr {
+                        self.expr(expr);
+                    }
+                    self.block(&mut branch.block);
+                }
+            },
+            Expr::StructLiteral(literal) => {
+                for (_, value) in literal.items.iter_mut() {
+                    if let Some(value) = value {
+                        self.expr(value);
+                    }
+                }
+                if let Some(base) = &mut literal.base {
+                    self.expr(base);
+                }
+            },
+            Expr::Range(range) => self.range(range),
+            Expr::Map(map) => {
+                for (key, value) in map.items.iter_mut() {
+                    self.expr(key);
+                    self.expr(value);
+                }
+            },
+            Expr::List(List{items})
+            | Expr::Tuple(Tuple{items}) => {
+                for item in items.iter_mut() {
+                    self.expr(item);
+                }
+            },
+            Expr::PreUniOp(uni_op)
+            | Expr::PostUniOp(uni_op) => self.expr(&mut uni_op.opr),
+            Expr::Ok(expr)
+            | Expr::Err(expr)
+            | Expr::Some(expr)
+            | Expr::Borrow(expr)
+            | Expr::Clone(expr)
+            | Expr::Owned(expr) => self.expr(expr),
+            _ => ()
+        }
+    }
+}
+
+//================
+//   segments()
+//================
+// what follows the receiver of an access, `.حول(f)` is `.map(f)` unless the module has a method `حول`
+impl<'a> HirLowering<'a> {
+    fn segments(
+        &mut self,
+        expr: &mut Expr
+    ) {
+        match expr {
+            Expr::BinOp(bin_op) if bin_op.is_access() => {
+                self.segments(&mut bin_op.l_opr);
+                self.segments(&mut bin_op.r_opr);
+            },
+            Expr::BinOp(call) if matches!((&*call.l_opr, &*call.r_opr), (Expr::Ref(_), Expr::Tuple(_))) => {
+                if let Expr::Ref(callee) = &mut *call.l_opr {
+                    if !self.methods.contains(&callee.value.to_string()) {
+                        self.rename("fn", callee);
+                    }
+                }
+                self.expr(&mut call.r_opr);
+            },
+            _ => self.expr(expr)
+        }
+    }
+}
+
+//================
+//   range()
+//================
+impl<'a> HirLowering<'a> {
+    fn range(
+        &mut self,
+        range: &mut Range
+    ) {
+        if let Some(start) = &mut range.start {
+            self.expr(start);
+        }
+        if let Some(end) = &mut range.end {
+            self.expr(end);
+        }
+    }
+}
+
+//================
+//   pattern()
+//================
+impl<'a> HirLowering<'a> {
+    fn pattern(
+        &mut self,
+        pattern: &mut Pattern
+    ) {
+        match pattern {
+            Pattern::PrimitiveLiteral(expr) => self.expr(expr),
+            Pattern::Range(range) => self.range(range),
+            Pattern::List(ListPattern{items, ..})
+            | Pattern::Tuple(TuplePattern{items})
+            | Pattern::Or(items) => {
+                for item in items.iter_mut() {
+                    self.pattern(item);
+                }
+            },
+            Pattern::Struct(struct_pat) => {
+                for (_, item) in struct_pat.items.iter_mut() {
+                    if let Some(item) = item {
+                        self.pattern(item);
+                    }
+                }
+            },
+            Pattern::Binding(binding) => self.pattern(&mut binding.pattern),
+            Pattern::Ok(pattern)
+            | Pattern::Err(pattern)
+            | Pattern::Some(pattern) => self.pattern(pattern),
+            _ => ()
+        }
+    }
+}
+
+//================
+//   _type()
+//================
+impl<'a> HirLowering<'a> {
+    fn _type(
+        &mut self,
+        _type: &mut Type
+    ) {
+        match _type {
+            Type::PrimitiveType(PrimitiveType{id}) => self.rename("type", id),
+            Type::NamedType(named) => {
+                if !self.types.contains(&named.name.value.to_string()) {
+                    self.rename("trait", &mut named.name);
+                }
+                for arg in named.args.iter_mut() {
+                    self._type(arg);
+                }
+            },
+            Type::OptionType(option) => self._type(&mut option.some_type),
+            Type::ResultType(result) => {
+                self._type(&mut result.ok_type);
+                self._type(&mut result.err_type);
+            },
+            Type::ListType(list) => self._type(&mut list.els_type),
+            Type::MapType(map) => {
+                self._type(&mut map.key_type);
+                self._type(&mut map.value_type);
+            },
+            Type::TupleType(tuple) => {
+                for t in tuple.types.iter_mut() {
+                    self._type(t);
+                }
+            },
+            Type::FnType(fn_type) => {
+                for t in fn_type.params_types.iter_mut() {
+                    self._type(t);
+                }
+                if let Some(ret_type) = &mut fn_type.ret_type {
+                    self._type(ret_type);
+                }
+            },
+            Type::StructType(_)
+            | Type::UnitType => ()
+        }
+    }
+}
+
+//================
+//   rename()
+//================
+// spells a word of the lexicon namespace `ns` with its neutral word, other names are kept
+impl<'a> HirLowering<'a> {
+    fn rename(
+        &self,
+        ns: &str,
+        t: &mut Token
+    ) {
+        let Some(concept) = self.lexicon.concept(ns, &self.lang, &t.value.to_string()) else { return };
+        let word = self.lexicon.word(&NEUTRAL_LANG, concept);
+        t.value = match t.value {
+            TokenValue::Bool(_) => TokenValue::Bool(word),
+            _ => TokenValue::Id(word)
+        };
+    }
+}
@@ -6,6 +6,8 @@ use std::{
 use regex::Regex;
 
 use crate::{
+    transl::lexicon::Lexicon,
+    util::ar::is_eastern_digit,
     lang::{
         Lang,
         error::Error,
@@ -25,11 +27,24 @@ use crate::{
 const NULL: char = '\0';
 const TATWEEL: char = '\u{640}'; // ـ
 
+// explicit embeddings / overrides / isolates, these can reorder how the source is displayed
+// without changing how it is compiled (Trojan Source, CVE-2021-42574)
+const BIDI_LRE: char = '\u{202A}';
+const BIDI_RLE: char = '\u{202B}';
+const BIDI_PDF: char = '\u{202C}';
+const BIDI_LRO: char = '\u{202D}';
+const BIDI_RLO: char = '\u{202E}';
+const BIDI_LRI: char = '\u{2066}';
+const BIDI_RLI: char = '\u{2067}';
+const BIDI_FSI: char = '\u{2068}';
+const BIDI_PDI: char = '\u{2069}';
+
 //================
 //   Lexer
 //================
 pub struct Lexer<'a> {
     lang: &'a Lang,
+    lexicon: &'a Lexicon,
     start_location: Location,
     end_location: Location,
     current: char,
@@ -40,6 +55,9 @@ pub struct Lexer<'a> {
     space_indent: bool,
     tab_indent: bool,
     indent_conflict_reported: bool,
+    allow_bidi: bool,
+    in_text: bool,
+    bidi_stack: Vec<(char, Location)>,
     any_letter: Regex,
     any_numeric: Regex,
 }
@@ -51,6 +69,7 @@ impl<'a> Lexer<'a> {
     pub fn new() -> Self {
         Lexer {
             lang: &Lang::Ar,
+            lexicon: Lexicon::embedded(),
             start_location: Location::new(1,1),
             end_location: Location::new(1,1),
             current: NULL,
@@ -61,11 +80,30 @@ impl<'a> Lexer<'a> {
             space_indent: false,
             tab_indent: false,
             indent_conflict_reported: false,
+            allow_bidi: false,
+            in_text: false,
+            bidi_stack: vec![],
             any_letter: Regex::new(r"\p{L}").unwrap(),
             any_numeric: Regex::new(r"\p{N}").unwrap(),
         }
     }
 
+    //---------------------
+    //  lexicon()
+    //---------------------    
+    pub fn lexicon(&mut self, lexicon: &'a Lexicon) {
+        self.lexicon = lexicon;
+    }
+
+    //---------------------
+    //  allow_bidi()
+    //---------------------    
+    // bidi control characters are rejected by default, projects that need them in their strings 
+    // and comments can opt out through conf.seen, they still have to be balanced. 
+    pub fn allow_bidi(&mut self, allow: bool) {
+        self.allow_bidi = allow;
+    }
+
     //---------------------
     //  tokens()
     //---------------------        
@@ -79,43 +117,44 @@ impl<'a> Lexer<'a> {
             self.start_location = self.end_location.clone();
             let c = self.next();
 
-            match self.lang {
-                Lang::Ar => {
-                    match c {
-                        '؟' => self.add_token(TokenValue::Question),
-                        '\\' => self.ar_comment_asgmt_div_bwand(),
-                        '/' => self.ar_bwor(),
-                        '«' => self.ar_string(),
-                        '‹' => self.ar_character(),   
-                        '0'..='9' => {
-                            self.number_western();
-                            self.number_postfix();
-                        },
-                        '٠' ..='٩' => {
-
-                            self.number_eastern();
-                            self.number_postfix();
-                        }                                                 
-                        _ => self.common(c)  
-                    }      
-                }, 
-                Lang::En => {
-                    match c {
-                        '?' => self.add_token(TokenValue::Question),
-                        '/' => self.en_comment_asgmt_div_bwand(),
-                        '\\' => self.en_bwor(),
-                        '"' => self.en_string(),
-                        '\'' => self.en_character(),  
-                        '0'..='9' => {
-                            self.number_en();
-                            self.number_postfix();
-                        },
-                        '٠' ..='٩' => {
-                            self.insert_error(format!("only English Numerals are allowed in English source files: {}", self.current));
-                            self.skip_invalid_num_or_id();
-                        }                                                      
-                        _ => self.common(c)                              
-                    }
+            if self.lang.is_rtl() {
+                match c {
+                    '؟' => self.add_token(TokenValue::Question),
+                    '\\' => self.ar_comment_asgmt_div_bwand(),
+                    '/' => self.ar_bwor(),
+                    '«' => self.ar_string(),
+                    '‹' => self.ar_character(),   
+                    '0'..='9' => {
+                        self.number_western();
+                        self.number_postfix();
+                    },
+                    x if self.is_native_digit(x) => {
+
+                        self.number_eastern();
+                        self.number_postfix();
+                    }                                                 
+                    x if is_eastern_digit(x) => {
+                        self.insert_error(format!("only {} or Western (0 - 9) numerals are allowed in this source file: {}", self.native_digits(), self.current));
+                        self.skip_invalid_num_or_id();
+                    }                                                      
+                    _ => self.common(c)  
+                }      
+            } else {
+                match c {
+                    '?' => self.add_token(TokenValue::Question),
+                    '/' => self.en_comment_asgmt_div_bwand(),
+                    '\\' => self.en_bwor(),
+                    '"' => self.en_string(),
+                    '\'' => self.en_character(),  
+                    '0'..='9' => {
+                        self.number_en();
+                        self.number_postfix();
+                    },
+                    x if is_eastern_digit(x) => {
+                        self.insert_error(format!("only English Numerals are allowed in English source files: {}", self.current));
+                        self.skip_invalid_num_or_id();
+                    }                                                      
+                    _ => self.common(c)                              
                 }
             }                
         }   
@@ -166,10 +205,12 @@ impl<'a> Lexer<'a> {
             '}' => self.add_token(TokenValue::CloseCurly),
             ';' => self.add_token(TokenValue::Semicolon),
             ',' => self.add_token(TokenValue::Comma),
+            x if is_bidi_control(x) => (),  // already reported by next()
 
             '\u{1EE4D}' => self.add_token(TokenValue::Res),
             '⎔' => self.add_token(TokenValue::At),
             '؛' => self.add_token(TokenValue::Semicolon),
+            '،' => self.add_token(TokenValue::Comma),
             '⏎' => self.add_token(TokenValue::Ret),
             '✓' => self.add_token(TokenValue::Ok),
             '✗' => self.add_token(TokenValue::Err),
@@ -219,6 +260,8 @@ impl<'a> Lexer<'a> {
         self.space_indent = false;
         self.tab_indent = false;
         self.indent_conflict_reported = false;
+        self.in_text = false;
+        self.bidi_stack = vec![];
     }
 
 
@@ -247,6 +290,7 @@ impl<'a> Lexer<'a> {
 
         if let Some(c) = c {
             self.current = c;
+            let location = self.end_location.clone();
 
             match c {
                 '\n' => {
@@ -262,6 +306,10 @@ impl<'a> Lexer<'a> {
             } else {
                 self.look_ahead = NULL;
             }
+
+            if is_bidi_control(c) {
+                self.bidi_control(c, location);
+            }
         } else {
             self.current = NULL;
             self.look_ahead = NULL;
@@ -345,6 +393,15 @@ impl<'a> Lexer<'a> {
     }
 
 
+    //---------------------
+    //  expect_double_dot()
+    //---------------------        
+    // `1..5` is a range not the float `1.` followed by `.5`
+    fn expect_double_dot(&self) -> bool {
+        self.look_ahead == '.'
+        && self.iter.clone().nth(1) == Some('.')
+    }
+
     //---------------------
     //  expect_eof()
     //---------------------        
@@ -375,10 +432,85 @@ impl<'a> Lexer<'a> {
     }
 
 
+    //---------------------
+    //  insert_error_at()
+    //---------------------        
+    fn insert_error_at(&mut self, location: Location, msg: String) {
+        let mut end_location = location.clone();
+        end_location.column += 1;
+        self.errors.as_mut().unwrap().push(   
+            Error::new(
+                location,
+                end_location,
+                msg,
+            )
+        );
+    }
+
+    //---------------------
+    //  bidi_control()
+    //---------------------        
+    fn bidi_control(
+        &mut self, 
+        c: char,
+        location: Location
+    ) {
+        if !self.allow_bidi || !self.in_text {
+            self.insert_error_at(
+                location,
+                format!("bidirectional control character U+{:04X} is not allowed, it can make the code appear different from how it is compiled", c as u32)
+            );
+            return;
+        }
+
+        match c {
+            BIDI_LRE | BIDI_RLE | BIDI_LRO | BIDI_RLO | BIDI_LRI | BIDI_RLI | BIDI_FSI => self.bidi_stack.push((c, location)),
+            BIDI_PDF | BIDI_PDI => {
+                let expected = match self.bidi_stack.last() {
+                    Some((BIDI_LRI, _)) | Some((BIDI_RLI, _)) | Some((BIDI_FSI, _)) => BIDI_PDI,
+                    Some(_) => BIDI_PDF,
+                    None => NULL
+                };
+                if c == expected {
+                    self.bidi_stack.pop();
+                } else {
+                    self.insert_error_at(
+                        location,
+                        format!("unbalanced bidirectional control character U+{:04X}", c as u32)
+                    );
+                }
+            },
+            _ => ()
+        }
+    }
+
+    //---------------------
+    //  begin_text()
+    //---------------------        
+    // strings, characters, comments and embedded code are the only places bidi controls can be allowed in
+    fn begin_text(&mut self) {
+        self.in_text = true;
+        self.bidi_stack = vec![];
+    }
+
+    //---------------------
+    //  end_text()
+    //---------------------        
+    fn end_text(&mut self) {
+        self.in_text = false;
+        while let Some((c, location)) = self.bidi_stack.pop() {
+            self.insert_error_at(
+                location,
+                format!("unterminated bidirectional control character U+{:04X}", c as u32)
+            );
+        }
+    }
+
     //---------------------
     //  comment()
     //---------------------        
     fn comment(&mut self) {
+        self.begin_text();
         while !self.expect_eof() {
             if self.expect_eol() {
                 // self.next();
@@ -387,6 +519,7 @@ impl<'a> Lexer<'a> {
                 self.next();
             }
         }
+        self.end_text();
         // self.start_location = self.end_location.clone();
     }
 
@@ -398,6 +531,7 @@ impl<'a> Lexer<'a> {
         sym: char
     ) {
         let mut levels = vec![];
+        self.begin_text();
         while !self.expect_eof() {
             if self.expect(sym, '*') {
                 self.skip(2); 
@@ -415,6 +549,7 @@ impl<'a> Lexer<'a> {
                 self.skip(1)
             } // skip others
         }
+        self.end_text();
 
         if let Some(location) = levels.pop() {
             self.start_location = location;
@@ -474,9 +609,10 @@ impl<'a> Lexer<'a> {
     //  escape_character()
     //---------------------        
     fn escape_character(&mut self, c: char) -> char {
-        match self.lang {
-            Lang::Ar => self.ar_escape_character(c),
-            Lang::En => self.en_escape_character(c)
+        if self.lang.is_rtl() {
+            self.ar_escape_character(c)
+        } else {
+            self.en_escape_character(c)
         }
     }
 
@@ -530,6 +666,7 @@ impl<'a> Lexer<'a> {
     //---------------------        
     fn enclosed_value(&mut self, symbol: char) -> String {
         let mut value = String::from("");
+        self.begin_text();
 
         while !self.expect_eof() {
             self.next();
@@ -558,6 +695,7 @@ impl<'a> Lexer<'a> {
             let c = self.escape_character(self.current);
             value.push(c);
         }
+        self.end_text();
         value
     }
 
@@ -569,6 +707,7 @@ impl<'a> Lexer<'a> {
         close_sym: char
     ) {
         let mut value = String::from("");
+        self.begin_text();
 
         while !self.expect_eof() {
             self.next();
@@ -594,6 +733,7 @@ impl<'a> Lexer<'a> {
                 value.push(c);
             }
         }
+        self.end_text();
     }
 
     //---------------------
@@ -675,14 +815,11 @@ impl<'a> Lexer<'a> {
         let mut res = "".to_string();
         let mut line = "".to_string();
 
-        let terminator = match self.lang { 
-            Lang::Ar  => "اه",
-            Lang::En => "end"
-        };
+        let terminator = self.lexicon.word(self.lang, "code.end");
 
         while !self.expect_eof() {
             if self.start_location.column <= hash_col &&
-              line.trim() == terminator {
+              line.trim() == terminator.as_str() {
                 break;
             } else if self.expect_eol() {
                 line.push(self.next());
@@ -705,6 +842,7 @@ impl<'a> Lexer<'a> {
     //---------------------        
     fn code(&mut self, hash_col: usize) {
         let mut value = String::from("");
+        self.begin_text();
         while !self.expect_eof() {
             if self.expect_eol() {
                 if value.trim() == "" {
@@ -718,6 +856,7 @@ impl<'a> Lexer<'a> {
             }
             value.push(self.next());
         }
+        self.end_text();
     }
 
 
@@ -875,26 +1014,7 @@ impl<'a> Lexer<'a> {
 
         }
 
-        match v.as_str() {
-            "صواب"  => self.add_token(TokenValue::Bool(v)),
-            "خطا"  => self.add_token(TokenValue::Bool(v)),
-
-            "قيمة" => self.add_token(TokenValue::Some),
-            "بلا" => self.add_token(TokenValue::None),
-
-            "ليكن" => self.add_token(TokenValue::Let),
-            "في" => self.add_token(TokenValue::In),
-            "حيث" => self.add_token(TokenValue::Where),
-            "طابق" => self.add_token(TokenValue::Match),
-            "لكل" => self.add_token(TokenValue::For),
-            "بينما" => self.add_token(TokenValue::While),
-            "اذا" => self.add_token(TokenValue::If),
-            "والا" => self.add_token(TokenValue::Else),
-            // "احضر" => self.add_token(TokenValue::Use),
-            "_" => self.add_token(TokenValue::Underscore),
-            _ => self.add_token(TokenValue::Id(v)),
-        }
-
+        self.add_id_or_keyword(v);
     }
 
     //---------------------
@@ -910,27 +1030,45 @@ impl<'a> Lexer<'a> {
             }            
         }
 
-        match v.as_str() {
-            "true"  => self.add_token(TokenValue::Bool(v)),
-            "false"  => self.add_token(TokenValue::Bool(v)),
-
-            "Res" => self.add_token(TokenValue::Res),
-            "Ok" => self.add_token(TokenValue::Ok),
-            "Err" => self.add_token(TokenValue::Err),
-            "Some" => self.add_token(TokenValue::Some),
-            "None" => self.add_token(TokenValue::None),
-
-            "let" => self.add_token(TokenValue::Let),
-            "in" => self.add_token(TokenValue::In),
-            "where" => self.add_token(TokenValue::Where),
-            "match" => self.add_token(TokenValue::Match),
-            "for" => self.add_token(TokenValue::For),
-            "while" => self.add_token(TokenValue::While),
-            "if" => self.add_token(TokenValue::If),
-            "else" => self.add_token(TokenValue::Else),
-            "_" => self.add_token(TokenValue::Underscore),
-            _ => self.add_token(TokenValue::Id(v)),
+        self.add_id_or_keyword(v);
+    }
+
+    //---------------------
+    //  add_id_or_keyword()
+    //---------------------      
+    fn add_id_or_keyword(&mut self, v: String) {
+        if v == "_" {
+            self.add_token(TokenValue::Underscore);
+            return;
         }
+
+        let value = match self.lexicon.concept("kw", self.lang, &v) {
+            Some("kw.true") | Some("kw.false") => TokenValue::Bool(v),
+            Some("kw.res") => TokenValue::Res,
+            Some("kw.ok") => TokenValue::Ok,
+            Some("kw.err") => TokenValue::Err,
+            Some("kw.some") => TokenValue::Some,
+            Some("kw.none") => TokenValue::None,
+            Some("kw.let") => TokenValue::Let,
+            Some("kw.in") => TokenValue::In,
+            Some("kw.where") => TokenValue::Where,
+            Some("kw.match") => TokenValue::Match,
+            Some("kw.for") => TokenValue::For,
+            Some("kw.while") => TokenValue::While,
+            Some("kw.loop") => TokenValue::Loop,
+            Some("kw.break") => TokenValue::Break,
+            Some("kw.continue") => TokenValue::Continue,
+            Some("kw.ret") => TokenValue::Ret,
+            Some("kw.if") => TokenValue::If,
+            Some("kw.else") => TokenValue::Else,
+            Some("kw.as") => TokenValue::As,
+            Some("kw.fn") => TokenValue::Fn,
+            Some("kw.pub") => TokenValue::Pub,
+            Some("kw.internal") => TokenValue::Internal,
+            Some("kw.mut") => TokenValue::Mut,
+            _ => TokenValue::Id(v),
+        };
+        self.add_token(value);
     }
 
     //---------------------
@@ -938,9 +1076,10 @@ impl<'a> Lexer<'a> {
     //---------------------        
     fn id_or_keyword(&mut self) {
         
-        match self.lang {
-            Lang::Ar =>self.id_or_keyword_ar(),
-            Lang::En =>self.id_or_keyword_en()
+        if self.lang.is_rtl() {
+            self.id_or_keyword_ar()
+        } else {
+            self.id_or_keyword_en()
         }
     }
 
@@ -954,6 +1093,15 @@ impl<'a> Lexer<'a> {
                 value.push_str(self.fractional().as_str());
                 self.add_token(TokenValue::Float(value));
             }
+            '.' => {
+                self.skip(1);
+                if self.look_ahead == '=' {
+                    self.skip(1);
+                    self.add_token(TokenValue::DoubleDotEqual);
+                } else {
+                    self.add_token(TokenValue::DoubleDot);
+                }
+            }
             _ => self.add_token(TokenValue::Dot),
         }
     }
@@ -975,24 +1123,47 @@ impl<'a> Lexer<'a> {
         } 
     }
 
+    //---------------------
+    //  is_native_digit()
+    //---------------------        
+    fn is_native_digit(&self, c: char) -> bool {
+        match self.lang.native_digits() {
+            Some((first, last)) => (first..=last).contains(&c),
+            None => false
+        }
+    }
+
+    //---------------------
+    //  native_digits()
+    //---------------------        
+    // the native digits range, for error messages
+    fn native_digits(&self) -> String {
+        match self.lang.native_digits() {
+            Some((first, last)) => format!("native digits ({} - {})", first, last),
+            None => "Western digits (0 - 9)".to_string()
+        }
+    }
+
     //---------------------
     //  digit_eastern()
     //---------------------        
     fn digit_eastern(&mut self) -> (String, bool) {
         let mut v = String::from(self.current);
         let mut expect_float = false;
+        let decimal_sep = self.lang.decimal_sep();
         while !self.expect_eof() {
             match self.look_ahead {
-                '٠'..='٩' => v.push(self.next()),
-                ',' => {
+                x if self.is_native_digit(x) => v.push(self.next()),
+                x if x == decimal_sep && !self.expect_double_dot() => {
                     v.push(self.next());
                     expect_float = true;
                     v.push_str(self.fractional().as_str());
                 },
-                '0'..='9' => {
+                x if x.is_ascii_digit() || is_eastern_digit(x) => {
                     self.insert_error(
                         format!(
-                            "you can either use Eastern Arabic digits (٠ - ٩) or Western (0 - 9) but not a mix: {}", 
+                            "you can either use {} or Western (0 - 9) but not a mix: {}", 
+                            self.native_digits(),
                             self.current
                         )
                     );
@@ -1013,15 +1184,16 @@ impl<'a> Lexer<'a> {
         while !self.expect_eof() {
             match self.look_ahead {
                 '0'..='9' => v.push(self.next()),
-                '.' => {
+                '.' if !self.expect_double_dot() => {
                     v.push(self.next());
                     expect_float = true;
                     v.push_str(self.fractional().as_str());
                 },
-                '٠'..='٩' => {
+                x if is_eastern_digit(x) => {
                     self.insert_error(
                         format!(
-                            "you can either use Eastern Arabic digits (٠ - ٩) or Western (0 - 9) but not a mix: {}", 
+                            "you can either use {} or Western (0 - 9) but not a mix: {}", 
+                            self.native_digits(),
                             self.current
                         )
                     );
@@ -1042,7 +1214,7 @@ impl<'a> Lexer<'a> {
         while !self.expect_eof() {
             match self.look_ahead {
                 '0'..='9' => v.push(self.next()),
-                '.' => {
+                '.' if !self.expect_double_dot() => {
                     v.push(self.next());
                     expect_float = true;
                     v.push_str(self.fractional().as_str());
@@ -1140,4 +1312,14 @@ impl<'a> Lexer<'a> {
         }
     }
 
-}
\ No newline at end of file
+}
+
+//================
+//   is_bidi_control()
+//================
+pub fn is_bidi_control(c: char) -> bool {
+    matches!(
+        c, 
+        BIDI_LRE | BIDI_RLE | BIDI_PDF | BIDI_LRO | BIDI_RLO | BIDI_LRI | BIDI_RLI | BIDI_FSI | BIDI_PDI
+    )
+}
@@ -0,0 +1,584 @@
+use std::collections::HashMap;
+
+use super::{
+    token::{
+        Token,
+        TokenValue
+    },
+    syntax_tree::ast::*,
+    error::{
+        Error,
+    },
+};
+
+use crate::{
+    error,
+    transl::lexicon::Lexicon
+};
+
+//================
+//   Constants
+//================
+// methods of the standard library that change the value they are called on, the types of locals
+//  are not known yet so a call to one of them is enough to make a local mutable
+const STD_MUT_METHODS: &[&str] = &[
+    "push", "push_str", "pop", "insert", "remove", "clear", "append", "extend", "truncate",
+    "retain", "drain", "sort", "sort_by", "dedup", "reverse", "swap"
+];
+
+//================
+//   SelfUse
+//================
+// what a method does with `self`, the first place it does it
+struct SelfUse {
+    mutated: Option<Token>,
+    moved: Option<Token>
+}
+
+impl SelfUse {
+    //---------------------
+    //  new()
+    //---------------------
+    fn new() -> Self {
+        Self {
+            mutated: None,
+            moved: None
+        }
+    }
+
+    //---------------------
+    //  receiver()
+    //---------------------
+    fn receiver(&self) -> Receiver {
+        match (&self.moved, &self.mutated) {
+            (Some(_), Some(_)) => Receiver::MutOwned,
+            (Some(_), None) => Receiver::Owned,
+            (None, Some(_)) => Receiver::MutRef,
+            (None, None) => Receiver::Ref
+        }
+    }
+}
+
+//================
+//   Mutability
+//================
+// decides how every method takes `self` and which locals are mutated, so only what changes is
+//  written `mut` in the generated rust
+pub struct Mutability<'a> {
+    lexicon: &'a Lexicon,
+    methods: HashMap<String, Receiver>,     // the receivers of the methods of the module, by method name
+    scopes: Vec<Vec<(String, usize)>>,      // the names in scope, innermost last, with their index in `mutated`
+    mutated: Vec<bool>,
+    self_use: SelfUse
+}
+
+impl<'a> Mutability<'a> {
+    //---------------------
+    //  new()
+    //---------------------
+    pub fn new() -> Self {
+        Self {
+            lexicon: Lexicon::embedded(),
+            methods: HashMap::new(),
+            scopes: vec![],
+            mutated: vec![],
+            self_use: SelfUse::new()
+        }
+    }
+
+    //---------------------
+    //  lexicon()
+    //---------------------
+    pub fn lexicon(&mut self, lexicon: &'a Lexicon) {
+        self.lexicon = lexicon;
+    }
+
+    //---------------------
+    //  check()
+    //---------------------
+    // the receivers are inferred again until none of them changes, a method that calls a `&mut self`
+    //  method on `self` needs `&mut self` too, receivers given by `@ref` / `@mut_ref` / `@own` are kept
+    pub fn check(
+        &mut self,
+        ast: &mut Vec<ModElement>,
+    ) -> Vec<Error> {
+        self.methods.clear();
+        for _fn in methods(ast) {
+            self.add_method(_fn, _fn.receiver.unwrap_or(Receiver::Ref));
+        }
+
+        loop {
+            let before = self.methods.clone();
+            for _fn in methods_mut(ast).filter(|_fn| _fn.receiver.is_none()) {
+                let receiver = self.body(_fn).receiver();
+                self.add_method(_fn, receiver);
+            }
+            if self.methods == before { break }
+        }
+
+        let mut errors = vec![];
+        for el in ast.iter_mut() {
+            match el {
+                ModElement::Decl(decl) => {
+                    if let Some(expr) = &mut decl.expr {
+                        self.scopes = vec![];
+                        self.expr(expr);
+                    }
+                },
+                ModElement::MainFn(_fn)
+                | ModElement::Fn(_fn) => {
+                    self.body(_fn);
+                },
+                ModElement::StructImpl(struct_impl) => {
+                    for _fn in struct_impl.fns.iter_mut() {
+                        let self_use = self.body(_fn);
+                        if _fn.is_method {
+                            self.receiver(_fn, &self_use, &mut errors);
+                        }
+                    }
+                },
+                ModElement::Struct(_struct) => {
+                    for field in _struct.fields.iter_mut().flatten() {
+                        if let Some(default) = &mut field.default {
+                            self.scopes = vec![];
+                            self.expr(default);
+                        }
+                    }
+                },
+                _ => ()
+            }
+        }
+        errors
+    }
+
+    //---------------------
+    //  add_method()
+    //---------------------
+    // methods of different structs can share a name, the call takes the most demanding receiver
+    fn add_method(
+        &mut self,
+        _fn: &Fn,
+        receiver: Receiver
+    ) {
+        let Some(name) = &_fn.name else { return };
+        let entry = self.methods
+            .entry(name.value.to_string())
+            .or_insert(receiver);
+        if receiver > *entry {
+            *entry = receiver;
+        }
+    }
+}
+
+//================
+//   methods()
+//================
+fn methods(ast: &Vec<ModElement>) -> impl Iterator<Item = &Fn> {
+    ast.iter()
+        .filter_map(|el| match el {
+            ModElement::StructImpl(struct_impl) => Some(struct_impl.fns.iter()),
+            _ => None
+        })
+        .flatten()
+        .filter(|_fn| _fn.is_method)
+}
+
+//================
+//   methods_mut()
+//================
+fn methods_mut(ast: &mut Vec<ModElement>) -> impl Iterator<Item = &mut Fn> {
+    ast.iter_mut()
+        .filter_map(|el| match el {
+            ModElement::StructImpl(struct_impl) => Some(struct_impl.fns.iter_mut()),
+            _ => None
+        })
+        .flatten()
+        .filter(|_fn| _fn.is_method)
+}
+
+//================
+//   receiver()
+//================
+// an inferred receiver is written on the method, a given one is checked against the body
+impl<'a> Mutability<'a> {
+    fn receiver(
+        &mut self,
+        _fn: &mut Fn,
+        self_use: &SelfUse,
+        errors: &mut Vec<Error>
+    ) {
+        match (_fn.receiver, &self_use.mutated, &self_use.moved) {
+            (None, _, _) => _fn.receiver = Some(self_use.receiver()),
+            (Some(Receiver::Ref), Some(t), _) => errors.push(
+                error!("`self` is changed in a method that takes it by reference, use `@mut_ref`".to_string(), t)
+            ),
+            (Some(Receiver::Ref | Receiver::MutRef), _, Some(t)) => errors.push(
+                error!("`self` is moved out of a method that takes it by reference, use `@own`".to_string(), t)
+            ),
+            (Some(Receiver::Owned), Some(_), _) => _fn.receiver = Some(Receiver::MutOwned),
+            _ => ()
+        }
+    }
+}
+
+//================
+//   body()
+//================
+// every function starts with no names in scope
+impl<'a> Mutability<'a> {
+    fn body(
+        &mut self,
+        _fn: &mut Fn
+    ) -> SelfUse {
+        self.scopes = vec![];
+        self.mutated = vec![];
+        self.self_use = SelfUse::new();
+        self._fn(_fn);
+        std::mem::replace(&mut self.self_use, SelfUse::new())
+    }
+}
+
+//================
+//   _fn()
+//================
+// a lambda shares the scopes around it, a local it changes is mutable where it is declared
+impl<'a> Mutability<'a> {
+    fn _fn(
+        &mut self,
+        _fn: &mut Fn
+    ) {
+        self.scopes.push(vec![]);
+        let params = _fn.params
+            .iter()
+            .map(|param| self.bind(&param.pat))
+            .collect::<Vec<_>>();
+        let mut where_decls = vec![];
+        for decl in _fn.where_decls.iter_mut() {
+            if let Some(expr) = &mut decl.expr {
+                self.expr(expr);
+            }
+            where_decls.push(self.bind(&decl.pattern));
+        }
+        self.block(&mut _fn.block);
+        self.scopes.pop();
+
+        for (param, slots) in _fn.params.iter_mut().zip(params) {
+            param.is_mut |= self.any_mutated(&slots);
+        }
+        for (decl, slots) in _fn.where_decls.iter_mut().zip(where_decls) {
+            decl.is_mut |= self.any_mutated(&slots);
+        }
+    }
+}
+
+//================
+//   block()
+//================
+impl<'a> Mutability<'a> {
+    fn block(
+        &mut self,
+        block: &mut Vec<BlockElement>
+    ) {
+        self.scopes.push(vec![]);
+        let mut decls = vec![];
+        for (i, el) in block.iter_mut().enumerate() {
+            match el {
+                BlockElement::Decl(decl) => {
+                    if let Some(expr) = &mut decl.expr {
+                        self.expr(expr);
+                    }
+                    decls.push((i, self.bind(&decl.pattern)));
+                },
+                BlockElement::Expr(expr) => self.expr(expr),
+                BlockElement::Ret(Ret{expr: Some(expr), ..}) => {
+                    if let Expr::Ref(t) = &**expr {
+                        if self.is_self(t) {
+                            self.self_use.moved.get_or_insert(t.clone());
+                        }
+                    }
+                    self.expr(expr);
+                },
+                BlockElement::Ret(_)
+                | BlockElement::MainArgs => ()
+            }
+        }
+        self.scopes.pop();
+
+        for (i, slots) in decls {
+            if let BlockElement::Decl(decl) = &mut block[i] {
+                decl.is_mut |= self.any_mutated(&slots);
+            }
+        }
+    }
+}
+
+//================
+//   expr()
+//================
+impl<'a> Mutability<'a> {
+    fn expr(
+        &mut self,
+        expr: &mut Expr
+    ) {
+        match expr {
+            Expr::Fn(_fn) => self._fn(_fn),
+            Expr::LetIn(let_in) => {
+                if let Some(expr) = &mut let_in.decl.expr {
+                    self.expr(expr);
+                }
+                self.scopes.push(vec![]);
+                let slots = self.bind(&let_in.decl.pattern);
+                self.expr(&mut let_in.expr);
+                self.scopes.pop();
+                let_in.decl.is_mut |= self.any_mutated(&slots);
+            },
+            Expr::For(_for) => {
+                self.expr(&mut _for.in_expr.expr);
+                self.scopes.push(vec![]);
+                let _ = self.bind(&_for.in_expr.pattern);
+                self.block(&mut _for.block);
+                self.scopes.pop();
+            },
+            Expr::While(_while) => {
+                self.expr(&mut _while.expr);
+                self.block(&mut _while.block);
+            },
+            Expr::Loop(_loop) => self.block(&mut _loop.block),
+            Expr::Break(_break) => {
+                if let Some(expr) = &mut _break.expr {
+                    self.expr(expr);
+                }
+            },
+            Expr::Match(_match) => {
+                self.expr(&mut _match.expr);
+                for arm in _match.arms.iter_mut() {
+                    self.scopes.push(vec![]);
+                    let _ = self.bind(&arm.pattern);
+                    if let Some(guard) = &mut arm.guard {
+                        self.expr(guard);
+                    }
+                    self.block(&mut arm.block);
+                    self.scopes.pop();
+                }
+            },
+            Expr::If(_if) => {
+                for branch in _if.branches.iter_mut() {
+                    if let Some(expr) = &mut branch.expr {
+                        self.expr(expr);
+                    }
+                    self.block(&mut branch.block);
+                }
+            },
+            Expr::BinOp(bin_op) if bin_op.is_access() => {
+                self.access(bin_op);
+                self.expr(&mut bin_op.l_opr);
+                self.segments(&mut bin_op.r_opr);
+            },
+            Expr::BinOp(bin_op) => {
+                if is_assign(&bin_op.op.value) {
+                    self.mutate(&bin_op.l_opr);
+                }
+                self.expr(&mut bin_op.l_opr);
+                self.expr(&mut bin_op.r_opr);
+            },
+            Expr::StructLiteral(literal) => {
+                for (_, value) in literal.items.iter_mut() {
+                    if let Some(value) = value {
+                        self.expr(value);
+                    }
+                }
+                if let Some(base) = &mut literal.base {
+                    self.expr(base);
+                }
+            },
+            Expr::Range(range) => {
+                if let Some(start) = &mut range.start {
+                    self.expr(start);
+                }
+                if let Some(end) = &mut range.end {
+                    self.expr(end);
+                }
+            },
+            Expr::Map(map) => {
+                for (key, value) in map.items.iter_mut() {
+                    self.expr(key);
+                    self.expr(value);
+                }
+            },
+            Expr::Cast(cast) => self.expr(&mut cast.expr),
+            Expr::PreUniOp(uni_op)
+            | Expr::PostUniOp(uni_op) => self.expr(&mut uni_op.opr),
+            Expr::List(List{items})
+            | Expr::Tuple(Tuple{items}) => {
+                for item in items.iter_mut() {
+                    self.expr(item);
+                }
+            },
+            Expr::Ok(expr)
+            | Expr::Err(expr)
+            | Expr::Some(expr) => self.expr(expr),
+            _ => ()
+        }
+    }
+}
+
+//================
+//   access()
+//================
+// `x.push(1)`, `self.items.clear()`: the last segment of an access chain is the method that is called
+//  on the value, or an assignment as `self.n += 1` is read `self.(n += 1)`
+impl<'a> Mutability<'a> {
+    fn access(
+        &mut self,
+        bin_op: &BinOp
+    ) {
+        let mut last = &*bin_op.r_opr;
+        while let Expr::BinOp(segment) = last {
+            if !segment.is_access() { break }
+            last = &segment.r_opr;
+        }
+        let Expr::BinOp(call) = last else { return };
+        if is_assign(&call.op.value) {
+            self.mutate(&bin_op.l_opr);
+            return
+        }
+        let (Expr::Ref(method), Expr::Tuple(_)) = (&*call.l_opr, &*call.r_opr) else { return };
+
+        let name = method.value.to_string();
+        let receiver = self.methods.get(&name).copied();
+        if receiver.is_some_and(|r| r.is_mut()) || STD_MUT_METHODS.contains(&name.as_str()) {
+            self.mutate(&bin_op.l_opr);
+        }
+        if receiver.is_some_and(|r| r.is_owned()) {
+            if let Expr::Ref(t) = &*bin_op.l_opr {
+                if self.is_self(t) {
+                    self.self_use.moved.get_or_insert(t.clone());
+                }
+            }
+        }
+    }
+}
+
+//================
+//   segments()
+//================
+// the names in an access chain are fields and methods, only the arguments and the indexes are walked
+impl<'a> Mutability<'a> {
+    fn segments(
+        &mut self,
+        expr: &mut Expr
+    ) {
+        match expr {
+            Expr::BinOp(bin_op) if bin_op.is_access() => {
+                self.segments(&mut bin_op.l_opr);
+                self.segments(&mut bin_op.r_opr);
+            },
+            Expr::BinOp(bin_op) => self.expr(&mut bin_op.r_opr),
+            Expr::Ref(_) => (),
+            _ => self.expr(expr)
+        }
+    }
+}
+
+//================
+//   mutate()
+//================
+// `x = ..`, `x.a += ..`, `x[i] = ..` all change `x`
+impl<'a> Mutability<'a> {
+    fn mutate(
+        &mut self,
+        expr: &Expr
+    ) {
+        let Some(t) = root(expr) else { return };
+        if self.is_self(t) {
+            self.self_use.mutated.get_or_insert(t.clone());
+            return
+        }
+        let name = t.value.to_string();
+        let slot = self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .find(|(n, _)| *n == name)
+            .map(|(_, slot)| *slot);
+        if let Some(slot) = slot {
+            self.mutated[slot] = true;
+        }
+    }
+}
+
+//================
+//   bind()
+//================
+// declares the names of a pattern in the innermost scope
+impl<'a> Mutability<'a> {
+    fn bind(
+        &mut self,
+        pattern: &Pattern
+    ) -> Vec<usize> {
+        let mut slots = vec![];
+        for id in pattern.bindings() {
+            let slot = self.mutated.len();
+            self.mutated.push(false);
+            self.scopes
+                .last_mut()
+                .unwrap()
+                .push((id.value.to_string(), slot));
+            slots.push(slot);
+        }
+        slots
+    }
+}
+
+//================
+//   any_mutated()
+//================
+impl<'a> Mutability<'a> {
+    fn any_mutated(
+        &self,
+        slots: &Vec<usize>
+    ) -> bool {
+        slots.iter().any(|slot| self.mutated[*slot])
+    }
+}
+
+//================
+//   is_self()
+//================
+impl<'a> Mutability<'a> {
+    fn is_self(
+        &self,
+        t: &Token
+    ) -> bool {
+        self.lexicon.concept_any("kw", &t.value.to_string()) == Some("kw.self")
+    }
+}
+
+//================
+//   root()
+//================
+// the local an access or an index starts from
+fn root(expr: &Expr) -> Option<&Token> {
+    match expr {
+        Expr::Ref(t) => Some(t),
+        Expr::BinOp(bin_op) if bin_op.is_access() || matches!(bin_op.op.value, TokenValue::OpenBracket) => root(&bin_op.l_opr),
+        _ => None
+    }
+}
+
+//================
+//   is_assign()
+//================
+fn is_assign(op: &TokenValue) -> bool {
+    matches!(
+        op,
+        TokenValue::Equal
+        | TokenValue::AddEqual
+        | TokenValue::SubEqual
+        | TokenValue::MulEqual
+        | TokenValue::DivEqual
+        | TokenValue::BitwiseAndEqual
+        | TokenValue::BitwiseOrEqual
+        | TokenValue::BitwiseXorEqual
+    )
+}
@@ -0,0 +1,997 @@
+use std::collections::HashMap;
+
+use super::{
+    token::{
+        Token,
+        TokenValue
+    },
+    syntax_tree::ast::*,
+    error::{
+        Error,
+    },
+};
+
+use crate::{
+    error,
+    transl::lexicon::Lexicon
+};
+
+//================
+//   Constants
+//================
+// methods of the standard library that only look at their arguments, a string literal is passed as `&str`
+//  and any other value is borrowed
+const STD_BORROW_METHODS: &[&str] = &[
+    "contains", "contains_key", "starts_with", "ends_with", "push_str", "split"
+];
+
+//================
+//   Slot
+//================
+// a local of the function being lowered
+struct Slot {
+    copy: bool,             // a number or a bool, given away without a `.clone()`
+    elem_copy: bool,        // a list or a map of copy values, indexing it does not need a `.clone()`
+    str_ref: bool,          // a `str` param, written as a `&str`, it is given away as a `.to_string()`
+    loop_depth: usize       // the number of loops around the declaration
+}
+
+//================
+//   Sig
+//================
+// what the places a function or a method is called at need to know of it
+struct Sig {
+    str_params: Vec<bool>,      // the params written as `&str`, the arguments given to them are borrowed
+    ret_type: Option<Type>
+}
+
+//================
+//   Ownership
+//================
+// lowers the AST before it is written as rust: string literals that are only read become `&str`, values
+//  that are used again after they are given away are cloned, and the few moves that can not be cloned
+//  are reported, so the users never see an error of the rust borrow checker
+// a function is walked twice in the same order, the first walk records where every local is used and
+//  the second one rewrites the moves that have a later use
+pub struct Ownership<'a> {
+    lexicon: &'a Lexicon,
+    methods: HashMap<String, (Receiver, bool)>,     // the receivers of the methods, and if they were written with `@own`
+    fns: HashMap<String, Sig>,                      // the functions of the module, by name
+    method_sigs: HashMap<String, Sig>,              // the methods of the module, by name
+    fields: HashMap<String, StructFields>,          // the fields of the structs of the module, by struct name
+    owner: Option<String>,                          // the struct of the method being lowered
+    scopes: Vec<Vec<(String, usize)>>,              // the names in scope, innermost last, with their index in `slots`
+    slots: Vec<Slot>,
+    uses: Vec<Vec<usize>>,                          // the positions every slot is used at, found by the first walk
+    pos: usize,                                     // the number of names walked so far
+    loop_depth: usize,
+    rewrite: bool,
+    errors: Vec<Error>
+}
+
+impl<'a> Ownership<'a> {
+    //---------------------
+    //  new()
+    //---------------------
+    pub fn new() -> Self {
+        Self {
+            lexicon: Lexicon::embedded(),
+            methods: HashMap::new(),
+            fns: HashMap::new(),
+            method_sigs: HashMap::new(),
+            fields: HashMap::new(),
+            owner: None,
+            scopes: vec![],
+            slots: vec![],
+            uses: vec![],
+            pos: 0,
+            loop_depth: 0,
+            rewrite: false,
+            errors: vec![]
+        }
+    }
+
+    //---------------------
+    //  lexicon()
+    //---------------------
+    pub fn lexicon(&mut self, lexicon: &'a Lexicon) {
+        self.lexicon = lexicon;
+    }
+
+    //---------------------
+    //  lower()
+    //---------------------
+    // runs after the mutability pass, the receivers of the methods are known
+    pub fn lower(
+        &mut self,
+        ast: &mut Vec<ModElement>,
+    ) -> Vec<Error> {
+        self.methods.clear();
+        self.fns.clear();
+        self.method_sigs.clear();
+        self.fields.clear();
+        self.errors = vec![];
+        for el in ast.iter() {
+            match el {
+                ModElement::StructImpl(struct_impl) => {
+                    for _fn in struct_impl.fns.iter().filter(|_fn| _fn.is_method) {
+                        if let (Some(name), Some(receiver)) = (&_fn.name, _fn.receiver) {
+                            let is_own = self.has_attr("attr.own", &_fn.attrs);
+                            self.methods.insert(name.value.to_string(), (receiver, is_own));
+                        }
+                        if let Some(name) = &_fn.name {
+                            let sig = self.sig(_fn);
+                            self.method_sigs.insert(name.value.to_string(), sig);
+                        }
+                    }
+                },
+                ModElement::Fn(_fn) => {
+                    if let Some(name) = &_fn.name {
+                        let sig = self.sig(_fn);
+                        self.fns.insert(name.value.to_string(), sig);
+                    }
+                },
+                ModElement::Struct(_struct) => {
+                    let fields = _struct.fields.clone().unwrap_or(vec![]);
+                    self.fields.insert(_struct.name.value.to_string(), fields);
+                },
+                _ => ()
+            }
+        }
+
+        for el in ast.iter_mut() {
+            match el {
+                ModElement::MainFn(_fn)
+                | ModElement::Fn(_fn) => self.body(_fn, None),
+                ModElement::StructImpl(struct_impl) => {
+                    let owner = struct_impl.name.value.to_string();
+                    for _fn in struct_impl.fns.iter_mut() {
+                        self.body(_fn, Some(owner.clone()));
+                    }
+                },
+                _ => ()
+            }
+        }
+        std::mem::take(&mut self.errors)
+    }
+
+    //---------------------
+    //  has_attr()
+    //---------------------
+    fn has_attr(
+        &self,
+        concept: &str,
+        attrs: &Option<Vec<Attr>>
+    ) -> bool {
+        attrs
+            .iter()
+            .flatten()
+            .any(|attr| self.lexicon.concept_any("attr", &attr.name().value.to_string()) == Some(concept))
+    }
+
+    //---------------------
+    //  sig()
+    //---------------------
+    fn sig(
+        &self,
+        _fn: &Fn
+    ) -> Sig {
+        Sig {
+            str_params: _fn.params
+                .iter()
+                .map(|param| param._type.as_ref().is_some_and(|_type| self.is_str_type(_type)))
+                .collect(),
+            ret_type: _fn.ret_type.clone()
+        }
+    }
+}
+
+//================
+//   body()
+//================
+impl<'a> Ownership<'a> {
+    fn body(
+        &mut self,
+        _fn: &mut Fn,
+        owner: Option<String>
+    ) {
+        self.owner = owner;
+        for rewrite in [false, true] {
+            self.rewrite = rewrite;
+            self.scopes = vec![];
+            self.slots = vec![];
+            self.pos = 0;
+            self.loop_depth = 0;
+            if !rewrite {
+                self.uses = vec![];
+            }
+            self._fn(_fn, true);
+        }
+    }
+}
+
+//================
+//   _fn()
+//================
+// a lambda can be called many times, what it gives away is cloned as in a loop
+// the `str` params of functions and methods are `&str`, the ones of lambdas are `String`
+impl<'a> Ownership<'a> {
+    fn _fn(
+        &mut self,
+        _fn: &mut Fn,
+        is_item: bool
+    ) {
+        self.scopes.push(vec![]);
+        for param in _fn.params.iter() {
+            let (copy, elem_copy) = match &param._type {
+                Some(_type) => (self.is_copy_type(_type), self.is_copy_elem_type(_type)),
+                None => (false, false)
+            };
+            self.bind(&param.pat, copy, elem_copy);
+            let str_ref = is_item && param._type.as_ref().is_some_and(|_type| self.is_str_type(_type));
+            if let (true, Pattern::Id(_), Some(slot)) = (str_ref, &param.pat, self.slots.last_mut()) {
+                slot.str_ref = true;
+            }
+        }
+        for decl in _fn.where_decls.iter_mut() {
+            self.decl(decl);
+        }
+        self.block(&mut _fn.block);
+        self.scopes.pop();
+    }
+}
+
+//================
+//   block()
+//================
+impl<'a> Ownership<'a> {
+    fn block(
+        &mut self,
+        block: &mut Vec<BlockElement>
+    ) {
+        self.scopes.push(vec![]);
+        for el in block.iter_mut() {
+            match el {
+                BlockElement::Decl(decl) => self.decl(decl),
+                BlockElement::Expr(expr) => self.expr(expr),
+                BlockElement::Ret(Ret{expr: Some(expr), ..}) => self.moved(expr),
+                BlockElement::Ret(_)
+                | BlockElement::MainArgs => ()
+            }
+        }
+        self.scopes.pop();
+    }
+}
+
+//================
+//   decl()
+//================
+impl<'a> Ownership<'a> {
+    fn decl(
+        &mut self,
+        decl: &mut Decl
+    ) {
+        if let Some(expr) = &mut decl.expr {
+            self.moved(expr);
+        }
+        // a local without a type takes the one of the function it is returned from
+        let _type = decl._type.clone().or_else(|| decl.expr.as_ref().and_then(|expr| self.ret_type(expr)));
+        let (copy, elem_copy) = match (&_type, &decl.expr) {
+            (Some(_type), _) => (self.is_copy_type(_type), self.is_copy_elem_type(_type)),
+            (None, Some(Expr::List(List{items}))) => (false, items.first().is_some_and(is_copy_literal)),
+            (None, Some(expr)) => (is_copy_literal(expr), false),
+            (None, None) => (false, false)
+        };
+        self.bind(&decl.pattern, copy, elem_copy);
+    }
+}
+
+//================
+//   expr()
+//================
+// an expression whose value is only read
+impl<'a> Ownership<'a> {
+    fn expr(
+        &mut self,
+        expr: &mut Expr
+    ) {
+        match expr {
+            Expr::Ref(t) => self.use_name(t),
+            Expr::Fn(_fn) => {
+                self.loop_depth += 1;
+                self._fn(_fn, false);
+                self.loop_depth -= 1;
+            },
+            Expr::LetIn(let_in) => {
+                self.scopes.push(vec![]);
+                self.decl(&mut let_in.decl);
+                self.expr(&mut let_in.expr);
+                self.scopes.pop();
+            },
+            Expr::For(_for) => {
+                self.moved(&mut _for.in_expr.expr);
+                let copy = matches!(*_for.in_expr.expr, Expr::Range(_));
+                self.loop_depth += 1;
+                self.scopes.push(vec![]);
+                self.bind(&_for.in_expr.pattern, copy, false);
+                self.block(&mut _for.block);
+                self.scopes.pop();
+                self.loop_depth -= 1;
+            },
+            Expr::While(_while) => {
+                self.loop_depth += 1;
+                self.expr(&mut _while.expr);
+                self.block(&mut _while.block);
+                self.loop_depth -= 1;
+            },
+            Expr::Loop(_loop) => {
+                self.loop_depth += 1;
+                self.block(&mut _loop.block);
+                self.loop_depth -= 1;
+            },
+            Expr::Break(_break) => {
+                if let Some(expr) = &mut _break.expr {
+                    self.moved(expr);
+                }
+            },
+            Expr::Match(_match) => {
+                // binding a part of the value by name moves it out of the matched value
+                let binds = _match.arms.iter().any(|arm| !arm.pattern.bindings().is_empty());
+                if binds {
+                    self.moved(&mut _match.expr);
+                } else {
+                    self.expr(&mut _match.expr);
+                }
+                for arm in _match.arms.iter_mut() {
+                    self.scopes.push(vec![]);
+                    self.bind(&arm.pattern, false, false);
+                    if let Some(guard) = &mut arm.guard {
+                        self.expr(guard);
+                    }
+                    self.block(&mut arm.block);
+                    self.scopes.pop();
+                }
+            },
+            Expr::If(_if) => {
+                for branch in _if.branches.iter_mut() {
+                    if let Some(expr) = &mut branch.expr {
+                        self.expr(expr);
+                    }
+                    self.block(&mut branch.block);
+                }
+            },
+            Expr::BinOp(bin_op) if bin_op.is_access() => self.access(bin_op),
+            Expr::BinOp(bin_op) if is_call(bin_op) => self.call(bin_op),
+            Expr::BinOp(bin_op) => self.bin_op(bin_op),
+            Expr::StructLiteral(literal) => {
+                for (name, value) in literal.items.iter_mut() {
+                    match value {
+                        Some(value) => self.moved(value),
+                        None => {
+                            // `{ x, }` gives `x` away, it is written in full when `x` has to be cloned
+                            let mut shorthand = Expr::Ref(name.clone());
+                            self.moved(&mut shorthand);
+                            if matches!(shorthand, Expr::Clone(_) | Expr::Owned(_)) {
+                                *value = Some(shorthand);
+                            }
+                        }
+                    }
+                }
+                if let Some(base) = &mut literal.base {
+                    self.moved(base);
+                }
+            },
+            Expr::Range(range) => {
+                if let Some(start) = &mut range.start {
+                    self.expr(start);
+                }
+                if let Some(end) = &mut range.end {
+                    self.expr(end);
+                }
+            },
+            Expr::Map(map) => {
+                for (key, value) in map.items.iter_mut() {
+                    self.moved(key);
+                    self.moved(value);
+                }
+            },
+            Expr::List(List{items})
+            | Expr::Tuple(Tuple{items}) => {
+                for item in items.iter_mut() {
+                    self.moved(item);
+                }
+            },
+            Expr::Ok(expr)
+            | Expr::Err(expr)
+            | Expr::Some(expr) => self.moved(expr),
+            Expr::Cast(cast) => self.expr(&mut cast.expr),
+            Expr::PreUniOp(uni_op)
+            | Expr::PostUniOp(uni_op) => self.expr(&mut uni_op.opr),
+            Expr::Borrow(expr)
+            | Expr::Clone(expr) => self.expr(expr),
+            _ => ()
+        }
+    }
+}
+
+//================
+//   bin_op()
+//================
+// `a + b` gives `a` away when it is a string and borrows `b`, a literal on the right of a `+` or a comparison is a `&str`
+impl<'a> Ownership<'a> {
+    fn bin_op(
+        &mut self,
+        bin_op: &mut BinOp
+    ) {
+        match bin_op.op.value {
+            TokenValue::Equal => {
+                // the old value is dropped, assigning is not a use
+                if !matches!(*bin_op.l_opr, Expr::Ref(_)) {
+                    self.expr(&mut bin_op.l_opr);
+                }
+                self.moved(&mut bin_op.r_opr);
+            },
+            TokenValue::Add => {
+                self.moved(&mut bin_op.l_opr);
+                self.added(&mut bin_op.r_opr);
+            },
+            TokenValue::AddEqual => {
+                self.expr(&mut bin_op.l_opr);
+                self.added(&mut bin_op.r_opr);
+            },
+            TokenValue::Eq
+            | TokenValue::NE
+            | TokenValue::LT
+            | TokenValue::LE
+            | TokenValue::GT
+            | TokenValue::GE => {
+                self.read_str(&mut bin_op.l_opr);
+                self.read_str(&mut bin_op.r_opr);
+            },
+            _ => {
+                self.expr(&mut bin_op.l_opr);
+                self.expr(&mut bin_op.r_opr);
+            }
+        }
+    }
+}
+
+//================
+//   call()
+//================
+// `f(a, b)` gives its arguments away, unless they are `str` params of `f`, `println` only reads them
+impl<'a> Ownership<'a> {
+    fn call(
+        &mut self,
+        call: &mut BinOp
+    ) {
+        let (concept, str_params) = match &*call.l_opr {
+            Expr::Ref(t) => (
+                self.lexicon.concept_any("fn", &t.value.to_string()),
+                self.fns.get(&t.value.to_string()).map(|sig| sig.str_params.clone()).unwrap_or_default()
+            ),
+            _ => (None, vec![])
+        };
+        self.expr(&mut call.l_opr);
+        let Expr::Tuple(args) = &mut *call.r_opr else { return };
+        for (i, arg) in args.items.iter_mut().enumerate() {
+            match concept {
+                Some("fn.println" | "fn.print") => self.read_str(arg),
+                Some("fn.import") => (),
+                _ if str_params.get(i) == Some(&true) => self.borrowed(arg),
+                _ => self.moved(arg)
+            }
+        }
+    }
+}
+
+//================
+//   access()
+//================
+// `x.a.b`, `x.f(y)`: the names after the dots are fields and methods, not locals. a method that takes
+//  `self` gives `x` away, it is cloned when `x` is used again, unless the method was written with `@own`
+impl<'a> Ownership<'a> {
+    fn access(
+        &mut self,
+        bin_op: &mut BinOp
+    ) {
+        let method = match &*bin_op.r_opr {
+            Expr::BinOp(call) if is_call(call) => match &*call.l_opr {
+                Expr::Ref(t) => self.methods.get(&t.value.to_string()).map(|m| (t.clone(), *m)),
+                _ => None
+            },
+            _ => None
+        };
+        match method {
+            Some((name, (_, true))) => {
+                let p = self.pos + 1;
+                self.expr(&mut bin_op.l_opr);
+                if self.rewrite {
+                    if let Some(t) = self.used_again(&bin_op.l_opr, p) {
+                        self.errors.push(error!(
+                            format!("`{}` is given to `{}`, which owns it, and can not be used after it", t.value, name.value),
+                            t
+                        ));
+                    }
+                }
+            },
+            Some((_, (receiver, false))) if receiver.is_owned() => self.moved(&mut bin_op.l_opr),
+            // a literal is borrowed by the method, `"a b".split(" ")`
+            _ => self.read_str(&mut bin_op.l_opr)
+        }
+        self.segments(&mut bin_op.r_opr);
+    }
+}
+
+//================
+//   segments()
+//================
+impl<'a> Ownership<'a> {
+    fn segments(
+        &mut self,
+        expr: &mut Expr
+    ) {
+        match expr {
+            Expr::BinOp(bin_op) if bin_op.is_access() => {
+                self.segments(&mut bin_op.l_opr);
+                self.segments(&mut bin_op.r_opr);
+            },
+            Expr::BinOp(call) if is_call(call) => {
+                let (borrows, str_params) = match &*call.l_opr {
+                    Expr::Ref(t) => (
+                        STD_BORROW_METHODS.contains(&t.value.to_string().as_str()),
+                        self.method_sigs.get(&t.value.to_string()).map(|sig| sig.str_params.clone()).unwrap_or_default()
+                    ),
+                    _ => (false, vec![])
+                };
+                let Expr::Tuple(args) = &mut *call.r_opr else { return };
+                for (i, arg) in args.items.iter_mut().enumerate() {
+                    if borrows || str_params.get(i) == Some(&true) {
+                        self.borrowed(arg);
+                    } else {
+                        self.moved(arg);
+                    }
+                }
+            },
+            Expr::BinOp(bin_op) => self.expr(&mut bin_op.r_opr),
+            Expr::Ref(_) => (),
+            _ => self.expr(expr)
+        }
+    }
+}
+
+//================
+//   moved()
+//================
+// an expression whose value is given away: to a local, a function, a field or a list
+impl<'a> Ownership<'a> {
+    fn moved(
+        &mut self,
+        expr: &mut Expr
+    ) {
+        let p = self.pos + 1;
+        self.expr(expr);
+        if !self.rewrite { return }
+        if self.is_str_ref(expr) {
+            let value = std::mem::replace(expr, Expr::Unit);
+            *expr = Expr::Owned(Box::new(value));
+        } else if self.needs_clone(expr, p) {
+            let value = std::mem::replace(expr, Expr::Unit);
+            *expr = Expr::Clone(Box::new(value));
+        }
+    }
+}
+
+//================
+//   needs_clone()
+//================
+// `p` is the position of the first name of the expression
+impl<'a> Ownership<'a> {
+    fn needs_clone(
+        &self,
+        expr: &Expr,
+        p: usize
+    ) -> bool {
+        if let Expr::BinOp(index) = expr {
+            if matches!(index.op.value, TokenValue::OpenBracket) && place(&index.l_opr).is_none() {
+                // an element of a list that is not a local, e.g. `words()[0]`
+                return !self.ret_type(&index.l_opr).is_some_and(|_type| self.is_copy_elem_type(&_type))
+            }
+        }
+        let Some(place) = place(expr) else { return false };
+        if self.is_self(place.root) {
+            // a field can not be moved out of `&self`, it is cloned whatever the receiver is
+            return match place.field {
+                Some(field) => !self.is_copy_field(field),
+                None => false
+            }
+        }
+        let Some(slot) = self.slot(place.root) else { return false };
+        if place.indexed {
+            return !self.slots[slot].elem_copy
+        }
+        if place.field.is_none() && self.slots[slot].copy {
+            return false
+        }
+        self.used_again(expr, p).is_some()
+    }
+}
+
+//================
+//   used_again()
+//================
+// the local an expression starts from, when it is used after position `p` or in a loop it is not declared in
+impl<'a> Ownership<'a> {
+    fn used_again<'e>(
+        &self,
+        expr: &'e Expr,
+        p: usize
+    ) -> Option<&'e Token> {
+        let place = place(expr)?;
+        let slot = self.slot(place.root)?;
+        let later = self.uses
+            .get(slot)
+            .is_some_and(|uses| uses.iter().any(|q| *q > p));
+        if later || self.loop_depth > self.slots[slot].loop_depth {
+            Some(place.root)
+        } else {
+            None
+        }
+    }
+}
+
+//================
+//   borrowed()
+//================
+impl<'a> Ownership<'a> {
+    fn borrowed(
+        &mut self,
+        expr: &mut Expr
+    ) {
+        self.expr(expr);
+        if !self.rewrite { return }
+        match expr {
+            Expr::Str(t) => *expr = Expr::StrSlice(t.clone()),
+            Expr::Borrow(_)
+            | Expr::StrSlice(_) => (),
+            _ if self.is_str_ref(expr) => (),
+            _ => {
+                let value = std::mem::replace(expr, Expr::Unit);
+                *expr = Expr::Borrow(Box::new(value));
+            }
+        }
+    }
+}
+
+//================
+//   read_str()
+//================
+// a string literal that is only read is written as a `&str`, a char literal is written as a string too
+impl<'a> Ownership<'a> {
+    fn read_str(
+        &mut self,
+        expr: &mut Expr
+    ) {
+        match expr {
+            Expr::Str(t) if self.rewrite => *expr = Expr::StrSlice(t.clone()),
+            _ => self.expr(expr)
+        }
+    }
+}
+
+//================
+//   added()
+//================
+// the right side of a `+`, a string is added as a `&str` and a number as it is
+impl<'a> Ownership<'a> {
+    fn added(
+        &mut self,
+        expr: &mut Expr
+    ) {
+        if matches!(expr, Expr::Str(_)) || is_copy_literal(expr) || self.is_copy(expr) {
+            self.read_str(expr);
+        } else {
+            self.borrowed(expr);
+        }
+    }
+}
+
+//================
+//   use_name()
+//================
+impl<'a> Ownership<'a> {
+    fn use_name(
+        &mut self,
+        t: &Token
+    ) {
+        self.pos += 1;
+        if self.rewrite { return }
+        if let Some(slot) = self.slot(t) {
+            self.uses[slot].push(self.pos);
+        }
+    }
+}
+
+//================
+//   bind()
+//================
+impl<'a> Ownership<'a> {
+    fn bind(
+        &mut self,
+        pattern: &Pattern,
+        copy: bool,
+        elem_copy: bool
+    ) {
+        let is_id = matches!(pattern, Pattern::Id(_));
+        for id in pattern.bindings() {
+            let slot = self.slots.len();
+            self.slots.push(Slot {
+                copy: copy && is_id,
+                elem_copy: elem_copy && is_id,
+                str_ref: false,
+                loop_depth: self.loop_depth
+            });
+            if !self.rewrite {
+                self.uses.push(vec![]);
+            }
+            self.scopes
+                .last_mut()
+                .unwrap()
+                .push((id.value.to_string(), slot));
+        }
+    }
+}
+
+//================
+//   slot()
+//================
+impl<'a> Ownership<'a> {
+    fn slot(
+        &self,
+        t: &Token
+    ) -> Option<usize> {
+        let name = t.value.to_string();
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .find(|(n, _)| *n == name)
+            .map(|(_, slot)| *slot)
+    }
+}
+
+//================
+//   is_str_ref()
+//================
+impl<'a> Ownership<'a> {
+    fn is_str_ref(
+        &self,
+        expr: &Expr
+    ) -> bool {
+        match expr {
+            Expr::Ref(t) => self.slot(t).is_some_and(|slot| self.slots[slot].str_ref),
+            _ => false
+        }
+    }
+}
+
+//================
+//   is_copy()
+//================
+// a local, an element or a returned value whose type is known to be copy
+impl<'a> Ownership<'a> {
+    fn is_copy(
+        &self,
+        expr: &Expr
+    ) -> bool {
+        if let Some(_type) = self.ret_type(expr) {
+            return self.is_copy_type(&_type)
+        }
+        let Some(place) = place(expr) else { return false };
+        let Some(slot) = self.slot(place.root) else { return false };
+        match (place.field, place.indexed) {
+            (None, false) => self.slots[slot].copy,
+            (None, true) => self.slots[slot].elem_copy,
+            _ => false
+        }
+    }
+}
+
+//================
+//   ret_type()
+//================
+// the declared return type of `f(..)` or `x.f(..)` when `f` is a function or a method of the module
+impl<'a> Ownership<'a> {
+    fn ret_type(
+        &self,
+        expr: &Expr
+    ) -> Option<Type> {
+        match expr {
+            Expr::BinOp(call) if is_call(call) => match &*call.l_opr {
+                Expr::Ref(t) => self.fns.get(&t.value.to_string())?.ret_type.clone(),
+                _ => None
+            },
+            Expr::BinOp(bin_op) if bin_op.is_access() => self.method_ret_type(&bin_op.r_opr),
+            _ => None
+        }
+    }
+}
+
+//================
+//   method_ret_type()
+//================
+// the last segment of an access
+impl<'a> Ownership<'a> {
+    fn method_ret_type(
+        &self,
+        expr: &Expr
+    ) -> Option<Type> {
+        match expr {
+            Expr::BinOp(bin_op) if bin_op.is_access() => self.method_ret_type(&bin_op.r_opr),
+            Expr::BinOp(call) if is_call(call) => match &*call.l_opr {
+                Expr::Ref(t) => self.method_sigs.get(&t.value.to_string())?.ret_type.clone(),
+                _ => None
+            },
+            _ => None
+        }
+    }
+}
+
+//================
+//   is_self()
+//================
+impl<'a> Ownership<'a> {
+    fn is_self(
+        &self,
+        t: &Token
+    ) -> bool {
+        self.lexicon.concept_any("kw", &t.value.to_string()) == Some("kw.self")
+    }
+}
+
+//================
+//   is_copy_field()
+//================
+// a field of the struct of the method being lowered
+impl<'a> Ownership<'a> {
+    fn is_copy_field(
+        &self,
+        field: &Token
+    ) -> bool {
+        let Some(fields) = self.owner.as_ref().and_then(|owner| self.fields.get(owner)) else { return false };
+        fields
+            .iter()
+            .find(|f| f.name.value == field.value)
+            .is_some_and(|f| self.is_copy_type(&f._type))
+    }
+}
+
+//================
+//   is_copy_type()
+//================
+impl<'a> Ownership<'a> {
+    fn is_copy_type(
+        &self,
+        _type: &Type
+    ) -> bool {
+        match _type {
+            Type::PrimitiveType(PrimitiveType{id, ..}) => {
+                let concept = self.lexicon.concept_any("type", &id.value.to_string());
+                concept.is_some() && concept != Some("type.str")
+            },
+            Type::TupleType(tuple) => tuple.types.iter().all(|t| self.is_copy_type(t)),
+            Type::OptionType(option) => self.is_copy_type(&option.some_type),
+            _ => false
+        }
+    }
+}
+
+//================
+//   is_str_type()
+//================
+impl<'a> Ownership<'a> {
+    fn is_str_type(
+        &self,
+        _type: &Type
+    ) -> bool {
+        match _type {
+            Type::PrimitiveType(PrimitiveType{id, ..}) => self.lexicon.concept_any("type", &id.value.to_string()) == Some("type.str"),
+            _ => false
+        }
+    }
+}
+
+//================
+//   is_copy_elem_type()
+//================
+// a list or a map whose elements are copy
+impl<'a> Ownership<'a> {
+    fn is_copy_elem_type(
+        &self,
+        _type: &Type
+    ) -> bool {
+        match _type {
+            Type::MapType(map) => self.is_copy_type(&map.value_type),
+            _ => self.is_copy_list_type(_type)
+        }
+    }
+}
+
+//================
+//   is_copy_list_type()
+//================
+impl<'a> Ownership<'a> {
+    fn is_copy_list_type(
+        &self,
+        _type: &Type
+    ) -> bool {
+        match _type {
+            Type::ListType(list) => self.is_copy_type(&list.els_type),
+            _ => false
+        }
+    }
+}
+
+//================
+//   Place
+//================
+// `x`, `x.a.b` or `x[i]`, an expression that names a value instead of computing a new one
+struct Place<'e> {
+    root: &'e Token,
+    field: Option<&'e Token>,   // the last field, `b` in `x.a.b`
+    indexed: bool
+}
+
+//================
+//   place()
+//================
+fn place(expr: &Expr) -> Option<Place> {
+    match expr {
+        Expr::Ref(t) => Some(Place{ root: t, field: None, indexed: false }),
+        Expr::BinOp(bin_op) if matches!(bin_op.op.value, TokenValue::OpenBracket) => {
+            let place = place(&bin_op.l_opr)?;
+            Some(Place{ indexed: true, ..place })
+        },
+        Expr::BinOp(bin_op) if bin_op.is_access() => {
+            let Expr::Ref(root) = &*bin_op.l_opr else { return None };
+            let field = last_field(&bin_op.r_opr)?;
+            Some(Place{ root, field: Some(field), indexed: false })
+        },
+        _ => None
+    }
+}
+
+//================
+//   last_field()
+//================
+// `None` when a segment is not a field, e.g. a method call
+fn last_field(expr: &Expr) -> Option<&Token> {
+    match expr {
+        Expr::Ref(t) => Some(t),
+        Expr::BinOp(bin_op) if bin_op.is_access() => {
+            last_field(&bin_op.l_opr)?;
+            last_field(&bin_op.r_opr)
+        },
+        _ => None
+    }
+}
+
+//================
+//   is_call()
+//================
+fn is_call(bin_op: &BinOp) -> bool {
+    matches!(bin_op.op.value, TokenValue::OpenParen) && matches!(*bin_op.r_opr, Expr::Tuple(_))
+}
+
+//================
+//   is_copy_literal()
+//================
+fn is_copy_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Bool(_)
+        | Expr::Int(_)
+        | Expr::Float(_) => true,
+        Expr::PreUniOp(uni_op) => is_copy_literal(&uni_op.opr),
+        _ => false
+    }
+}
@@ -1,6 +1,5 @@
-use std::collections::HashMap;
-
 use super::{
+    Lang,
     token::{
         Token, 
         TokenValue
@@ -13,7 +12,10 @@ use super::{
     },
 };
 
-use crate::error;
+use crate::{
+    error,
+    transl::lexicon::Lexicon
+};
 
 //======================
 //  expect!()
@@ -49,6 +51,9 @@ pub struct Parser<'a> {
     ast: Option<Vec<ModElement>>,
     symtab: Option<SymTab>,
     errors: Option<Vec<Error>>,
+    lexicon: &'a Lexicon,
+    lang: Lang,                 // the language of the file, the names of the types are looked up in it
+    cond: bool,                 // parsing the condition of an `if`, `while`, `match` or `for`, `x { y }` is followed by a block
     // indents: Vec<Token> 
 } // TODO
 
@@ -63,10 +68,27 @@ impl<'a> Parser<'a>{
             ast: None,
             symtab: None,
             errors: None,
+            lexicon: Lexicon::embedded(),
+            lang: Lang::En,
+            cond: false,
             // indents: vec![]
         }
     }
 
+    //---------------------
+    //  lexicon()
+    //---------------------    
+    pub fn lexicon(&mut self, lexicon: &'a Lexicon) {
+        self.lexicon = lexicon;
+    }
+
+    //---------------------
+    //  lang()
+    //---------------------
+    pub fn lang(&mut self, lang: &Lang) {
+        self.lang = lang.clone();
+    }
+
     //---------------------
     //  init()
     //---------------------    
@@ -93,14 +115,19 @@ impl<'a> Parser<'a>{
         self.init(tokens);
         while !self.expect_eof() {
             let attrs = self.maybe_attrs(); // FIXME: should test if Some(attrs) and test only for constructs that accept/require attrs, then in the else test for the ones that don't
+            let vis_token = self.lookahead();
+            let vis = self.optional_visibility();
 
             let t = self.lookahead();
             // self.indents.push(t);
             if let Some(decl) = self.maybe_short_import() { // import("..", ...)  <===>   name := import("...", name)
+                self.no_visibility(vis, &vis_token);
                 self.mod_insert(ModElement::Decl(decl))
             } else if let Some(decl) = self.maybe_let_decl() {
+                self.no_visibility(vis, &vis_token);
                 self.mod_insert(ModElement::Decl(decl));
             } else if let Some(e) = self.maybe_lambda_or_decl(&attrs) {
+                self.no_visibility(vis, &vis_token);
                 match e {
                     LambdaOrDecl::Lambda(_fn) => self.mod_insert(ModElement::MainFn(_fn)),
                     LambdaOrDecl::Decl(decl) => self.mod_insert(ModElement::Decl(decl))
@@ -108,16 +135,24 @@ impl<'a> Parser<'a>{
                 
             } else if self.expect_id() {
                 let id = self.next();
-                if let Some(_fn) = self.maybe_fn(Some(&id), &attrs, false) {    // TODO instead of passing a boolean to indicate method/func , split it to maybe_fn() / maybe_method() for readability
+                let generics = self.maybe_generic_params().unwrap_or(vec![]);
+                if let Some(mut _fn) = self.maybe_fn(Some(&id), &generics, &attrs, false) {    // TODO instead of passing a boolean to indicate method/func , split it to maybe_fn() / maybe_method() for readability
+                    _fn.vis = vis;
                     let _ = self.symtab().insert_fn(&_fn);
                     self.mod_insert(ModElement::Fn(_fn));    
-                } else if let Some(e) = self.maybe_struct(&id, &attrs) {
+                } else if let Some(mut e) = self.maybe_struct(&id, &generics, &attrs) {
+                    e.vis = vis;
                     self.mod_insert(ModElement::Struct(e));
-                } else if let Some(e) = self.maybe_struct_impl(&id, &attrs) {
+                } else if let Some(mut e) = self.maybe_struct_impl(&id, &attrs) {
+                    for _fn in e.fns.iter_mut() {
+                        _fn.vis = vis;
+                    }
                     self.mod_insert(ModElement::StructImpl(e));
                 } else if let Some(e) = self.maybe_enum_impl(&id) {
+                    self.no_visibility(vis, &vis_token);
                     self.mod_insert(ModElement::EnumImpl(e));
                 }  else  if let Some(decl) = self.maybe_short_decl(Some(&id)) {
+                    self.no_visibility(vis, &vis_token);
                     let _ = self.symtab().insert_decl(&decl);
                     self.mod_insert(ModElement::Decl(decl));
                 } else {
@@ -127,8 +162,10 @@ impl<'a> Parser<'a>{
                 }
                 
             } else if let Some(e) = self.maybe_trait() {
+                self.no_visibility(vis, &vis_token);
                 self.mod_insert(ModElement::Trait(e));
-            } else if let Some(e) = self.maybe_enum() {
+            } else if let Some(mut e) = self.maybe_enum() {
+                e.vis = vis;
                 self.mod_insert(ModElement::Enum(e));
             } else {                    
                 let t = self.lookahead();
@@ -240,6 +277,26 @@ impl<'a> Parser<'a>{
     //---------------------
     //  expect_in()
     //---------------------    
+    fn expect_in(&mut self) -> bool { expect!(&self, TokenValue::In) }
+
+    //---------------------
+    //  expect_mut()
+    //---------------------    
+    fn expect_mut(&mut self) -> bool { expect!(&self, TokenValue::Mut) }
+
+    //---------------------
+    //  expect_pub()
+    //---------------------    
+    fn expect_pub(&mut self) -> bool { expect!(&self, TokenValue::Pub) }
+
+    //---------------------
+    //  expect_internal()
+    //---------------------    
+    fn expect_internal(&mut self) -> bool { expect!(&self, TokenValue::Internal) }
+
+    //---------------------
+    //  require_in()
+    //---------------------    
     fn require_in(&mut self) -> Result<Token, Error> { 
         require!(
             &self, 
@@ -384,6 +441,18 @@ impl<'a> Parser<'a>{
     //---------------------    
     fn expect_bar(&mut self) -> bool { expect!(&self, TokenValue::Bar) }
 
+    //---------------------
+    //  expect_pattern_or()
+    //---------------------    
+    // `|` between the alternatives of a pattern, `او` / `یا` are only keywords here and ids everywhere else
+    fn expect_pattern_or(&mut self) -> bool {
+        match &self.lookahead().value {
+            TokenValue::Bar => true,
+            TokenValue::Id(v) => self.lexicon.concept_any("kw", v) == Some("kw.or"),
+            _ => false
+        }
+    }
+
     //---------------------
     //  expect_dot()
     //---------------------    
@@ -410,6 +479,87 @@ impl<'a> Parser<'a>{
     //---------------------    
     fn expect_at(&mut self) -> bool { expect!(&self, TokenValue::At) }
 
+    //---------------------
+    //  expect_as()
+    //---------------------    
+    fn expect_as(&mut self) -> bool { expect!(&self, TokenValue::As) }
+
+    //---------------------
+    //  expect_fn()
+    //---------------------    
+    fn expect_fn(&mut self) -> bool { expect!(&self, TokenValue::Fn) }
+
+    //---------------------
+    //  expect_pipe_forward()
+    //---------------------    
+    fn expect_pipe_forward(&mut self) -> bool { expect!(&self, TokenValue::PipeForward) }
+
+    //---------------------
+    //  expect_range()
+    //---------------------    
+    fn expect_range(&mut self) -> bool { 
+        expect!(&self, TokenValue::DoubleDot) 
+        || expect!(&self, TokenValue::DoubleDotEqual) 
+    }
+
+    //---------------------
+    //  expect_rest_pattern()
+    //---------------------    
+    // `..` alone, as in `[first, ..]`
+    fn expect_rest_pattern(&mut self) -> bool { 
+        expect!(&self, TokenValue::DoubleDot)
+        && matches!(
+            self.lookahead_n_ws(2).value, 
+            TokenValue::Comma | TokenValue::CloseBracket | TokenValue::CloseParen
+        )
+    }
+
+    //---------------------
+    //  expect_spread()
+    //---------------------    
+    // `..base` at the end of a struct literal
+    fn expect_spread(&mut self) -> bool { 
+        expect!(&self, TokenValue::DoubleDot)
+    }
+
+    //---------------------
+    //  expect_infix_range()
+    //---------------------    
+    // `..` after an expression has to be on the same line
+    fn expect_infix_range(&mut self) -> bool { 
+        matches!(
+            self.lookahead_n_ws(1).value, 
+            TokenValue::DoubleDot | TokenValue::DoubleDotEqual
+        )
+    }
+
+    //---------------------
+    //  expect_expr_on_line()
+    //---------------------    
+    // for the optional expressions, `a..` is open and `break` has no value when nothing 
+    //  that can start an expression follows them
+    fn expect_expr_on_line(&mut self) -> bool { 
+        !matches!(
+            self.lookahead_n_ws(1).value, 
+            TokenValue::NewLine
+            | TokenValue::Eof
+            | TokenValue::Semicolon
+            | TokenValue::Comma
+            | TokenValue::CloseBracket
+            | TokenValue::CloseParen
+            | TokenValue::OpenCurly
+            | TokenValue::CloseCurly
+            | TokenValue::ThinArrow
+            | TokenValue::Arrow
+            | TokenValue::PipeForward
+        )
+    }
+
+    //---------------------
+    //  expect_add()
+    //---------------------    
+    fn expect_add(&mut self) -> bool { expect!(&self, TokenValue::Add) }
+
 
     //---------------------
     //  expect_thin_arrow()
@@ -632,12 +782,14 @@ impl<'a> Parser<'a>{
     //---------------------
     //  expect_terminator()
     //---------------------    
+    // the `}` closing a block ends its last element too: `if x { continue }`
     fn expect_terminator(&mut self) -> bool {
         let t = self.lookahead_n_ws(1);
         match t.value {
             TokenValue::NewLine 
             | TokenValue::Eof 
-            |  TokenValue::Semicolon  => true,
+            |  TokenValue::Semicolon
+            | TokenValue::CloseCurly => true,
             _ => false
         }
     }          
@@ -689,8 +841,7 @@ impl<'a> Parser<'a>{
         // || expect!(&self, TokenValue::Index)
         // || expect!(&self, TokenValue::OpenCurly)
         || self.expect_struct_literal()
-        || expect!(&self, TokenValue::OpenParen)
-        || expect!(&self, TokenValue::OpenBracket)
+        || self.expect_call_or_index()
         || expect!(&self, TokenValue::Equal)
         || expect!(&self, TokenValue::AddEqual)
         || expect!(&self, TokenValue::SubEqual)
@@ -699,7 +850,7 @@ impl<'a> Parser<'a>{
         || expect!(&self, TokenValue::BitwiseOrEqual)
         || expect!(&self, TokenValue::BitwiseAndEqual)
         || expect!(&self, TokenValue::BitwiseXorEqual)
-        || expect!(&self, TokenValue::PipeForward)
+        // `|>` is not parsed as an operand's operator, see maybe_expr()
         || expect!(&self, TokenValue::Eq)
         || expect!(&self, TokenValue::NE)
         || expect!(&self, TokenValue::GT)
@@ -714,10 +865,19 @@ impl<'a> Parser<'a>{
         || expect!(&self, TokenValue::LogicalOr)
         || expect!(&self, TokenValue::Dot)
         || expect!(&self, TokenValue::Dollar)
-        || expect!(&self, TokenValue::At)
+        // only on the same line, an `@` starting the next line is the attribute of the next item
+        || matches!(self.lookahead_n_ws(1).value, TokenValue::At)
 
     } 
 
+    //---------------------
+    //  expect_call_or_index()
+    //---------------------    
+    // only on the same line, `f(x)` followed by `() -> {` on the next line is a new function and not a call
+    fn expect_call_or_index(&mut self) -> bool {
+        matches!(self.lookahead_n_ws(1).value, TokenValue::OpenParen | TokenValue::OpenBracket)
+    }
+
     //---------------------
     //  expect_index()
     //---------------------    
@@ -751,7 +911,8 @@ impl<'a> Parser<'a>{
     //---------------------        
     fn expect_struct_literal(&mut self) -> bool {
         // expect!(&self, TokenValue::OpenCurly)
-        self.is_struct_literal()        
+        self.is_struct_literal()
+        || (!self.cond && self.is_lone_shorthand())
     }
     
 
@@ -782,6 +943,31 @@ impl<'a> Parser<'a>{
     //---------------------    
     fn expect_while(&mut self) -> bool { expect!(&self, TokenValue::While) }
 
+    //---------------------
+    //  expect_loop()
+    //---------------------    
+    fn expect_loop(&mut self) -> bool { expect!(&self, TokenValue::Loop) }
+
+    //---------------------
+    //  expect_break()
+    //---------------------    
+    fn expect_break(&mut self) -> bool { expect!(&self, TokenValue::Break) }
+
+    //---------------------
+    //  expect_continue()
+    //---------------------    
+    fn expect_continue(&mut self) -> bool { expect!(&self, TokenValue::Continue) }
+
+    //---------------------
+    //  expect_ret()
+    //---------------------    
+    fn expect_ret(&mut self) -> bool { expect!(&self, TokenValue::Ret) }
+
+    //---------------------
+    //  expect_label()
+    //---------------------    
+    fn expect_label(&mut self) -> bool { expect!(&self, TokenValue::Dollar) }
+
     //---------------------
     //  expect_if()
     //---------------------    
@@ -864,12 +1050,48 @@ impl<'a> Parser<'a> {
         if !self.expect_at() { return Err(error!("expecting `@`".to_string(), self.lookahead())) }
 
         self.next();
-        if let Some(id) = self.maybe_id() {
-            Ok(Attr{ expr: AttrExpr::Ref(id) })
-        } else {
-            Err(error!("expecting an identifier after `@`".to_string(), self.lookahead()))
+        let name = match self.maybe_id() {
+            Some(id) => id,
+            None => return Err(error!("expecting an identifier after `@`".to_string(), self.lookahead()))
+        };
+        // the arguments start on the same line as the name
+        if !matches!(self.lookahead_n_ws(1).value, TokenValue::OpenParen) {
+            return Ok(Attr{ expr: AttrExpr::Ref(name) })
+        }
+        self.next();
+
+        let mut args = vec![];
+        while !self.expect_close_paren() && !self.expect_eof() {
+            args.push(self.require_attr_arg()?);
+            if !self.expect_comma() { break }
+            self.next();
         }
+        self.require_close_paren()?;
+        Ok(Attr{ expr: AttrExpr::Call(AttrCall{ name, args }) })
+    }
+}
 
+//================
+// require_attr_arg()
+//================
+// `expr` or `name: expr`
+impl<'a> Parser<'a> {
+    pub fn require_attr_arg(&mut self) -> Result<AttrArg, Error> {
+        let i = match self.lookahead_n_ws(1).value {
+            TokenValue::NewLine => 2,
+            _ => 1
+        };
+        let is_named = matches!(self.lookahead_n_ws(i).value, TokenValue::Id(_))
+            && matches!(self.lookahead_n_ws(i + 1).value, TokenValue::Colon);
+        let name = if is_named {
+            let name = self.next();
+            self.next();
+            Some(name)
+        } else {
+            None
+        };
+        let expr = self.require_expr()?;
+        Ok(AttrArg{ name, expr })
     }
 }
 
@@ -898,8 +1120,12 @@ impl<'a> Parser<'a> {
             Some(Type::ResultType(Box::new(_type)))
         } else if let Some(_type) = self.maybe_primitive_type() {
             Some(Type::PrimitiveType(_type))
-        } else if let Some(_type) = self.maybe_list_type() {
-            Some(Type::ListType(_type))
+        } else if let Some(_type) = self.maybe_list_or_map_type() {
+            Some(_type)
+        } else if let Some(_type) = self.maybe_map_type() {
+            Some(Type::MapType(_type))
+        } else if let Some(_type) = self.maybe_fn_type() {
+            Some(Type::FnType(_type))
         } else if let Some(_type) = self.maybe_unit_or_tuple_type() {
             match _type {
                 UnitOrTupleType::Unit => Some(Type::UnitType),
@@ -907,6 +1133,8 @@ impl<'a> Parser<'a> {
             }
         } else if let Some(_type) = self.maybe_struct_type() {
             Some(Type::StructType(_type))
+        } else if let Some(_type) = self.maybe_named_type() {
+            Some(Type::NamedType(_type))
         } else {
             None
         };
@@ -984,27 +1212,12 @@ impl<'a> Parser<'a> {
 //================
 // maybe_primitive_type()
 //================
-// FIXME: separate ar/en
 impl<'a> Parser<'a> {
     pub fn maybe_primitive_type (&mut self) -> Option<PrimitiveType>{
-        if !self.expect_id() { return None }
-        let id = self.next();
-        match &id.value {
-            TokenValue::Id(v)   =>  
-                match v.as_str() {
-                    "bool" 
-                    | "منطقي"
-                    | "int"
-                    | "صحيح"
-                    | "float"
-                    | "عائم"
-                    | "char"
-                    | "محرف" 
-                    | "str" 
-                    | "سلسلة" => {
-                        Some( PrimitiveType{ id } )
-                    },
-                    _ => None
+        match &self.lookahead().value {
+            TokenValue::Id(v) if self.lexicon.concept("type", &self.lang, v).is_some() => {
+                let id = self.next();
+                Some( PrimitiveType{ id } )
             },
             _ => None
         }
@@ -1012,17 +1225,100 @@ impl<'a> Parser<'a> {
 }
 
 //================
-// maybe_list_type()
+// maybe_list_or_map_type()
 //================
+// `[T]` or `[K: V]`
 impl<'a> Parser<'a> {
-    pub fn maybe_list_type (&mut self) -> Option<ListType>{
+    pub fn maybe_list_or_map_type (&mut self) -> Option<Type>{
         if !self.expect_open_bracket() { return None }
         self.next();
         let _type = self.require_type();
         let _type = self.res_to_opt(_type)?;
+
+        let value_type = if self.expect_colon() {
+            self.next();
+            let value_type = self.require_type();
+            Some(self.res_to_opt(value_type)?)
+        } else {
+            None
+        };
+
         let _close_bracket = self.require_close_bracket();
         let _ = self.res_to_opt(_close_bracket)?;
-        Some(ListType {els_type: Box::new(_type) } )
+
+        match value_type {
+            None => Some(Type::ListType(ListType {els_type: Box::new(_type) } )),
+            Some(value_type) => Some(Type::MapType(MapType {
+                key_type: Box::new(_type),
+                value_type: Box::new(value_type),
+                sorted: false
+            }))
+        }
+    }
+}
+
+//================
+// maybe_map_type()
+//================
+// `map<K, V>`, `sorted_map<K, V>`
+impl<'a> Parser<'a> {
+    pub fn maybe_map_type (&mut self) -> Option<MapType>{
+        let sorted = match &self.lookahead().value {
+            TokenValue::Id(v) => match self.lexicon.concept("map", &self.lang, v) {
+                Some("map.map") => false,
+                Some("map.sorted") => true,
+                _ => return None
+            },
+            _ => return None
+        };
+        let name = self.next();
+        let args = self.maybe_type_args();
+        match args {
+            Some(args) if args.len() == 2 => {
+                let mut args = args.into_iter();
+                Some(MapType {
+                    key_type: Box::new(args.next()?),
+                    value_type: Box::new(args.next()?),
+                    sorted
+                })
+            },
+            _ => {
+                self.insert_err(
+                    error!(format!("expecting the key and value types: `{}<K, V>`", name.value), name)
+                );
+                None
+            }
+        }
+    }
+}
+
+//================
+// maybe_fn_type()
+//================
+// `fn(int, str): bool`, the return type is optional
+impl<'a> Parser<'a> {
+    pub fn maybe_fn_type (&mut self) -> Option<FnType>{
+        if !self.expect_fn() { return None }
+        self.next();
+        let open_paren = self.require_open_paren();
+        let _ = self.res_to_opt(open_paren)?;
+
+        let mut params_types = vec![];
+        while !self.expect_close_paren() {
+            let _type = self.require_type();
+            let _type = self.res_to_opt(_type)?;
+            params_types.push(_type);
+            if !self.expect_comma() { break }
+            self.next();
+        }
+        let close_paren = self.require_close_paren();
+        let _ = self.res_to_opt(close_paren)?;
+
+        let ret_type = match self.optional_type_annotation() {
+            Some(_type) => Some(Box::new(_type)),
+            None => None
+        };
+        Some( FnType{ params_types, ret_type } )
     }
 }
 
@@ -1077,6 +1373,102 @@ impl<'a> Parser<'a> {
     }
 }
 
+//================
+// maybe_named_type()
+//================
+// any id that is not a primitive type: a struct, a type parameter or a trait, e.g: `T`, `Stack<T>`
+impl<'a> Parser<'a> {
+    pub fn maybe_named_type (&mut self) -> Option<NamedType>{
+        let name = self.maybe_id()?;
+        let args = if self.expect_open_angle() {
+            self.maybe_type_args()?
+        } else {
+            vec![]
+        };
+        Some( NamedType{ name, args } )
+    }
+}
+
+//================
+// maybe_type_args()
+//================
+// `<int, T>`
+impl<'a> Parser<'a> {
+    pub fn maybe_type_args (&mut self) -> Option<Vec<Type>>{
+        if !self.expect_open_angle() { return None }
+        self.next();
+        let mut args = vec![];
+        loop {
+            let _type = self.require_type();
+            let _type = self.res_to_opt(_type)?;
+            args.push(_type);
+            if !self.expect_comma() { break }
+            self.next();
+        }
+        let close_angle = self.require_close_angle();
+        let _ = self.res_to_opt(close_angle)?;
+        Some(args)
+    }
+}
+
+//================
+// maybe_generic_params()
+//================
+// `<T, U: Display + Clone>` after the name of a function or a struct
+impl<'a> Parser<'a> {
+    pub fn maybe_generic_params (&mut self) -> Option<Vec<GenericParam>>{
+        if !self.expect_open_angle() { return None }
+        self.next();
+        let mut generics = vec![];
+        loop {
+            let param = self.require_generic_param();
+            let param = self.res_to_opt(param)?;
+            generics.push(param);
+            if !self.expect_comma() { break }
+            self.next();
+        }
+        let close_angle = self.require_close_angle();
+        let _ = self.res_to_opt(close_angle)?;
+        Some(generics)
+    }
+}
+
+//================
+// require_generic_param()
+//================
+impl<'a> Parser<'a> {
+    fn require_generic_param (&mut self) -> Result<GenericParam, Error>{
+        let name = self.require_id()?;
+        let mut bounds = vec![];
+        if self.expect_colon() {
+            self.next();
+            loop {
+                bounds.push(self.require_type()?);
+                if !self.expect_add() { break }
+                self.next();
+            }
+        }
+        Ok( GenericParam{ name, bounds } )
+    }
+}
+
+//================
+// maybe_generic_ref()
+//================
+// explicit type arguments at the use site: `parse::<int>(s)`, `Stack::<int>{ items: [] }`
+impl<'a> Parser<'a> {
+    pub fn maybe_generic_ref (
+        &mut self,
+        id: &Token
+    ) -> Option<GenericRef>{
+        if !matches!(self.lookahead_n_ws(1).value, TokenValue::DoubleColon) { return None }
+        if !matches!(self.lookahead_n_ws(2).value, TokenValue::LT) { return None }
+        self.next();
+        let args = self.maybe_type_args()?;
+        Some( GenericRef{ id: id.clone(), args } )
+    }
+}
+
 //================
 // maybe_bool()
 //================
@@ -1156,13 +1548,22 @@ impl<'a> Parser<'a> {
 impl<'a> Parser<'a> {
     pub fn maybe_struct_literal (&mut self) -> Option<StructLiteral> {
         if !self.is_struct_literal() { return None}
+        self.struct_literal_fields()
+    }
+}
+
+//================
+// struct_literal_fields()
+//================
+impl<'a> Parser<'a> {
+    fn struct_literal_fields (&mut self) -> Option<StructLiteral> {
         let open_curly = if self.expect_open_curly() {
             self.next();
             true
         } else {
             false
         };
-        let mut literal = StructLiteral{ items: vec![]};
+        let mut literal = StructLiteral{ items: vec![], base: None };
         loop {
             if self.expect_eof() && open_curly {
                 let t = self.lookahead();
@@ -1176,18 +1577,26 @@ impl<'a> Parser<'a> {
             }
 
 
-            let t = self.lookahead();
-                               
+            if open_curly && self.expect_spread() {
+                self.next();
+                let base = self.require_expr();
+                literal.base = Some(Box::new(self.res_to_opt(base)?));
+                let close_curly = self.require_close_curly();
+                let _ = self.res_to_opt(close_curly)?;
+                return Some(literal);
+            }
+
             let key = self.require_id();
             let key = self.res_to_opt(key)?;
 
-            let colon = self.require_colon();
-            let _ = self.res_to_opt(colon)?;
-
-            let expr = self.require_expr();
-            let expr = self.res_to_opt(expr)?;
-
-            literal.items.push((key, Some(expr)));
+            if self.expect_colon() {
+                self.next();
+                let expr = self.require_expr();
+                let expr = self.res_to_opt(expr)?;
+                literal.items.push((key, Some(expr)));
+            } else {
+                literal.items.push((key, None));
+            }
             self.optional_comma();
         }
     }
@@ -1210,12 +1619,52 @@ impl<'a> Parser<'a> {
 //================
 // maybe_pattern()
 //================
+// `a | b` (`a او b`) matches either alternative
 impl<'a> Parser<'a> {
     pub fn maybe_pattern (
         &mut self,
     ) -> Option<Pattern>{
-        let p = if let Some(p) = self.maybe_primitive_literal() {
-            Some(Pattern::PrimitiveLiteral(Box::new(p)))
+        let first = self.maybe_single_pattern()?;
+        if !self.expect_pattern_or() { return Some(first) }
+
+        let mut patterns = vec![first];
+        while self.expect_pattern_or() {
+            self.next();
+            let t = self.lookahead();
+            match self.maybe_single_pattern() {
+                Some(p) => patterns.push(p),
+                None => {
+                    self.insert_err(error!("expecting a pattern after `|`".to_string(), t));
+                    return None
+                }
+            }
+        }
+        Some(Pattern::Or(patterns))
+    }
+}
+
+//================
+// maybe_single_pattern()
+//================
+impl<'a> Parser<'a> {
+    fn maybe_single_pattern (
+        &mut self,
+    ) -> Option<Pattern>{
+        let p = if self.expect_rest_pattern() {
+            self.next();
+            Some(Pattern::Rest)
+        } else if let Some(p) = self.maybe_variant_pattern() {
+            Some(p)
+        } else if self.expect_range() {
+            let range = self.require_range(None, true);
+            Some(Pattern::Range(self.res_to_opt(range)?))
+        } else if let Some(p) = self.maybe_primitive_literal() {
+            if self.expect_infix_range() {
+                let range = self.require_range(Some(p), true);
+                Some(Pattern::Range(self.res_to_opt(range)?))
+            } else {
+                Some(Pattern::PrimitiveLiteral(Box::new(p)))
+            }
         } else if let Some(id) = self.maybe_id() {
             if self.expect_open_curly() { 
                 if let Some(mut p) = self.maybe_struct_pattern() {
@@ -1239,6 +1688,18 @@ impl<'a> Parser<'a> {
                     ));
                     None                    
                 }                
+            } else if self.expect_at() {
+                // `n @ 1..=9`
+                self.next();
+                self.symtab().insert_id_pattern(&id);
+                let t = self.lookahead();
+                match self.maybe_single_pattern() {
+                    Some(p) => Some(Pattern::Binding(BindingPattern { id, pattern: Box::new(p) })),
+                    None => {
+                        self.insert_err(error!("expecting a pattern after `@`".to_string(), t));
+                        None
+                    }
+                }
             } else {
                 self.symtab().insert_id_pattern(&id);
                 Some(Pattern::Id(IdPattern { id }))
@@ -1277,6 +1738,39 @@ impl<'a> Parser<'a> {
     }
 }
 
+//================
+// maybe_variant_pattern()
+//================
+// FIXME: HARDCODING VARIANTS FOR THE DEMO
+impl<'a> Parser<'a> {
+    fn maybe_variant_pattern (
+        &mut self,
+    ) -> Option<Pattern>{
+        if self.expect_none() {
+            self.next();
+            return Some(Pattern::None)
+        }
+
+        let variant = if self.expect_some() || self.expect_ok() || self.expect_err() {
+            self.next()
+        } else {
+            return None
+        };
+        let open_paren = self.require_open_paren();
+        let _ = self.res_to_opt(open_paren)?;
+        let pattern = self.require_pattern();
+        let pattern = Box::new(self.res_to_opt(pattern)?);
+        let close_paren = self.require_close_paren();
+        let _ = self.res_to_opt(close_paren)?;
+
+        match variant.value {
+            TokenValue::Some => Some(Pattern::Some(pattern)),
+            TokenValue::Ok => Some(Pattern::Ok(pattern)),
+            _ => Some(Pattern::Err(pattern)),
+        }
+    }
+}
+
 //================
 // maybe_patterns()
 //================
@@ -1368,7 +1862,8 @@ impl<'a> Parser<'a> {
                     let _type = self.optional_type_annotation();
                     params.push(Param{
                         pat,
-                        _type
+                        _type,
+                        is_mut: false
                     });
                     self.optional_comma();
 
@@ -1421,7 +1916,7 @@ impl<'a> Parser<'a> {
     fn require_struct_patterns(
         &mut self,
     ) -> Result<StructPatternFields, Error> {
-        let mut pattern = StructPatternFields::from([]);
+        let mut pattern = StructPatternFields::new();
         loop {
             
             if self.expect_id() {
@@ -1430,13 +1925,13 @@ impl<'a> Parser<'a> {
                 if self.expect_colon() {
                     self.next();
                     if let Some(p) = self.maybe_pattern() {
-                        pattern.insert(t, Some(p));
+                        pattern.push((t, Some(p)));
                     } else {
                         let t = self.current();
                         self.insert_err( error!( format!("expecting a pattern: {:?}", t.value), t ) );   
                     }    
                 } else {
-                    pattern.insert(t, None);
+                    pattern.push((t, None));
                 }
 
                 self.optional_comma();  
@@ -1457,18 +1952,25 @@ impl<'a> Parser<'a> {
     ) -> Option<EnumPattern> {
         
         if !self.expect_dot()  { return None }
+        self.next();
         let id = self.require_id();
         let variant_name = self.res_to_opt(id)?;
-        if !self.expect_arg_list() { return None}
-        self.next();
-        let pattern = self.require_pattern();
-        let pattern = self.res_to_opt(pattern)?;
-        let close_paren = self.require_close_paren();
-        let _ = self.res_to_opt(close_paren)?;
+
+        // a variant without a value has no parens, `.Red`
+        let pattern = if self.expect_arg_list() {
+            self.next();
+            let pattern = self.require_pattern();
+            let pattern = self.res_to_opt(pattern)?;
+            let close_paren = self.require_close_paren();
+            let _ = self.res_to_opt(close_paren)?;
+            Some(Box::new(pattern))
+        } else {
+            None
+        };
 
         let variant = EnumVariant { 
             name: variant_name, 
-            pattern: Some(Box::new(pattern))
+            pattern
         };
 
         Some( EnumPattern {  name: None,  variant  } )
@@ -1514,15 +2016,64 @@ impl<'a> Parser<'a> {
 }
 
 //================
-// maybe_list()
+// maybe_list_or_map()
 //================
+// a list `[a, b]`, or a map when the first item is followed by a colon `[k: v]`, `[:]` is the empty map
 impl<'a> Parser<'a> {
-    pub fn maybe_list (&mut self)  -> Option<List> {
+    pub fn maybe_list_or_map (&mut self)  -> Option<Expr> {
         if !self.expect_open_bracket() { return None }
         self.next();
-        let exprs = self.require_exprs(TokenValue::CloseBracket);
-        let exprs = self.res_to_opt(exprs)?;
-        Some(List{items: exprs})
+
+        if self.expect_colon() {
+            self.next();
+            let close_bracket = self.require_close_bracket();
+            let _ = self.res_to_opt(close_bracket)?;
+            return Some(Expr::Map(Map{items: vec![]}))
+        }
+
+        if self.expect_close_bracket() {
+            self.next();
+            return Some(Expr::List(List{items: vec![]}))
+        }
+
+        let first = self.require_expr();
+        let first = self.res_to_opt(first)?;
+
+        if self.expect_colon() {
+            let items = self.require_map_items(first);
+            let items = self.res_to_opt(items)?;
+            Some(Expr::Map(Map{items}))
+        } else {
+            self.optional_comma();
+            let exprs = self.require_exprs(TokenValue::CloseBracket);
+            let mut exprs = self.res_to_opt(exprs)?;
+            exprs.insert(0, first);
+            Some(Expr::List(List{items: exprs}))
+        }
+    }
+}
+
+//================
+// require_map_items()
+//================
+impl<'a> Parser<'a> {
+    fn require_map_items (
+        &mut self,
+        first_key: Expr
+    )  -> Result<Vec<(Expr, Expr)>, Error> {
+        let mut items = vec![];
+        let mut key = first_key;
+        loop {
+            self.require_colon()?;
+            let value = self.require_expr()?;
+            items.push((key, value));
+            self.optional_comma();
+            if self.expect_close_bracket() {
+                self.next();
+                return Ok(items)
+            }
+            key = self.require_expr()?;
+        }
     }
 }
 
@@ -1612,7 +2163,8 @@ impl<'a> Parser<'a> {
                     Decl {
                         pattern: pattern, 
                         _type: None,
-                        expr: Some(expr)
+                        expr: Some(expr),
+                        is_mut: false
                     }
                 )    
             }
@@ -1627,6 +2179,7 @@ impl<'a> Parser<'a> {
     pub fn maybe_let_decl (&mut self) -> Option<Decl> {
         if ! self.expect_let() { return None }
         self.next();
+        let is_mut = self.optional_mut();
         let pattern = self.require_pattern();
         let pattern = self.res_to_opt(pattern)?;
 
@@ -1651,7 +2204,8 @@ impl<'a> Parser<'a> {
                         Decl {
                             pattern: pattern, 
                             _type: _type,
-                            expr: Some(expr)
+                            expr: Some(expr),
+                            is_mut
                         }
                     )    
                 }
@@ -1661,7 +2215,8 @@ impl<'a> Parser<'a> {
                 Decl {
                     pattern: pattern, 
                     _type: _type,
-                    expr: None
+                    expr: None,
+                    is_mut
                 }
             )
 
@@ -1670,6 +2225,134 @@ impl<'a> Parser<'a> {
 
 }
 
+//================
+// optional_mut()
+//================
+// `let mut x = ..`, a local the mutability pass can not see mutated, e.g. through a method of an unknown type
+impl<'a> Parser<'a> {
+    fn optional_mut (&mut self) -> bool {
+        if self.expect_mut() {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+//================
+// is_let_in()
+//================
+// `let x = e in body` is an expression, not a declaration: looks for an `in` before the end of the line
+//  that is not the `in` of a `for`
+impl<'a> Parser<'a> {
+    pub fn is_let_in(&mut self) -> bool {
+        let mut i = match self.lookahead_n_ws(1).value {
+            TokenValue::NewLine => 2,
+            _ => 1
+        };
+        let mut depth = 0;
+        let mut fors = 0;
+        loop {
+            let t = self.lookahead_n_ws(i);
+            match t.value {
+                TokenValue::Eof => return false,
+                TokenValue::NewLine
+                | TokenValue::Semicolon if depth == 0 => return false,
+                TokenValue::OpenParen
+                | TokenValue::OpenBracket
+                | TokenValue::OpenCurly => depth += 1,
+                TokenValue::CloseParen
+                | TokenValue::CloseBracket
+                | TokenValue::CloseCurly => {
+                    if depth == 0 { return false }
+                    depth -= 1;
+                },
+                TokenValue::For => fors += 1,
+                TokenValue::In if fors > 0 => fors -= 1,
+                TokenValue::In if depth == 0 => return true,
+                _ => ()
+            }
+            i += 1;
+        }
+    }
+}
+
+//================
+// maybe_let_in()
+//================
+// the binding has its own scope, left while parsing `e` and entered again for the body
+impl<'a> Parser<'a> {
+    pub fn maybe_let_in (&mut self) -> Option<LetIn> {
+        if !self.expect_let() { return None }
+        self.next();
+
+        let is_mut = self.optional_mut();
+        let scope = self.symtab().new_scope();
+        let pattern = self.require_pattern();
+        let pattern = self.res_to_opt(pattern)?;
+        let _type = self.optional_type_annotation();
+        self.symtab().exit_scope();
+
+        let equal = self.require_equal();
+        let _ = self.res_to_opt(equal)?;
+        let expr = self.require_expr();
+        let expr = self.res_to_opt(expr)?;
+        let _in = self.require_in();
+        let _ = self.res_to_opt(_in)?;
+
+        self.symtab().enter_scope(scope);
+        let body = self.require_expr();
+        self.symtab().exit_scope();
+        let body = self.res_to_opt(body)?;
+
+        Some(
+            LetIn {
+                decl: Box::new(Decl { pattern, _type, expr: Some(expr), is_mut }),
+                expr: Box::new(body)
+            }
+        )
+    }
+}
+
+//================
+// optional_where_decls()
+//================
+// `where x = e` or `where { x = e ... }` after the body of a function
+impl<'a> Parser<'a> {
+    pub fn optional_where_decls (&mut self) -> Result<Vec<Decl>, Error> {
+        if !self.expect_where() { return Ok(vec![]) }
+        self.next();
+        if !self.expect_open_curly() {
+            return Ok(vec![self.require_where_decl()?])
+        }
+        self.next();
+
+        let mut decls = vec![];
+        while !self.expect_close_curly() && !self.expect_eof() {
+            decls.push(self.require_where_decl()?);
+            if !self.expect_close_curly() {
+                self.require_terminator()?;
+            }
+        }
+        self.require_close_curly()?;
+        Ok(decls)
+    }
+}
+
+//================
+// require_where_decl()
+//================
+impl<'a> Parser<'a> {
+    pub fn require_where_decl (&mut self) -> Result<Decl, Error> {
+        let pattern = self.require_pattern()?;
+        let _type = self.optional_type_annotation();
+        self.require_equal()?;
+        let expr = self.require_expr()?;
+        Ok( Decl { pattern, _type, expr: Some(expr), is_mut: false } )
+    }
+}
+
 //================
 // require_let_decl()
 //================
@@ -1711,12 +2394,16 @@ impl<'a> Parser<'a> {
         
         let mut expr = l_opr.clone();
 
-        while self.expect_bin_op() || self.expect_postfix_uni_op(){
+        while self.expect_bin_op() || self.expect_postfix_uni_op() || self.expect_as() {
             if let Some(cond) = cond {
                 if !cond { break; }
             }  
             match self.require_op(&expr) {
-                Err(err) => panic!("bug : {:?} ", err),
+                // the operand that failed has reported why, e.g. `l[1..=]`
+                Err(err) => {
+                    self.insert_err(err);
+                    break;
+                },
                 Ok(_expr) => {
                     expr = _expr;
                 }
@@ -1736,7 +2423,11 @@ impl<'a> Parser<'a> {
     )  -> Option<Expr> {
         let op = self.lookahead();
         let l_opr = l_opr.clone();
-        if self.expect_postfix_uni_op() {
+        if self.expect_as() {
+            let cast = self.require_cast(&l_opr);
+            self.res_to_opt(cast)
+
+        } else if self.expect_postfix_uni_op() {
             let postfix_op = self.next();
             let l_opr = self.l_opr(&l_opr, &postfix_op);
             Some(l_opr)
@@ -1750,7 +2441,9 @@ impl<'a> Parser<'a> {
             let r_opr = self.require_r_opr(&l_opr);
             let mut r_opr = self.res_to_opt(r_opr)?;
 
-            if self.expect_bin_op() {
+            // a call or an index binds tighter than any operator after it, `l[i] = l[i] + 1`
+            let postfix = matches!(op.value, TokenValue::OpenBracket | TokenValue::OpenParen);
+            if !postfix && self.expect_bin_op() {
                 let next_op = self.lookahead().to_string();
 
                 r_opr = match &r_opr {
@@ -2002,7 +2695,11 @@ impl<'a> Parser<'a> {
         } else {
             self.next();            
             // self.maybe_prim()
-            self.maybe_expr()
+            if operator::is_bin_rassoc(&t.to_string()) {
+                self.maybe_expr()                   // `y = x |> f` assigns the result of the pipe
+            } else {
+                self.maybe_expr_without_range()
+            }
         };      
     
 
@@ -2013,6 +2710,28 @@ impl<'a> Parser<'a> {
     }
 }
 
+//================
+// require_cast()
+//================
+// `as` binds tighter than the binary operators: `a + b as i64` casts `b` only
+impl<'a> Parser<'a> {
+    fn require_cast(
+        &mut self,
+        l_opr: &Expr,
+    ) -> Result<Expr, Error> {
+        self.next();
+        let _type = self.require_type()?;
+        Ok(
+            Expr::Cast(
+                Cast {
+                    expr: Box::new(l_opr.to_owned()),
+                    _type
+                }
+            )
+        )
+    }
+}
+
 //================
 // require_access()
 //================
@@ -2067,8 +2786,11 @@ impl<'a> Parser<'a> {
 //================
 impl<'a> Parser<'a> {
     fn require_struct_init(&mut self) -> Result<Expr, Error> {
-        let struct_literal = self.require_struct_lietral()?;
-        Ok(Expr::StructLiteral(struct_literal))
+        // `Point { y }` is a literal after the name of the struct, even with a single shorthand field
+        match self.struct_literal_fields() {
+            Some(struct_literal) => Ok(Expr::StructLiteral(struct_literal)),
+            None => Err(error!("expecting a struct literal".to_string(), self.lookahead()))
+        }
     }
 }
 
@@ -2092,6 +2814,7 @@ impl<'a> Parser<'a> {
     pub fn maybe_struct (
         &mut self,
         id: &Token,
+        generics: &Vec<GenericParam>,
         attrs: &Option<Vec<Attr>>
     )  -> Option<Struct> {
 
@@ -2117,6 +2840,8 @@ impl<'a> Parser<'a> {
             Struct {
                 name,
                 attrs,
+                vis: Visibility::Private,
+                generics: generics.clone(),
                 fields,
                 impls
             }
@@ -2124,6 +2849,43 @@ impl<'a> Parser<'a> {
     }
 }
 
+//================
+// optional_visibility()
+//================
+// `pub` / `internal` written before a function, a struct, a method or a field
+impl<'a> Parser<'a> {
+    fn optional_visibility (
+        &mut self,
+    )  -> Visibility {
+        if self.expect_pub() {
+            self.next();
+            Visibility::Public
+        } else if self.expect_internal() {
+            self.next();
+            Visibility::Internal
+        } else {
+            Visibility::Private
+        }
+    }
+}
+
+//================
+// no_visibility()
+//================
+impl<'a> Parser<'a> {
+    fn no_visibility (
+        &mut self,
+        vis: Visibility,
+        t: &Token
+    ) {
+        if vis != Visibility::Private {
+            self.insert_err(
+                error!("a visibility can only be written on a function, a struct, a method or a field".to_string(), t)
+            );
+        }
+    }
+}
+
 //================
 // maybe_struct_fields()
 //================
@@ -2131,13 +2893,20 @@ impl<'a> Parser<'a> {
     fn maybe_struct_fields (
         &mut self,
     )  -> Result<StructFields, Error> {
-        let mut fields: StructFields = HashMap::new();
+        let mut fields: StructFields = vec![];
         
         loop {
+            let vis_token = self.lookahead();
+            let vis = self.optional_visibility();
             if self.expect_id() {
-                let field = self.require_field()?;
-                fields.insert(field.0, field.1);
+                let mut field = self.require_field()?;
+                field.vis = vis;
+                if fields.iter().any(|f| f.name.value == field.name.value) {
+                    self.insert_err( error!( format!("field `{}` is declared more than once", field.name.value), field.name.clone() ) );
+                }
+                fields.push(field);
             }  else {
+                self.no_visibility(vis, &vis_token);
                 let close_curly = self.require_close_curly()?;
                 return Ok(fields)
             }
@@ -2149,14 +2918,21 @@ impl<'a> Parser<'a> {
 //================
 // require_field()
 //================
+// `name: type` or `name: type = default`
 impl<'a> Parser<'a> {
     fn require_field(
         &mut self
-    ) -> Result<(Token, Type), Error> {
-        let field_name = self.require_id()?;
+    ) -> Result<StructField, Error> {
+        let name = self.require_id()?;
         let _ = self.require_colon()?;
-        let field_type = self.require_type()?;
-        Ok((field_name, field_type))
+        let _type = self.require_type()?;
+        let default = if self.expect_equal() {
+            self.next();
+            Some(self.require_expr()?)
+        } else {
+            None
+        };
+        Ok(StructField{ vis: Visibility::Private, name, _type, default })
     }
 }
 
@@ -2180,7 +2956,8 @@ impl<'a> Parser<'a> {
             true
         };
         let fn_id = self.maybe_id();
-        let _fn = self.require_fn(fn_id.as_ref(), attrs, is_method);
+        let generics = self.maybe_generic_params().unwrap_or(vec![]);
+        let _fn = self.require_fn(fn_id.as_ref(), &generics, attrs, is_method);
         if let Some(_fn) = self.res_to_opt(_fn) {
             Some(
                 StructImpl {
@@ -2284,7 +3061,7 @@ impl<'a> Parser<'a> {
 
         match sym {
             Some(TokenValue::ThinArrow) => {
-                match self.maybe_fn(None, attrs, false) {
+                match self.maybe_fn(None, &vec![], attrs, false) {
                     Some(_fn) => Some(LambdaOrDecl::Lambda(_fn)),
                     None => None
                 }                        
@@ -2318,6 +3095,7 @@ impl<'a> Parser<'a> {
     pub fn maybe_fn (
         &mut self,
         id: Option<&Token>,
+        generics: &Vec<GenericParam>,
         attrs: &Option<Vec<Attr>>, 
         is_method: bool
     )  -> Option<Fn> {
@@ -2334,16 +3112,28 @@ impl<'a> Parser<'a> {
         let block = self.require_block();
         let block = self.res_to_opt(block)?;        
 
+        // parsed in the scope of the params, around the scope of the body
+        let where_decls = if id.is_some() {
+            let where_decls = self.optional_where_decls();
+            self.res_to_opt(where_decls)?
+        } else {
+            vec![]
+        };
+
         self.symtab().exit_scope();
 
         Some(
             Fn{
                 attrs: attrs.clone(),
+                vis: Visibility::Private,
                 is_method,
+                receiver: None,
                 name: id.cloned(),
+                generics: generics.clone(),
                 params,
                 ret_type,
-                block
+                block,
+                where_decls
             }
         )                    
             
@@ -2357,10 +3147,11 @@ impl<'a> Parser<'a> {
     pub fn require_fn(
         &mut self,
         id: Option<&Token>,
+        generics: &Vec<GenericParam>,
         attrs: &Option<Vec<Attr>>,
         is_method: bool
     ) -> Result<Fn, Error>{
-        match self.maybe_fn(id, attrs, is_method) {
+        match self.maybe_fn(id, generics, attrs, is_method) {
             None => Err( error!("expecting function declaration".to_string(), self.lookahead()) ),
             Some(params) => Ok(params)
         }
@@ -2418,24 +3209,72 @@ impl<'a> Parser<'a> {
                 TokenValue::Eof => break,
                 x => {
                     starts_with.push(x);
-                    if starts_with.len() == 3 { 
+                    if starts_with.len() == 4 { 
                         break; 
                     }
                 }   
             }
             i += 1;
         }
+        // a single shorthand field needs a trailing comma, `{ x }` is a block unless it follows the name of a struct,
+        //  see is_lone_shorthand()
+        //  and the spread is `{ ..base }` so a match on a range, `match x { ..0 => ...`, is not a literal
         match starts_with[..] {
             [
                 TokenValue::OpenCurly, 
                 TokenValue::Id(_),
-                TokenValue::Colon
+                TokenValue::Colon | TokenValue::Comma,
+                ..
+            ] 
+            | [
+                TokenValue::OpenCurly, 
+                TokenValue::DoubleDot,
+                TokenValue::Id(_),
+                TokenValue::CloseCurly | TokenValue::Dot
             ] => true, 
             _ => false
         }
     }
 }
 
+//================
+// is_lone_shorthand()
+//================ 
+// `Point { y }`, a single shorthand field right after the name of the struct
+impl<'a> Parser<'a> {
+    fn is_lone_shorthand(&mut self) -> bool {
+        if !matches!(self.current().value, TokenValue::Id(_)) { return false }
+        let mut i = 1;
+        let mut starts_with = vec![];
+        while starts_with.len() < 3 {
+            let t = self.lookahead_n_ws(i);
+            match t.value  {
+                TokenValue::NewLine => (),
+                TokenValue::Eof => break,
+                x => starts_with.push(x)
+            }
+            i += 1;
+        }
+        matches!(
+            starts_with[..],
+            [TokenValue::OpenCurly, TokenValue::Id(_), TokenValue::CloseCurly]
+        )
+    }
+}
+
+//================
+// require_cond()
+//================ 
+impl<'a> Parser<'a> {
+    fn require_cond(&mut self) -> Result<Expr, Error> {
+        let cond = self.cond;
+        self.cond = true;
+        let expr = self.require_expr();
+        self.cond = cond;
+        expr
+    }
+}
+
 //================
 // require_block()
 //================ 
@@ -2467,7 +3306,7 @@ impl<'a> Parser<'a> {
         if let Some(BlockElement::Expr(expr)) = els.last() {
             let expr = expr.clone();
             els.pop();
-            els.push(BlockElement::Expr(Expr::Ret(Box::new(expr))));
+            els.push(BlockElement::Ret(Ret{ token: None, expr: Some(Box::new(expr)) }));
         }
     }
 }
@@ -2485,13 +3324,15 @@ impl<'a> Parser<'a> {
             let t = self.lookahead();
             let expr = Expr::StructLiteral(self.require_struct_lietral()?);
             // self.symtab().exit_scope();
-            return Ok(vec![BlockElement::Expr(expr)])
+            let mut els = vec![BlockElement::Expr(expr)];
+            self.return_last_expr(&mut els);
+            return Ok(els)
         } else {
             self.next(); 
         }
 
         loop {
-            if self.expect_let() {
+            if self.expect_let() && !self.is_let_in() {
                 let decl = self.require_let_decl()?;
                 els.push(BlockElement::Decl(decl));
             } else if self.expect_short_decl() {
@@ -2499,6 +3340,10 @@ impl<'a> Parser<'a> {
                     self.require_terminator()?;
                     els.push(BlockElement::Decl(decl));                    
                 }                
+            } else if self.expect_ret() {
+                let ret = self.require_ret()?;
+                self.require_terminator()?;
+                els.push(BlockElement::Ret(ret));
             } else {
                 if let Some(expr) = self.maybe_expr() {
                     self.require_terminator()?;
@@ -2514,6 +3359,24 @@ impl<'a> Parser<'a> {
     }
 }
 
+//================
+// require_ret()
+//================ 
+// `ret` or `ret value`, the value has to start on the same line
+impl<'a> Parser<'a> {
+    pub fn require_ret (
+        &mut self,
+    )  -> Result<Ret, Error> {
+        let token = self.next();
+        let expr = if self.expect_expr_on_line() {
+            Some(Box::new(self.require_expr()?))
+        } else {
+            None
+        };
+        Ok( Ret { token: Some(token), expr } )
+    }
+}
+
 //================
 // require_block_one_liner()
 //================ 
@@ -2523,7 +3386,7 @@ impl<'a> Parser<'a> {
     )  -> Result<Vec<BlockElement>, Error> {
 
         let mut els = vec![];        
-        if self.expect_let() {
+        if self.expect_let() && !self.is_let_in() {
             let decl = self.require_let_decl()?;
             els.push(BlockElement::Decl(decl));
         } else if self.expect_short_decl() {
@@ -2531,9 +3394,19 @@ impl<'a> Parser<'a> {
                 self.require_terminator()?;
                 els.push(BlockElement::Decl(decl));    
             }                
+        } else if self.expect_ret() {
+            let ret = self.require_ret()?;
+            if !self.expect_close_paren() && !self.expect_comma() {
+                self.require_terminator()?;
+            }
+            els.push(BlockElement::Ret(ret));
         } else {
             if let Some(expr) = self.maybe_expr() {
-                self.require_terminator()?;
+                // a one liner lambda passed as an argument ends at the `,` or `)` that follows it,
+                //  a one liner function can be followed by its `where` bindings
+                if !self.expect_close_paren() && !self.expect_comma() && !self.expect_where() {
+                    self.require_terminator()?;
+                }
                 els.push(BlockElement::Expr(expr));
                 
             } else {
@@ -2621,13 +3494,15 @@ impl<'a> Parser<'a> {
     ) -> Result<Token, Error> {
         let mut i = 1;
         let mut level = 0;
-        let mut res: Result<Token,Error>;
+        let res: Result<Token,Error>;
         loop {
             let t = self.lookahead_n_ws(i);
             if TokenValue::Eof == t.value {
+                // lookahead_n_ws() keeps returning the eof token past the end
                 res = Err(
                     error!("unclosed , expected `)`".to_string(), t)
                 );
+                break;
             } else if open_sym == t.value {
                 level += 1
             } else if close_sym == t.value {
@@ -2635,8 +3510,6 @@ impl<'a> Parser<'a> {
                 if level == 0 {
                     res = Ok(self.lookahead_n_ws(i+1));
                     break;
-                } else {
-                    level -= 1;
                 }
             }
             i += 1;
@@ -2658,7 +3531,7 @@ impl<'a> Parser<'a> {
         let t = self.res_to_opt(after_close_paren)?;  
         match t.value {
             TokenValue::ThinArrow => {
-                self.maybe_fn(None, attrs, false) 
+                self.maybe_fn(None, &vec![], attrs, false) 
             },
             _ => None
         }
@@ -2677,7 +3550,7 @@ impl<'a> Parser<'a> {
         if !self.expect_match() { return None }
 
         let t = self.next();
-        let expr = self.require_expr();
+        let expr = self.require_cond();
         let open_curly = self.expect_open_curly();
         if open_curly { self.next(); }
         let expr = self.res_to_opt(expr)?;
@@ -2699,6 +3572,7 @@ impl<'a> Parser<'a> {
 
         Some( 
             Match { 
+                token: t,
                 expr: Box::new(expr), 
                 arms 
             }
@@ -2715,9 +3589,18 @@ impl<'a> Parser<'a> {
     ) -> Option<Arm> {
         if self.expect_close_curly() { return None }
 
+        let token = self.lookahead();
         let pattern = self.require_pattern();
         let pattern = self.res_to_opt(pattern)?;
 
+        let guard = if self.expect_if() {
+            self.next();
+            let guard = self.require_expr();
+            Some(self.res_to_opt(guard)?)
+        } else {
+            None
+        };
+
         let arrow = self.require_arrow();
         let _ = self.res_to_opt(arrow)?;
         
@@ -2726,7 +3609,9 @@ impl<'a> Parser<'a> {
 
         Some(
             Arm {
+                token,
                 pattern,
+                guard,
                 block: expr
             }
         )
@@ -2749,11 +3634,105 @@ impl<'a> Parser<'a> {
         let in_expr = self.res_to_opt(in_expr)?;  
         let block = self.require_block();
         let block = self.res_to_opt(block)?;        
-        Some( For { in_expr, block } )
+        Some( For { in_expr, block, label: None } )
     }
 }
 
 
+//================
+// maybe_loop()
+//================
+impl<'a> Parser<'a> {
+    fn maybe_loop(
+        &mut self,    
+    ) -> Option<Loop> {
+        if !self.expect_loop() { return None };
+        self.next();
+        let block = self.require_block();
+        let block = self.res_to_opt(block)?;        
+        Some( Loop { block, label: None } )
+    }
+}
+
+//================
+// maybe_label()
+//================
+// `$outer`
+impl<'a> Parser<'a> {
+    fn maybe_label(
+        &mut self,    
+    ) -> Option<Token> {
+        if !self.expect_label() { return None };
+        self.next();
+        let id = self.require_id();
+        self.res_to_opt(id)
+    }
+}
+
+//================
+// maybe_labelled_loop()
+//================
+// `$outer: for x in xs { ... }`, the label can be given to `break` and `continue` in nested loops
+impl<'a> Parser<'a> {
+    fn maybe_labelled_loop(
+        &mut self,    
+    ) -> Option<Expr> {
+        let label = self.maybe_label()?;
+        let colon = self.require_colon();
+        let _ = self.res_to_opt(colon)?;
+
+        if let Some(mut e) = self.maybe_for() {
+            e.label = Some(label);
+            Some( Expr::For(e) )
+        } else if let Some(mut e) = self.maybe_while() {
+            e.label = Some(label);
+            Some( Expr::While(e) )
+        } else if let Some(mut e) = self.maybe_loop() {
+            e.label = Some(label);
+            Some( Expr::Loop(e) )
+        } else {
+            let t = self.lookahead();
+            self.insert_err(error!("expecting a loop after the label".to_string(), t));
+            None
+        }
+    }
+}
+
+//================
+// maybe_break()
+//================
+// `break`, `break $outer`, `break value`
+impl<'a> Parser<'a> {
+    fn maybe_break(
+        &mut self,    
+    ) -> Option<Break> {
+        if !self.expect_break() { return None };
+        let token = self.next();
+        let label = self.maybe_label();
+        let expr = if self.expect_expr_on_line() {
+            let expr = self.require_expr();
+            Some(Box::new(self.res_to_opt(expr)?))
+        } else {
+            None
+        };
+        Some( Break { token, label, expr } )
+    }
+}
+
+//================
+// maybe_continue()
+//================
+impl<'a> Parser<'a> {
+    fn maybe_continue(
+        &mut self,    
+    ) -> Option<Continue> {
+        if !self.expect_continue() { return None };
+        let token = self.next();
+        let label = self.maybe_label();
+        Some( Continue { token, label } )
+    }
+}
+
 //================
 // require_in_expr()
 //===============
@@ -2765,7 +3744,7 @@ impl<'a> Parser<'a> {
         let pattern = self.require_pattern()?;
 
         self.require_in()?;
-        let expr = self.require_expr()?;
+        let expr = self.require_cond()?;
 
         Ok(InExpr { pattern, expr: Box::new(expr) })
     }
@@ -2780,7 +3759,7 @@ impl<'a> Parser<'a> {
     ) -> Option<While> {
         if !self.expect_while() { return None };
         self.next();
-        let expr = self.require_expr();
+        let expr = self.require_cond();
         let expr = self.res_to_opt(expr)?;
 
         let block = self.require_block();
@@ -2798,7 +3777,8 @@ impl<'a> Parser<'a> {
         Some( 
             While { 
                 expr: Box::new(expr), 
-                block: Box::new(block)
+                block: Box::new(block),
+                label: None
             } 
         )        
     }
@@ -2855,7 +3835,7 @@ impl<'a> Parser<'a> {
     ) -> Option<IfBranch> {
         if !self.expect_if() { return None };
         self.next();
-        let expr = self.require_expr();
+        let expr = self.require_cond();
         let expr = self.res_to_opt(expr)?;
 
         let block = self.require_block();
@@ -3042,8 +4022,8 @@ impl<'a> Parser<'a> {
     ) -> Option<Expr> {
         if let Some(e) = self.maybe_primitive_literal() {
             Some(e)
-        } else if let Some(e) = self.maybe_list() {
-            Some(Expr::List(e))    
+        } else if let Some(e) = self.maybe_list_or_map() {
+            Some(e)    
         } else if let Some(e) = self.maybe_tuple() {
             Some(Expr::Tuple(e))    
         } else if let Some(e) = self.maybe_struct_literal() {
@@ -3069,15 +4049,29 @@ impl<'a> Parser<'a> {
             Some(e)
         } else if let Some(t) = self.maybe_id() {
             self.symtab().insert_ref(&t);
-            Some(Expr::Ref(t))            
+            if let Some(e) = self.maybe_generic_ref(&t) {
+                Some(Expr::GenericRef(e))
+            } else {
+                Some(Expr::Ref(t))
+            }
         } else if let Some(e) = self.maybe_match() {
             Some( Expr::Match(e) )
         } else if let Some(e) = self.maybe_for() {
             Some( Expr::For(e) )
         } else if let Some(e) = self.maybe_while() {
             Some( Expr::While(e) )
+        } else if let Some(e) = self.maybe_loop() {
+            Some( Expr::Loop(e) )
+        } else if let Some(e) = self.maybe_labelled_loop() {
+            Some(e)
+        } else if let Some(e) = self.maybe_break() {
+            Some( Expr::Break(e) )
+        } else if let Some(e) = self.maybe_continue() {
+            Some( Expr::Continue(e) )
         } else if let Some(e) = self.maybe_if() {
             Some( Expr::If(e) )
+        } else if let Some(e) = self.maybe_let_in() {
+            Some( Expr::LetIn(e) )
         }  else if let Some(e) = self.maybe_ok() {
             Some(e)
         }  else if let Some(e) = self.maybe_err() {
@@ -3110,9 +4104,223 @@ impl<'a> Parser<'a> {
 //================
 // maybe_expr()
 //================
+// `|>` binds looser than every operator but the assignments, so the operands of a pipe are parsed first
+//  and the pipe is desugared into a call: `x |> f(a) |> g` is `g(f(x, a))`
 impl<'a> Parser<'a> {
     pub fn maybe_expr (
         &mut self,
+    ) -> Option<Expr>{
+        let mut expr = self.maybe_expr_without_pipe()?;
+        while self.expect_pipe_forward() {
+            let pipe = self.next();
+            let call = self.require_pipe_call(expr, &pipe);
+            expr = self.res_to_opt(call)?;
+        }
+        Some(expr)
+    }
+}
+
+//================
+// require_pipe_call()
+//================
+impl<'a> Parser<'a> {
+    fn require_pipe_call (
+        &mut self,
+        arg: Expr,
+        pipe: &Token
+    ) -> Result<Expr, Error>{
+        // `x |> .trim()` calls a method on the piped value
+        if self.expect_dot() {
+            let dot = self.next();
+            let method = self.require_prim()?;
+            let method = self.maybe_fn_call_args(method);
+            let method = match method {
+                Some(method) => method,
+                None => return Err(error!("expecting a method call after `|>`".to_string(), dot))
+            };
+            return Ok(
+                Expr::BinOp(
+                    BinOp {
+                        l_opr: Box::new(arg),
+                        r_opr: Box::new(method),
+                        op: dot
+                    }
+                )
+            )
+        }
+
+        let t = self.lookahead();
+        let callee = self.require_expr_without_pipe()?;
+        match pipe_into(callee, arg, pipe) {
+            Some(call) => Ok(call),
+            None => Err(error!("expecting a function, a call or a lambda after `|>`".to_string(), t))
+        }
+    }
+}
+
+//================
+// maybe_fn_call_args()
+//================
+// a method name followed by its args, `trim` alone becomes `trim()`
+impl<'a> Parser<'a> {
+    fn maybe_fn_call_args (
+        &mut self,
+        method: Expr
+    ) -> Option<Expr>{
+        match method {
+            Expr::Ref(_) | Expr::GenericRef(_) => {
+                if self.expect_arg_list() {
+                    let args = self.require_fn_call();
+                    let args = self.res_to_opt(args)?;
+                    Some(call(method, args, &self.lookahead()))
+                } else {
+                    let t = self.lookahead();
+                    Some(call(method, Expr::Tuple(Tuple{items: vec![]}), &t))
+                }
+            },
+            _ => None
+        }
+    }
+}
+
+//================
+// pipe_into()
+//================
+// inserts the piped value as the first argument of the call on the right hand side of `|>`
+fn pipe_into(
+    callee: Expr,
+    arg: Expr,
+    pipe: &Token
+) -> Option<Expr> {
+    match callee {
+        // x |> f     ==>   f(x)
+        Expr::Ref(_) 
+        | Expr::GenericRef(_)
+        | Expr::Fn(_) => {
+            Some(call(callee, Expr::Tuple(Tuple{items: vec![arg]}), pipe))
+        },
+        // x |> f(a)  ==>   f(x, a)
+        Expr::BinOp(BinOp{l_opr, r_opr, op}) if matches!(op.value, TokenValue::OpenParen) => {
+            match *r_opr {
+                Expr::Tuple(mut args) => {
+                    args.items.insert(0, arg);
+                    Some(
+                        Expr::BinOp(
+                            BinOp {
+                                l_opr,
+                                r_opr: Box::new(Expr::Tuple(args)),
+                                op
+                            }
+                        )
+                    )
+                },
+                _ => None
+            }
+        },
+        // x |> m.f(a)  ==>  m.f(x, a)
+        Expr::BinOp(BinOp{l_opr, r_opr, op}) if matches!(op.value, TokenValue::Dot) => {
+            let r_opr = pipe_into(*r_opr, arg, pipe)?;
+            Some(
+                Expr::BinOp(
+                    BinOp {
+                        l_opr,
+                        r_opr: Box::new(r_opr),
+                        op
+                    }
+                )
+            )
+        },
+        _ => None
+    }
+}
+
+//================
+// call()
+//================
+fn call(
+    callee: Expr,
+    args: Expr,
+    t: &Token
+) -> Expr {
+    Expr::BinOp(
+        BinOp {
+            l_opr: Box::new(callee),
+            r_opr: Box::new(args),
+            op: Token::new(TokenValue::OpenParen, t.location.clone())
+        }
+    )
+}
+
+//================
+// maybe_expr_without_pipe()
+//================
+// `..` binds looser than the other operators: `a + 1..b * 2` is `(a + 1)..(b * 2)`
+impl<'a> Parser<'a> {
+    pub fn maybe_expr_without_pipe (
+        &mut self,
+    ) -> Option<Expr>{
+        if self.expect_range() {
+            let range = self.require_range(None, false);
+            return Some(Expr::Range(self.res_to_opt(range)?))
+        }
+
+        let expr = self.maybe_expr_without_range()?;
+        if self.expect_infix_range() {
+            let range = self.require_range(Some(expr), false);
+            return Some(Expr::Range(self.res_to_opt(range)?))
+        }
+        Some(expr)
+    }
+}
+
+//================
+// require_range()
+//================
+// the ends of a range pattern can only be literals
+impl<'a> Parser<'a> {
+    fn require_range (
+        &mut self,
+        start: Option<Expr>,
+        is_pattern: bool
+    ) -> Result<Range, Error>{
+        let op = self.next();
+        let inclusive = matches!(op.value, TokenValue::DoubleDotEqual);
+
+        let end = if !self.expect_expr_on_line() {
+            None
+        } else if is_pattern {
+            let t = self.lookahead();
+            match self.maybe_primitive_literal() {
+                Some(end) => Some(Box::new(end)),
+                None => return Err(error!("expecting a literal at the end of the range pattern".to_string(), t))
+            }
+        } else {
+            Some(Box::new(self.require_expr_without_range()?))
+        };
+
+        if inclusive && end.is_none() {
+            return Err(error!("an inclusive range `..=` needs an end".to_string(), op))
+        }
+        if start.is_none() && end.is_none() && is_pattern {
+            return Err(error!("expecting a literal after `..`".to_string(), op))
+        }
+
+        Ok(
+            Range {
+                start: start.map(Box::new),
+                end,
+                inclusive
+            }
+        )
+    }
+}
+
+//================
+// maybe_expr_without_range()
+//================
+impl<'a> Parser<'a> {
+    pub fn maybe_expr_without_range (
+        &mut self,
     ) -> Option<Expr>{
         let expr = self.maybe_prim();
         match expr {
@@ -3138,6 +4346,36 @@ impl<'a> Parser<'a> {
     }
 }
 
+//================
+// require_expr_without_range()
+//================
+impl<'a> Parser<'a> {
+    fn require_expr_without_range (
+        &mut self,
+    ) -> Result<Expr, Error>{
+        let t = self.lookahead();
+        match self.maybe_expr_without_range() {
+            None => Err(error!( format!("expecting an expression: {:?}", t.value), t  )),
+            Some(expr) => Ok(expr)
+        }
+    }
+}
+
+//================
+// require_expr_without_pipe()
+//================
+impl<'a> Parser<'a> {
+    fn require_expr_without_pipe (
+        &mut self,
+    ) -> Result<Expr, Error>{
+        let t = self.lookahead();
+        match self.maybe_expr_without_pipe() {
+            None => Err(error!( format!("expecting an expression: {:?}", t.value), t  )),
+            Some(expr) => Ok(expr)
+        }
+    }
+}
+
 //================
 // require_expr()
 //================
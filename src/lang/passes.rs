@@ -0,0 +1,762 @@
+use std::collections::HashSet;
+
+use super::{
+    token::{
+        Token,
+        TokenValue
+    },
+    syntax_tree::ast::*,
+    hir::{
+        Hir,
+        NEUTRAL_LANG
+    },
+    type_checker::int_range,
+    error::{
+        Error,
+    },
+};
+
+use crate::{
+    warning,
+    transl::lexicon::Lexicon
+};
+
+//================
+//   PassOptions
+//================
+// `seen compile --no-pass const_fold --dump-ir`
+#[derive(Clone, Debug, Default)]
+pub struct PassOptions {
+    pub disabled: Vec<String>,      // the names of the passes that are turned off
+    pub dump_ir: bool               // prints the HIR before and after every pass that runs
+}
+
+impl PassOptions {
+    //---------------------
+    //  is_enabled()
+    //---------------------
+    pub fn is_enabled(&self, pass: &str) -> bool {
+        !self.disabled.iter().any(|name| name == pass)
+    }
+}
+
+//================
+//   Pass
+//================
+// one step of the middle-end, it rewrites the HIR of a module and can report warnings
+pub trait Pass {
+    //---------------------
+    //  name()
+    //---------------------
+    // the name given to `--no-pass`
+    fn name(&self) -> &'static str;
+
+    //---------------------
+    //  run()
+    //---------------------
+    fn run(
+        &mut self,
+        hir: &mut Hir,
+        lexicon: &Lexicon
+    ) -> Vec<Error>;
+}
+
+//================
+//   Pipeline
+//================
+pub struct Pipeline<'a> {
+    lexicon: &'a Lexicon,
+    options: PassOptions,
+    passes: Vec<Box<dyn Pass>>
+}
+
+impl<'a> Pipeline<'a> {
+    //---------------------
+    //  new()
+    //---------------------
+    // the builtin passes, in the order they run
+    pub fn new(options: PassOptions) -> Self {
+        Self {
+            lexicon: Lexicon::embedded(),
+            options,
+            passes: vec![
+                Box::new(Unreachable),
+                Box::new(ConstFold),
+                Box::new(DeadBranch),
+                Box::new(UnusedFn)
+            ]
+        }
+    }
+
+    //---------------------
+    //  lexicon()
+    //---------------------
+    pub fn lexicon(&mut self, lexicon: &'a Lexicon) {
+        self.lexicon = lexicon;
+    }
+
+    //---------------------
+    //  names()
+    //---------------------
+    pub fn names(&self) -> Vec<&'static str> {
+        self.passes
+            .iter()
+            .map(|pass| pass.name())
+            .collect()
+    }
+
+    //---------------------
+    //  check_options()
+    //---------------------
+    pub fn check_options(&self) -> Result<(), String> {
+        let names = self.names();
+        match self.options.disabled.iter().find(|name| !names.contains(&name.as_str())) {
+            Some(name) => Err(format!("unknown pass `{}`, the passes are: {}", name, names.join(", "))),
+            None => Ok(())
+        }
+    }
+
+    //---------------------
+    //  run()
+    //---------------------
+    // returns the warnings of the passes, `path` names the module in the dumps
+    pub fn run(
+        &mut self,
+        path: &str,
+        hir: &mut Hir
+    ) -> Vec<Error> {
+        let mut warnings = vec![];
+        for pass in self.passes.iter_mut() {
+            if !self.options.is_enabled(pass.name()) { continue }
+            if self.options.dump_ir {
+                dump(path, "before", pass.name(), hir);
+            }
+            warnings.extend(pass.run(hir, self.lexicon));
+            if self.options.dump_ir {
+                dump(path, "after", pass.name(), hir);
+            }
+        }
+        warnings
+    }
+}
+
+//================
+//   dump()
+//================
+fn dump(
+    path: &str,
+    when: &str,
+    pass: &str,
+    hir: &Hir
+) {
+    println!("== hir of `{}` {} `{}` ==", path, when, pass);
+    for el in hir.items.iter() {
+        println!("{:?}", el);
+    }
+    println!();
+}
+
+//================
+//   Unreachable
+//================
+// warns about the statements written after a `ret`, they are kept
+struct Unreachable;
+
+impl Pass for Unreachable {
+    fn name(&self) -> &'static str { "unreachable" }
+
+    fn run(
+        &mut self,
+        hir: &mut Hir,
+        lexicon: &Lexicon
+    ) -> Vec<Error> {
+        let mut visitor = UnreachableVisitor{ warnings: vec![] };
+        walk_items(&mut hir.items, &mut visitor);
+        visitor.warnings
+    }
+}
+
+struct UnreachableVisitor {
+    warnings: Vec<Error>
+}
+
+impl Visit for UnreachableVisitor {
+    fn block(&mut self, block: &mut Vec<BlockElement>) {
+        let ret = block
+            .iter()
+            .enumerate()
+            .find_map(|(i, el)| match el {
+                BlockElement::Ret(Ret{token: Some(t), ..}) if i < block.len() - 1 => Some(t),
+                _ => None
+            });
+        if let Some(t) = ret {
+            self.warnings.push(warning!("the code after this `ret` is never run".to_string(), t));
+        }
+    }
+}
+
+//================
+//   ConstFold
+//================
+// `1 + 2 * 3` is written `7`, the literals are western digits in the HIR whatever digits the source used.
+//  a value that does not fit in the declared type ( `int` when there is none ) is left to rustc
+struct ConstFold;
+
+impl Pass for ConstFold {
+    fn name(&self) -> &'static str { "const_fold" }
+
+    fn run(
+        &mut self,
+        hir: &mut Hir,
+        lexicon: &Lexicon
+    ) -> Vec<Error> {
+        walk_items(&mut hir.items, &mut ConstFoldVisitor{ lexicon, ranges: vec![] });
+        vec![]
+    }
+}
+
+struct ConstFoldVisitor<'a> {
+    lexicon: &'a Lexicon,
+    ranges: Vec<(i128, u128)>       // the range of each declared type the folded expression is in
+}
+
+impl<'a> ConstFoldVisitor<'a> {
+    // the range of the innermost declared type, `int` outside of any
+    fn range(&self) -> (i128, u128) {
+        self.ranges
+            .last()
+            .cloned()
+            .or(int_range("type.int"))
+            .unwrap()
+    }
+}
+
+impl<'a> Visit for ConstFoldVisitor<'a> {
+    fn enter_type(&mut self, _type: Option<&Type>) {
+        let range = match _type {
+            Some(Type::PrimitiveType(PrimitiveType{id})) => self.lexicon
+                .concept("type", &NEUTRAL_LANG, &id.value.to_string())
+                .and_then(int_range),
+            _ => None
+        };
+        let range = range.unwrap_or(self.range());
+        self.ranges.push(range);
+    }
+
+    fn leave_type(&mut self) {
+        self.ranges.pop();
+    }
+
+    fn expr(&mut self, expr: &mut Expr) {
+        let folded = match expr {
+            Expr::BinOp(bin_op) => fold_bin_op(bin_op, self.range(), self.lexicon),
+            Expr::PreUniOp(UniOp{opr, op}) if matches!(op.value, TokenValue::Exclamation) => {
+                bool_value(opr, self.lexicon).map(|v| bool_literal(!v, op, self.lexicon))
+            },
+            _ => None
+        };
+        if let Some(folded) = folded {
+            *expr = folded;
+        }
+    }
+}
+
+//================
+//   fold_bin_op()
+//================
+fn fold_bin_op(
+    bin_op: &BinOp,
+    range: (i128, u128),
+    lexicon: &Lexicon
+) -> Option<Expr> {
+    let op = &bin_op.op;
+    if let (Some(l), Some(r)) = (int_value(&bin_op.l_opr), int_value(&bin_op.r_opr)) {
+        return match op.value {
+            TokenValue::Add => int_literal(l.checked_add(r)?, range, op),
+            TokenValue::Sub
+            | TokenValue::Minus => int_literal(l.checked_sub(r)?, range, op),
+            TokenValue::Mul => int_literal(l.checked_mul(r)?, range, op),
+            TokenValue::Div if r != 0 => int_literal(l / r, range, op),
+            TokenValue::Perc if r != 0 => int_literal(l % r, range, op),
+            TokenValue::Eq => Some(bool_literal(l == r, op, lexicon)),
+            TokenValue::NE => Some(bool_literal(l != r, op, lexicon)),
+            TokenValue::LT => Some(bool_literal(l < r, op, lexicon)),
+            TokenValue::LE => Some(bool_literal(l <= r, op, lexicon)),
+            TokenValue::GT => Some(bool_literal(l > r, op, lexicon)),
+            TokenValue::GE => Some(bool_literal(l >= r, op, lexicon)),
+            _ => None
+        }
+    }
+    if let (Some(l), Some(r)) = (bool_value(&bin_op.l_opr, lexicon), bool_value(&bin_op.r_opr, lexicon)) {
+        return match op.value {
+            TokenValue::LogicalAnd => Some(bool_literal(l && r, op, lexicon)),
+            TokenValue::LogicalOr => Some(bool_literal(l || r, op, lexicon)),
+            TokenValue::Eq => Some(bool_literal(l == r, op, lexicon)),
+            TokenValue::NE => Some(bool_literal(l != r, op, lexicon)),
+            _ => None
+        }
+    }
+    None
+}
+
+//================
+//   int_value()
+//================
+// `7`, or `-7` which the parser keeps as a prefix op
+fn int_value(expr: &Expr) -> Option<i128> {
+    match expr {
+        Expr::Int(t) => t.value.to_string().replace('_', "").parse().ok(),
+        Expr::PreUniOp(UniOp{opr, op}) if matches!(op.value, TokenValue::Minus) => int_value(opr).map(|v| -v),
+        _ => None
+    }
+}
+
+//================
+//   int_literal()
+//================
+fn int_literal(
+    v: i128,
+    (min, max): (i128, u128),
+    op: &Token
+) -> Option<Expr> {
+    if v < min || (v > 0 && v as u128 > max) { return None }
+    let literal = Expr::Int(Token::new(TokenValue::Int(v.abs().to_string()), op.location.clone()));
+    if v < 0 {
+        Some(Expr::PreUniOp(UniOp {
+            opr: Box::new(literal),
+            op: Token::new(TokenValue::Minus, op.location.clone())
+        }))
+    } else {
+        Some(literal)
+    }
+}
+
+//================
+//   bool_value()
+//================
+fn bool_value(
+    expr: &Expr,
+    lexicon: &Lexicon
+) -> Option<bool> {
+    match expr {
+        Expr::Bool(t) => Some(lexicon.is("kw.true", &NEUTRAL_LANG, &t.value.to_string())),
+        _ => None
+    }
+}
+
+//================
+//   bool_literal()
+//================
+fn bool_literal(
+    v: bool,
+    op: &Token,
+    lexicon: &Lexicon
+) -> Expr {
+    let concept = if v { "kw.true" } else { "kw.false" };
+    let word = lexicon.word(&NEUTRAL_LANG, concept);
+    Expr::Bool(Token::new(TokenValue::Bool(word), op.location.clone()))
+}
+
+//================
+//   DeadBranch
+//================
+// an `if` or a `match` on a constant keeps only the branch that is taken, an `if` that takes no
+//  branch is removed
+struct DeadBranch;
+
+impl Pass for DeadBranch {
+    fn name(&self) -> &'static str { "dead_branch" }
+
+    fn run(
+        &mut self,
+        hir: &mut Hir,
+        lexicon: &Lexicon
+    ) -> Vec<Error> {
+        walk_items(&mut hir.items, &mut DeadBranchVisitor{ lexicon });
+        vec![]
+    }
+}
+
+struct DeadBranchVisitor<'a> {
+    lexicon: &'a Lexicon
+}
+
+impl<'a> Visit for DeadBranchVisitor<'a> {
+    fn expr(&mut self, expr: &mut Expr) {
+        let taken = match expr {
+            Expr::If(_if) => taken_branch(_if, self.lexicon),
+            Expr::Match(_match) => taken_arm(_match).map(Some),
+            _ => return
+        };
+        match taken {
+            Some(Some(block)) => *expr = Expr::Block(block),
+            Some(None) => *expr = Expr::Unit,
+            None => ()
+        }
+    }
+
+    fn block(&mut self, block: &mut Vec<BlockElement>) {
+        block.retain(|el| !matches!(el, BlockElement::Expr(Expr::Unit)));
+    }
+}
+
+//================
+//   taken_branch()
+//================
+// drops the branches that are never taken, and the ones after a branch that is always taken.
+//  `Some(Some(block))` when the `if` is always one block, `Some(None)` when it is never taken
+fn taken_branch(
+    _if: &mut If,
+    lexicon: &Lexicon
+) -> Option<Option<Vec<BlockElement>>> {
+    let mut branches = vec![];
+    for mut branch in _if.branches.drain(..) {
+        match branch.expr.as_deref().map(|expr| bool_value(expr, lexicon)) {
+            Some(Some(false)) => continue,
+            Some(Some(true)) => {
+                branch.expr = None;
+                branches.push(branch);
+                break
+            },
+            Some(None) => branches.push(branch),
+            None => {
+                branches.push(branch);
+                break
+            }
+        }
+    }
+    match branches.first() {
+        None => Some(None),
+        Some(IfBranch{expr: None, ..}) => Some(Some(branches.remove(0).block)),
+        Some(_) => {
+            _if.branches = branches;
+            None
+        }
+    }
+}
+
+//================
+//   taken_arm()
+//================
+// the first arm whose literal equals the matched literal, or a wildcard, as long as no arm before it
+//  could be taken
+fn taken_arm(_match: &Match) -> Option<Vec<BlockElement>> {
+    let value = literal_value(&_match.expr)?;
+    for arm in _match.arms.iter() {
+        if arm.guard.is_some() { return None }
+        match pattern_matches(&arm.pattern, &value)? {
+            true => return Some(arm.block.clone()),
+            false => continue
+        }
+    }
+    None
+}
+
+//================
+//   pattern_matches()
+//================
+// `None` when it is not known without running the program
+fn pattern_matches(
+    pattern: &Pattern,
+    value: &String
+) -> Option<bool> {
+    match pattern {
+        Pattern::Wildcard => Some(true),
+        Pattern::PrimitiveLiteral(expr) => literal_value(expr).map(|v| v == *value),
+        Pattern::Or(patterns) => {
+            let mut matches = false;
+            for pattern in patterns {
+                matches |= pattern_matches(pattern, value)?;
+            }
+            Some(matches)
+        },
+        _ => None
+    }
+}
+
+//================
+//   literal_value()
+//================
+// literals of different kinds never match, the kind is part of the value
+fn literal_value(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Bool(t) => Some(format!("bool {}", t.value)),
+        Expr::Char(t) => Some(format!("char {}", t.value)),
+        Expr::Str(t) => Some(format!("str {}", t.value)),
+        Expr::Int(_)
+        | Expr::PreUniOp(_) => int_value(expr).map(|v| format!("int {}", v)),
+        _ => None
+    }
+}
+
+//================
+//   UnusedFn
+//================
+// removes the private functions of the module that cannot be reached from the other items, e.g. the
+//  main function or the public ones, so functions that only name each other are removed too. functions
+//  with attributes are kept, e.g. `@test`
+struct UnusedFn;
+
+impl Pass for UnusedFn {
+    fn name(&self) -> &'static str { "unused_fn" }
+
+    fn run(
+        &mut self,
+        hir: &mut Hir,
+        lexicon: &Lexicon
+    ) -> Vec<Error> {
+        // the names each item refers to
+        let names = hir.items
+            .iter_mut()
+            .map(|el| {
+                let mut visitor = NamesVisitor{ names: HashSet::new() };
+                walk_item(el, &mut visitor);
+                visitor.names
+            })
+            .collect::<Vec<_>>();
+        let removable = hir.items
+            .iter()
+            .map(|el| match el {
+                ModElement::Fn(Fn{vis: Visibility::Private, attrs, name: Some(name), ..})
+                    if attrs.as_ref().map_or(true, |attrs| attrs.is_empty()) => Some(name.value.to_string()),
+                _ => None
+            })
+            .collect::<Vec<_>>();
+
+        // the items that are always kept are the roots, a removable function is reached when a reached item names it
+        let mut reached = removable
+            .iter()
+            .map(|name| name.is_none())
+            .collect::<Vec<_>>();
+        let mut pending = (0..reached.len())
+            .filter(|i| reached[*i])
+            .collect::<Vec<_>>();
+        while let Some(i) = pending.pop() {
+            for (j, name) in removable.iter().enumerate() {
+                if !reached[j] && name.as_ref().is_some_and(|name| names[i].contains(name)) {
+                    reached[j] = true;
+                    pending.push(j);
+                }
+            }
+        }
+
+        let mut warnings = vec![];
+        let mut reached = reached.into_iter();
+        hir.items.retain(|el| {
+            let is_reached = reached.next().unwrap();
+            if let (false, ModElement::Fn(Fn{name: Some(name), ..})) = (is_reached, el) {
+                warnings.push(warning!(format!("the function `{}` is never used", name.value), name.clone()));
+            }
+            is_reached
+        });
+        warnings
+    }
+}
+
+struct NamesVisitor {
+    names: HashSet<String>
+}
+
+impl Visit for NamesVisitor {
+    fn expr(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Ref(t) => {
+                self.names.insert(t.value.to_string());
+            },
+            Expr::GenericRef(generic_ref) => {
+                self.names.insert(generic_ref.id.value.to_string());
+            },
+            _ => ()
+        }
+    }
+}
+
+//================
+//   Visit
+//================
+// the passes see the expressions and the blocks after their children, so a parent sees folded children
+trait Visit {
+    fn expr(&mut self, expr: &mut Expr) {}
+    fn block(&mut self, block: &mut Vec<BlockElement>) {}
+    // around the expression given to a declaration or a field default, with its type if it is written
+    fn enter_type(&mut self, _type: Option<&Type>) {}
+    fn leave_type(&mut self) {}
+}
+
+//================
+//   walk_items()
+//================
+fn walk_items(
+    items: &mut Vec<ModElement>,
+    v: &mut dyn Visit
+) {
+    for el in items.iter_mut() {
+        walk_item(el, v);
+    }
+}
+
+//================
+//   walk_item()
+//================
+fn walk_item(
+    el: &mut ModElement,
+    v: &mut dyn Visit
+) {
+    match el {
+        ModElement::Decl(decl) => walk_decl(decl, v),
+        ModElement::MainFn(_fn)
+        | ModElement::Fn(_fn) => walk_fn(_fn, v),
+        ModElement::Struct(_struct) => {
+            for field in _struct.fields.iter_mut().flatten() {
+                if let Some(default) = &mut field.default {
+                    v.enter_type(Some(&field._type));
+                    walk_expr(default, v);
+                    v.leave_type();
+                }
+            }
+        },
+        ModElement::StructImpl(StructImpl{fns, ..})
+        | ModElement::EnumImpl(EnumImpl{fns, ..}) => {
+            for _fn in fns.iter_mut() {
+                walk_fn(_fn, v);
+            }
+        },
+        ModElement::Trait(_)
+        | ModElement::Enum(_) => ()
+    }
+}
+
+//================
+//   walk_fn()
+//================
+fn walk_fn(
+    _fn: &mut Fn,
+    v: &mut dyn Visit
+) {
+    for decl in _fn.where_decls.iter_mut() {
+        walk_decl(decl, v);
+    }
+    walk_block(&mut _fn.block, v);
+}
+
+//================
+//   walk_decl()
+//================
+fn walk_decl(
+    decl: &mut Decl,
+    v: &mut dyn Visit
+) {
+    if let Some(expr) = &mut decl.expr {
+        v.enter_type(decl._type.as_ref());
+        walk_expr(expr, v);
+        v.leave_type();
+    }
+}
+
+//================
+//   walk_block()
+//================
+fn walk_block(
+    block: &mut Vec<BlockElement>,
+    v: &mut dyn Visit
+) {
+    for el in block.iter_mut() {
+        match el {
+            BlockElement::Decl(decl) => walk_decl(decl, v),
+            BlockElement::Expr(expr) => walk_expr(expr, v),
+            BlockElement::Ret(Ret{expr: Some(expr), ..}) => walk_expr(expr, v),
+            BlockElement::Ret(_)
+            | BlockElement::MainArgs => ()
+        }
+    }
+    v.block(block);
+}
+
+//================
+//   walk_expr()
+//================
+fn walk_expr(
+    expr: &mut Expr,
+    v: &mut dyn Visit
+) {
+    match expr {
+        Expr::BinOp(bin_op) => {
+            walk_expr(&mut bin_op.l_opr, v);
+            walk_expr(&mut bin_op.r_opr, v);
+        },
+        Expr::PreUniOp(uni_op)
+        | Expr::PostUniOp(uni_op) => walk_expr(&mut uni_op.opr, v),
+        Expr::Cast(cast) => walk_expr(&mut cast.expr, v),
+        Expr::Range(range) => {
+            if let Some(start) = &mut range.start {
+                walk_expr(start, v);
+            }
+            if let Some(end) = &mut range.end {
+                walk_expr(end, v);
+            }
+        },
+        Expr::LetIn(let_in) => {
+            walk_decl(&mut let_in.decl, v);
+            walk_expr(&mut let_in.expr, v);
+        },
+        Expr::Fn(_fn) => walk_fn(_fn, v),
+        Expr::Match(_match) => {
+            walk_expr(&mut _match.expr, v);
+            for arm in _match.arms.iter_mut() {
+                if let Some(guard) = &mut arm.guard {
+                    walk_expr(guard, v);
+                }
+                walk_block(&mut arm.block, v);
+            }
+        },
+        Expr::For(_for) => {
+            walk_expr(&mut _for.in_expr.expr, v);
+            walk_block(&mut _for.block, v);
+        },
+        Expr::While(_while) => {
+            walk_expr(&mut _while.expr, v);
+            walk_block(&mut _while.block, v);
+        },
+        Expr::Loop(_loop) => walk_block(&mut _loop.block, v),
+        Expr::Break(Break{expr: Some(expr), ..}) => walk_expr(expr, v),
+        Expr::If(_if) => {
+            for branch in _if.branches.iter_mut() {
+                if let Some(expr) = &mut branch.expr {
+                    walk_expr(expr, v);
+                }
+                walk_block(&mut branch.block, v);
+            }
+        },
+        Expr::Block(block) => walk_block(block, v),
+        Expr::StructLiteral(literal) => {
+            for (_, value) in literal.items.iter_mut() {
+                if let Some(value) = value {
+                    walk_expr(value, v);
+                }
+            }
+            if let Some(base) = &mut literal.base {
+                walk_expr(base, v);
+            }
+        },
+        Expr::Map(map) => {
+            for (key, value) in map.items.iter_mut() {
+                walk_expr(key, v);
+                walk_expr(value, v);
+            }
+        },
+        Expr::List(List{items})
+        | Expr::Tuple(Tuple{items}) => {
+            for item in items.iter_mut() {
+                walk_expr(item, v);
+            }
+        },
+        Expr::Ok(expr)
+        | Expr::Err(expr)
+        | Expr::Some(expr)
+        | Expr::Borrow(expr)
+        | Expr::Clone(expr)
+        | Expr::Owned(expr) => walk_expr(expr, v),
+        _ => ()
+    }
+    v.expr(expr);
+}
@@ -1,11 +1,18 @@
 use std::collections::HashMap;
 
 
+use crate::error;
+
 use crate::lang::{
+    Lang,
     symtab::*,
+    token::Token,
+    syntax_tree::ast::*,
     error::Error
 };
 
+use crate::transl::lexicon::Lexicon;
+
 
 //================
 // ResTab
@@ -128,4 +135,475 @@ impl Resolver {
         }
 
     }
-}
\ No newline at end of file
+}
+
+//================
+// LoopInfo
+//================
+// the loops enclosing a `break` or a `continue`, innermost last
+struct LoopInfo {
+    label: Option<String>,
+    is_loop: bool       // `loop`, as opposed to `for` and `while`
+}
+
+impl Resolver {
+    //---------------------
+    //  check_loops()
+    //---------------------    
+    // `break` and `continue` outside of a loop, or naming a label that does not enclose them, 
+    //  this check does not need the symtab so it runs on its own until resolve() is turned on
+    pub fn check_loops(
+        &mut self,
+        ast: &Vec<ModElement>
+    ) -> Vec<Error> {
+        let mut errors = vec![];
+        for el in ast {
+            match el {
+                ModElement::Decl(decl) => self.loops_decl(decl, &mut vec![], &mut errors),
+                ModElement::MainFn(_fn)
+                | ModElement::Fn(_fn) => self.loops_fn(_fn, &mut errors),
+                ModElement::StructImpl(struct_impl) => {
+                    for _fn in &struct_impl.fns {
+                        self.loops_fn(_fn, &mut errors);
+                    }
+                },
+                _ => ()
+            }
+        }
+        errors
+    }
+
+    //---------------------
+    //  loops_block()
+    //---------------------    
+    fn loops_block(
+        &mut self,
+        block: &Vec<BlockElement>,
+        loops: &mut Vec<LoopInfo>,
+        errors: &mut Vec<Error>
+    ) {
+        for el in block {
+            match el {
+                BlockElement::Decl(decl) => self.loops_decl(decl, loops, errors),
+                BlockElement::Expr(expr) => self.loops_expr(expr, loops, errors),
+                BlockElement::Ret(Ret{expr: Some(expr), ..}) => self.loops_expr(expr, loops, errors),
+                BlockElement::Ret(_)
+                | BlockElement::MainArgs => ()
+            }
+        }
+    }
+
+    //---------------------
+    //  loops_decl()
+    //---------------------    
+    fn loops_decl(
+        &mut self,
+        decl: &Decl,
+        loops: &mut Vec<LoopInfo>,
+        errors: &mut Vec<Error>
+    ) {
+        if let Some(expr) = &decl.expr {
+            self.loops_expr(expr, loops, errors);
+        }
+    }
+
+    //---------------------
+    //  loops_body()
+    //---------------------    
+    fn loops_body(
+        &mut self,
+        label: &Option<Token>,
+        is_loop: bool,
+        block: &Vec<BlockElement>,
+        loops: &mut Vec<LoopInfo>,
+        errors: &mut Vec<Error>
+    ) {
+        let label = match label {
+            Some(label) => Some(label.value.to_string()),
+            None => None
+        };
+        loops.push(LoopInfo { label, is_loop });
+        self.loops_block(block, loops, errors);
+        loops.pop();
+    }
+
+    //---------------------
+    //  loops_fn()
+    //---------------------    
+    // a function starts with no enclosing loops, its `where` bindings too
+    fn loops_fn(
+        &mut self,
+        _fn: &Fn,
+        errors: &mut Vec<Error>
+    ) {
+        self.loops_block(&_fn.block, &mut vec![], errors);
+        for decl in &_fn.where_decls {
+            self.loops_decl(decl, &mut vec![], errors);
+        }
+    }
+
+    //---------------------
+    //  loops_expr()
+    //---------------------    
+    fn loops_expr(
+        &mut self,
+        expr: &Expr,
+        loops: &mut Vec<LoopInfo>,
+        errors: &mut Vec<Error>
+    ) {
+        match expr {
+            // a lambda can not leave the loops around it
+            Expr::Fn(_fn) => self.loops_fn(_fn, errors),
+            Expr::LetIn(let_in) => {
+                self.loops_decl(&let_in.decl, loops, errors);
+                self.loops_expr(&let_in.expr, loops, errors);
+            },
+            Expr::For(_for) => {
+                self.loops_expr(&_for.in_expr.expr, loops, errors);
+                self.loops_body(&_for.label, false, &_for.block, loops, errors);
+            },
+            Expr::While(_while) => {
+                self.loops_expr(&_while.expr, loops, errors);
+                self.loops_body(&_while.label, false, &_while.block, loops, errors);
+            },
+            Expr::Loop(_loop) => self.loops_body(&_loop.label, true, &_loop.block, loops, errors),
+            Expr::Break(_break) => {
+                if let Some(expr) = &_break.expr {
+                    self.loops_expr(expr, loops, errors);
+                }
+                match target_loop(&_break.token, &_break.label, loops) {
+                    Err(err) => errors.push(err),
+                    Ok(target) if _break.expr.is_some() && !target.is_loop => {
+                        errors.push(
+                            error!(
+                                "only `loop` can be left with a value, `for` and `while` can not".to_string(),
+                                &_break.token
+                            )
+                        );
+                    },
+                    Ok(_) => ()
+                }
+            },
+            Expr::Continue(_continue) => {
+                if let Err(err) = target_loop(&_continue.token, &_continue.label, loops) {
+                    errors.push(err);
+                }
+            },
+            Expr::Match(_match) => {
+                self.loops_expr(&_match.expr, loops, errors);
+                for arm in &_match.arms {
+                    if let Some(guard) = &arm.guard {
+                        self.loops_expr(guard, loops, errors);
+                    }
+                    self.loops_block(&arm.block, loops, errors);
+                }
+            },
+            Expr::If(_if) => {
+                for branch in &_if.branches {
+                    if let Some(expr) = &branch.expr {
+                        self.loops_expr(expr, loops, errors);
+                    }
+                    self.loops_block(&branch.block, loops, errors);
+                }
+            },
+            Expr::BinOp(bin_op) => {
+                self.loops_expr(&bin_op.l_opr, loops, errors);
+                self.loops_expr(&bin_op.r_opr, loops, errors);
+            },
+            Expr::PreUniOp(uni_op)
+            | Expr::PostUniOp(uni_op) => self.loops_expr(&uni_op.opr, loops, errors),
+            Expr::List(List{items})
+            | Expr::Tuple(Tuple{items}) => {
+                for item in items {
+                    self.loops_expr(item, loops, errors);
+                }
+            },
+            Expr::Ok(expr)
+            | Expr::Err(expr)
+            | Expr::Some(expr) => self.loops_expr(expr, loops, errors),
+            _ => ()
+        }
+    }
+}
+
+//================
+// target_loop()
+//================
+// the loop a `break` or a `continue` leaves
+fn target_loop<'l>(
+    token: &Token,
+    label: &Option<Token>,
+    loops: &'l Vec<LoopInfo>
+) -> Result<&'l LoopInfo, Error> {
+    match label {
+        None => match loops.last() {
+            Some(target) => Ok(target),
+            None => Err(error!(format!("`{}` outside of a loop", token.value), token))
+        },
+        Some(label) => {
+            let name = label.value.to_string();
+            match loops.iter().rev().find(|l| l.label.as_ref() == Some(&name)) {
+                Some(target) => Ok(target),
+                None => Err(error!(format!("undeclared label `${}`", name), label))
+            }
+        }
+    }
+}
+
+//================
+// Exports
+//================
+// the visibility of the top level items of a module, the fields of its structs are kept as `Point.x`
+pub type Exports = HashMap<String, Visibility>;
+
+//================
+// exports()
+//================
+pub fn exports(ast: &Vec<ModElement>) -> Exports {
+    let mut exports = HashMap::new();
+    for el in ast {
+        match el {
+            ModElement::Fn(_fn) => {
+                if let Some(name) = &_fn.name {
+                    exports.insert(name.value.to_string(), _fn.vis);
+                }
+            },
+            ModElement::Struct(_struct) => {
+                let name = _struct.name.value.to_string();
+                for field in _struct.fields.iter().flatten() {
+                    exports.insert(format!("{}.{}", name, field.name.value), field.vis);
+                }
+                exports.insert(name, _struct.vis);
+            },
+            ModElement::Enum(_enum) => {
+                exports.insert(_enum.name.value.to_string(), _enum.vis);
+            },
+            _ => ()
+        }
+    }
+    exports
+}
+
+//================
+// imports()
+//================
+// `m := import("m.seen")`, the name each module is imported as and the path it is imported from
+pub fn imports(
+    ast: &Vec<ModElement>,
+    lang: &Lang,
+    lexicon: &Lexicon
+) -> Vec<(String, String)> {
+    ast.iter()
+        .filter_map(|el| match el {
+            ModElement::Decl(Decl{ pattern: Pattern::Id(IdPattern{id}), expr: Some(Expr::BinOp(bin_op)), .. }) => {
+                match (&*bin_op.l_opr, &*bin_op.r_opr) {
+                    (Expr::Ref(callee), Expr::Tuple(args)) if lexicon.is("fn.import", lang, &callee.value.to_string()) => {
+                        match args.items.first() {
+                            Some(Expr::Str(path)) => Some((id.value.to_string(), path.value.to_string())),
+                            _ => None
+                        }
+                    },
+                    _ => None
+                }
+            },
+            _ => None
+        })
+        .collect()
+}
+
+impl Resolver {
+    //---------------------
+    //  check_visibility()
+    //---------------------    
+    // private items of an imported module used through the name it is imported as, `imports` maps
+    //  that name to what the module exports, modules that are not seen modules are not checked
+    pub fn check_visibility(
+        &mut self,
+        ast: &Vec<ModElement>,
+        imports: &HashMap<String, &Exports>
+    ) -> Vec<Error> {
+        let mut errors = vec![];
+        for el in ast {
+            match el {
+                ModElement::Decl(decl) => self.vis_decl(decl, imports, &mut errors),
+                ModElement::MainFn(_fn)
+                | ModElement::Fn(_fn) => self.vis_fn(_fn, imports, &mut errors),
+                ModElement::StructImpl(struct_impl) => {
+                    for _fn in &struct_impl.fns {
+                        self.vis_fn(_fn, imports, &mut errors);
+                    }
+                },
+                ModElement::Struct(_struct) => {
+                    for field in _struct.fields.iter().flatten() {
+                        if let Some(default) = &field.default {
+                            self.vis_expr(default, imports, &mut errors);
+                        }
+                    }
+                },
+                _ => ()
+            }
+        }
+        errors
+    }
+
+    //---------------------
+    //  vis_block()
+    //---------------------    
+    fn vis_block(
+        &mut self,
+        block: &Vec<BlockElement>,
+        imports: &HashMap<String, &Exports>,
+        errors: &mut Vec<Error>
+    ) {
+        for el in block {
+            match el {
+                BlockElement::Decl(decl) => self.vis_decl(decl, imports, errors),
+                BlockElement::Expr(expr) => self.vis_expr(expr, imports, errors),
+                BlockElement::Ret(Ret{expr: Some(expr), ..}) => self.vis_expr(expr, imports, errors),
+                BlockElement::Ret(_)
+                | BlockElement::MainArgs => ()
+            }
+        }
+    }
+
+    //---------------------
+    //  vis_decl()
+    //---------------------    
+    fn vis_decl(
+        &mut self,
+        decl: &Decl,
+        imports: &HashMap<String, &Exports>,
+        errors: &mut Vec<Error>
+    ) {
+        if let Some(expr) = &decl.expr {
+            self.vis_expr(expr, imports, errors);
+        }
+    }
+
+    //---------------------
+    //  vis_fn()
+    //---------------------    
+    fn vis_fn(
+        &mut self,
+        _fn: &Fn,
+        imports: &HashMap<String, &Exports>,
+        errors: &mut Vec<Error>
+    ) {
+        self.vis_block(&_fn.block, imports, errors);
+        for decl in &_fn.where_decls {
+            self.vis_decl(decl, imports, errors);
+        }
+    }
+
+    //---------------------
+    //  vis_expr()
+    //---------------------    
+    fn vis_expr(
+        &mut self,
+        expr: &Expr,
+        imports: &HashMap<String, &Exports>,
+        errors: &mut Vec<Error>
+    ) {
+        match expr {
+            Expr::Fn(_fn) => self.vis_fn(_fn, imports, errors),
+            Expr::LetIn(let_in) => {
+                self.vis_decl(&let_in.decl, imports, errors);
+                self.vis_expr(&let_in.expr, imports, errors);
+            },
+            Expr::For(_for) => {
+                self.vis_expr(&_for.in_expr.expr, imports, errors);
+                self.vis_block(&_for.block, imports, errors);
+            },
+            Expr::While(_while) => {
+                self.vis_expr(&_while.expr, imports, errors);
+                self.vis_block(&_while.block, imports, errors);
+            },
+            Expr::Loop(_loop) => self.vis_block(&_loop.block, imports, errors),
+            Expr::Break(_break) => {
+                if let Some(expr) = &_break.expr {
+                    self.vis_expr(expr, imports, errors);
+                }
+            },
+            Expr::Match(_match) => {
+                self.vis_expr(&_match.expr, imports, errors);
+                for arm in &_match.arms {
+                    if let Some(guard) = &arm.guard {
+                        self.vis_expr(guard, imports, errors);
+                    }
+                    self.vis_block(&arm.block, imports, errors);
+                }
+            },
+            Expr::If(_if) => {
+                for branch in &_if.branches {
+                    if let Some(expr) = &branch.expr {
+                        self.vis_expr(expr, imports, errors);
+                    }
+                    self.vis_block(&branch.block, imports, errors);
+                }
+            },
+            Expr::BinOp(bin_op) => {
+                if let (true, Expr::Ref(_mod)) = (bin_op.is_access(), &*bin_op.l_opr) {
+                    if let Some(exports) = imports.get(&_mod.value.to_string()) {
+                        self.vis_access(_mod, &bin_op.r_opr, exports, errors);
+                    }
+                }
+                self.vis_expr(&bin_op.l_opr, imports, errors);
+                self.vis_expr(&bin_op.r_opr, imports, errors);
+            },
+            Expr::StructLiteral(literal) => {
+                for (_, value) in &literal.items {
+                    if let Some(value) = value {
+                        self.vis_expr(value, imports, errors);
+                    }
+                }
+                if let Some(base) = &literal.base {
+                    self.vis_expr(base, imports, errors);
+                }
+            },
+            Expr::PreUniOp(uni_op)
+            | Expr::PostUniOp(uni_op) => self.vis_expr(&uni_op.opr, imports, errors),
+            Expr::List(List{items})
+            | Expr::Tuple(Tuple{items}) => {
+                for item in items {
+                    self.vis_expr(item, imports, errors);
+                }
+            },
+            Expr::Ok(expr)
+            | Expr::Err(expr)
+            | Expr::Some(expr) => self.vis_expr(expr, imports, errors),
+            _ => ()
+        }
+    }
+
+    //---------------------
+    //  vis_access()
+    //---------------------    
+    // `m.f(x)` or `m.Point { x: 1 }`, a struct with private fields can only be built in its own module
+    fn vis_access(
+        &mut self,
+        _mod: &Token,
+        expr: &Expr,
+        exports: &Exports,
+        errors: &mut Vec<Error>
+    ) {
+        let (item, is_literal) = match expr {
+            Expr::Ref(t) => (t, false),
+            Expr::BinOp(bin_op) => match (&*bin_op.l_opr, &*bin_op.r_opr) {
+                (Expr::Ref(t), Expr::StructLiteral(_)) => (t, true),
+                (Expr::Ref(t), _) => (t, false),
+                _ => return
+            },
+            _ => return
+        };
+
+        let name = item.value.to_string();
+        if exports.get(&name) == Some(&Visibility::Private) {
+            errors.push(error!(format!("`{}` is private to module `{}`", name, _mod.value), item));
+        } else if is_literal {
+            let prefix = format!("{}.", name);
+            if exports.iter().any(|(k, vis)| k.starts_with(&prefix) && *vis == Visibility::Private) {
+                errors.push(error!(format!("`{}` has private fields, it can only be built in module `{}`", name, _mod.value), item));
+            }
+        }
+    }
+}
@@ -1,5 +1,4 @@
 use std::{
-    collections::HashMap,
     fmt::{ self, Debug }
 };
 
@@ -8,10 +7,10 @@ use crate::lang::token::{Token, TokenValue};
 //================
 //   Aliases
 //================
-// FIXME: replace HashMaps with Vec<(T,E)> with lookup ( or use an ordered_hashmap implementation ), to preserve the order
-pub type StructFieldsTypes = HashMap<Token, Option<Type>>;
-pub type StructFields = HashMap<Token, Type>;
-pub type StructPatternFields = HashMap<Token, Option<Pattern>>;
+// fields are kept in declaration order, the generated rust follows the source
+pub type StructFieldsTypes = Vec<(Token, Option<Type>)>;
+pub type StructFields = Vec<StructField>;
+pub type StructPatternFields = Vec<(Token, Option<Pattern>)>;
 pub type StructLiteralFields = Vec<(Token, Option<Expr>)>;
 
 //================
@@ -36,6 +35,15 @@ pub struct List {
     pub items: Vec<Expr>,
 }
 
+//================
+//   Map
+//================
+// `[k1: v1, k2: v2]`, `[:]` is the empty map
+#[derive(Clone, Debug)]
+pub struct Map {
+    pub items: Vec<(Expr, Expr)>,
+}
+
 //================
 //   Tuple
 //================
@@ -47,12 +55,24 @@ pub struct Tuple {
 //================
 //   StructLiteral
 //================
+// an item without a value is the shorthand `{ x }` for `{ x: x }`
 #[derive(Clone, Debug)]
 pub struct StructLiteral {
-    pub items: StructLiteralFields
+    pub items: StructLiteralFields,
+    pub base: Option<Box<Expr>>     // `..base` fills the fields that are left out
 }
 
 
+//================
+//   LetIn
+//================
+// `let x = e in body`, `x` is only visible in `body`
+#[derive(Clone, Debug)]
+pub struct LetIn {
+    pub decl: Box<Decl>,
+    pub expr: Box<Expr>
+}
+
 //================
 //   Decl
 //================
@@ -60,7 +80,8 @@ pub struct StructLiteral {
 pub struct Decl {
     pub pattern: Pattern,
     pub _type : Option<Type>,
-    pub expr: Option<Expr> 
+    pub expr: Option<Expr>,
+    pub is_mut: bool        // written with `mut`, or found to be mutated by the mutability pass
 }
 
 //================
@@ -75,19 +96,26 @@ pub enum Expr {
     Char(Token),
     Str(Token),
     Ref(Token),
+    GenericRef(GenericRef),
     List(List),
+    Map(Map),
     Tuple(Tuple),
     StructLiteral(StructLiteral),  
     BinOp(BinOp),
     PreUniOp(UniOp),
     PostUniOp(UniOp),
+    Cast(Cast),
+    Range(Range),
+    LetIn(LetIn),
     Fn(Fn),
     Match(Match),
     For(For),
     While(While),
+    Loop(Loop),
+    Break(Break),
+    Continue(Continue),
     If(If),
     Code(Code),
-    Ret(Box<Expr>), // FIXME: THIS IS WRONG, return is a statement not an expressionm move to BlockElement
 
     // FIXME: HARDCODING VARIANTS FOR THE DEMO
     Ok(Box<Expr>),
@@ -95,6 +123,14 @@ pub enum Expr {
     Some(Box<Expr>),
     None,
 
+    // written by the ownership pass, never by the parser
+    StrSlice(Token),        // a string literal that is only read, `"x"` instead of `"x".to_string()`
+    Borrow(Box<Expr>),      // `&x`
+    Clone(Box<Expr>),       // `x.clone()`, the value is used again after it is given away
+    Owned(Box<Expr>),       // `x.to_string()`, a `&str` param that is given away
+
+    // written by the middle-end passes
+    Block(Vec<BlockElement>),   // the branch left of an `if` or a `match` on a constant
 }
 
 //================
@@ -104,7 +140,8 @@ pub enum Expr {
 pub enum BlockElement {
     MainArgs,   // FIXME: this is a workaround, main args are nothing but a Decl
     Decl(Decl),
-    Expr(Expr)
+    Expr(Expr),
+    Ret(Ret)
 }
 
 //================
@@ -136,17 +173,42 @@ impl BinOp {
 }
 
 
+//================
+//   Range
+//================
+// `a..b`, `a..=b`, `..b` and `a..`, also used as a pattern where both ends are literals
+#[derive(Clone, Debug)]
+pub struct Range {
+    pub start: Option<Box<Expr>>,
+    pub end: Option<Box<Expr>>,
+    pub inclusive: bool
+}
+
+//================
+//   Cast
+//================
+// `x as i64`
+#[derive(Clone, Debug)]
+pub struct Cast {
+    pub expr: Box<Expr>,
+    pub _type: Type
+}
+
 //================
 //   Fn
 //================
 #[derive(Clone, Debug)]
 pub struct Fn {
     pub attrs: Option<Vec<Attr>>,
+    pub vis: Visibility,
     pub is_method: bool,
+    pub receiver: Option<Receiver>,     // methods only, given by an attribute or inferred by the mutability pass
     pub name: Option<Token>,
+    pub generics: Vec<GenericParam>,
     pub params: Vec<Param>,
     pub ret_type: Option<Type>,
-    pub block: Vec<BlockElement>
+    pub block: Vec<BlockElement>,
+    pub where_decls: Vec<Decl>      // `where` bindings written after the body, visible to the whole body
 }
 
 //================
@@ -156,7 +218,56 @@ pub struct Fn {
 pub struct Param {
     // pub name: Token,
     pub pat: Pattern,
-    pub _type: Option<Type>
+    pub _type: Option<Type>,
+    pub is_mut: bool
+}
+
+//================
+//   Receiver
+//================
+// how a method takes `self`: `&self`, `&mut self`, `self` or `mut self`
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Receiver {
+    Ref,
+    MutRef,
+    Owned,
+    MutOwned
+}
+
+impl Receiver {
+    //---------------------
+    //  is_mut()
+    //---------------------        
+    pub fn is_mut(&self) -> bool {
+        matches!(self, Self::MutRef | Self::MutOwned)
+    }
+
+    //---------------------
+    //  is_owned()
+    //---------------------        
+    pub fn is_owned(&self) -> bool {
+        matches!(self, Self::Owned | Self::MutOwned)
+    }
+}
+
+//================
+//   GenericParam
+//================
+// a type parameter of a function or a struct, e.g: `T: Display + Clone`
+#[derive(Clone, Debug)]
+pub struct GenericParam {
+    pub name: Token,
+    pub bounds: Vec<Type>
+}
+
+//================
+//   GenericRef
+//================
+// a reference with explicit type arguments, e.g: `parse::<int>`
+#[derive(Clone, Debug)]
+pub struct GenericRef {
+    pub id: Token,
+    pub args: Vec<Type>
 }
 
 //================
@@ -169,8 +280,11 @@ pub enum Type{
     UnitType,
     PrimitiveType(PrimitiveType),
     ListType(ListType),
+    MapType(MapType),
     TupleType(TupleType),
-    StructType(StructType)
+    StructType(StructType),
+    NamedType(NamedType),
+    FnType(FnType)
 }
 
 //================
@@ -182,13 +296,33 @@ impl fmt::Display for Type {
     //---------------------      
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Type::OptionType(t) => todo!(),
-            Type::ResultType(t) => todo!(),
+            Type::OptionType(t) => write!(f, "{}?", t.some_type),
+            Type::ResultType(t) => write!(f, "Res<{}, {}>", t.ok_type, t.err_type),
             Type::UnitType => write!(f, "()"),
             Type::PrimitiveType(t) => write!(f, "{}", t.id),
-            Type::ListType(t) => todo!(),
-            Type::TupleType(t) => todo!(),
-            Type::StructType(t) => todo!(),
+            Type::ListType(t) => write!(f, "[{}]", t.els_type),
+            Type::MapType(t) => write!(f, "[{}: {}]", t.key_type, t.value_type),
+            Type::TupleType(t) => {
+                let types: Vec<String> = t.types.iter().map(|_type| _type.to_string()).collect();
+                write!(f, "({})", types.join(", "))
+            },
+            Type::StructType(t) => write!(f, "{}", t.name),
+            Type::FnType(t) => {
+                let params: Vec<String> = t.params_types.iter().map(|param| param.to_string()).collect();
+                let _ = write!(f, "fn({})", params.join(", "));
+                if let Some(ret_type) = &t.ret_type {
+                    let _ = write!(f, ": {}", ret_type);
+                }
+                Ok(())
+            },
+            Type::NamedType(t) => {
+                let _ = write!(f, "{}", t.name);
+                if !t.args.is_empty() {
+                    let args: Vec<String> = t.args.iter().map(|arg| arg.to_string()).collect();
+                    let _ = write!(f, "<{}>", args.join(", "));
+                }
+                Ok(())
+            },
         }
         
     }
@@ -230,6 +364,17 @@ pub struct ListType{
     pub els_type: Box<Type>
 }
 
+//================
+//   MapType
+//================
+// `[K: V]`, or `map<K, V>` / `sorted_map<K, V>` when the ordering of the keys matters
+#[derive(Clone, Debug)]
+pub struct MapType{
+    pub key_type: Box<Type>,
+    pub value_type: Box<Type>,
+    pub sorted: bool
+}
+
 //================
 //   TupleType
 //================
@@ -247,6 +392,38 @@ pub struct StructType {
     fields_types: StructFieldsTypes
 }
 
+//================
+//   NamedType
+//================
+// a user defined type, a type parameter or a trait ( in bounds ), with optional type arguments: `Stack<T>`
+#[derive(Clone, Debug)]
+pub struct NamedType {
+    pub name: Token,
+    pub args: Vec<Type>
+}
+
+//================
+//   FnType
+//================
+// the type of a function or a lambda: `fn(int, int): int`
+#[derive(Clone, Debug)]
+pub struct FnType {
+    pub params_types: Vec<Type>,
+    pub ret_type: Option<Box<Type>>
+}
+
+//================
+//   Visibility
+//================
+// items are private to their module unless written with `pub` ( `عام` ) or `internal` ( `داخلي` ),
+//  internal items can be used by every module of the project but not by other projects
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Visibility {
+    Private,
+    Internal,
+    Public
+}
+
 //================
 //   Struct
 //================
@@ -254,10 +431,33 @@ pub struct StructType {
 pub struct Struct{
     pub name: Token,
     pub attrs: Vec<Attr>,
+    pub vis: Visibility,
+    pub generics: Vec<GenericParam>,
     pub fields: Option<StructFields>,
     pub impls: Vec<Impl>
 }
 
+//================
+//   StructField
+//================
+// `name: type = default`, a field with a default or an optional type ( `int?` ) can be left out of a literal
+#[derive(Clone, Debug)]
+pub struct StructField {
+    pub vis: Visibility,
+    pub name: Token,
+    pub _type: Type,
+    pub default: Option<Expr>
+}
+
+impl StructField {
+    //---------------------
+    //  is_optional()
+    //---------------------
+    pub fn is_optional(&self) -> bool {
+        matches!(self._type, Type::OptionType(_))
+    }
+}
+
 //================
 //   StructImpl
 //================
@@ -304,6 +504,7 @@ pub struct TraitFn{
 #[derive(Debug)]
 pub struct Enum{
     pub name: Token,
+    pub vis: Visibility,
     pub variants: Vec<Variant>,
     pub impls: Vec<Impl>
 }
@@ -337,16 +538,69 @@ pub enum Pattern {
     Tuple(TuplePattern),
     Struct(StructPattern),
     Enum(EnumPattern),
-    Wildcard
+    Range(Range),
+    Or(Vec<Pattern>),
+    Binding(BindingPattern),
+    Rest,                   // `..` in a list pattern
+    Wildcard,
+
+    // FIXME: HARDCODING VARIANTS FOR THE DEMO
+    Ok(Box<Pattern>),
+    Err(Box<Pattern>),
+    Some(Box<Pattern>),
+    None
 }
 
 impl Pattern {
     pub fn is_refutable(&self) -> bool {
         match self {
-            Self::PrimitiveLiteral(_) => true,
+            Self::PrimitiveLiteral(_) 
+            | Self::Range(_) => true,
+            Self::Or(patterns) => patterns.iter().all(|p| p.is_refutable()),
+            Self::Binding(p) => p.pattern.is_refutable(),
+            Self::Ok(_)
+            | Self::Err(_)
+            | Self::Some(_)
+            | Self::None => true,
             _ => false
         }
     }
+
+    //---------------------
+    //  bindings()
+    //---------------------        
+    // the names the pattern declares, an or-pattern binds the same names in every alternative
+    pub fn bindings(&self) -> Vec<&Token> {
+        match self {
+            Self::Id(p) => vec![&p.id],
+            Self::Binding(p) => {
+                let mut ids = vec![&p.id];
+                ids.extend(p.pattern.bindings());
+                ids
+            },
+            Self::List(ListPattern{items})
+            | Self::Tuple(TuplePattern{items}) => items.iter().flat_map(|p| p.bindings()).collect(),
+            Self::Struct(p) => p.items
+                .iter()
+                .flat_map(|(id, p)| match p {
+                    Some(p) => p.bindings(),
+                    None => vec![id]
+                })
+                .collect(),
+            Self::Enum(p) => p.variant.pattern
+                .as_ref()
+                .map(|p| p.bindings())
+                .unwrap_or(vec![]),
+            Self::Or(patterns) => patterns
+                .first()
+                .map(|p| p.bindings())
+                .unwrap_or(vec![]),
+            Self::Ok(p)
+            | Self::Err(p)
+            | Self::Some(p) => p.bindings(),
+            _ => vec![]
+        }
+    }
 }
 
 //================
@@ -358,6 +612,16 @@ pub struct IdPattern {
 }
 
 
+//================
+//   BindingPattern
+//================
+// `n @ 1..=9` binds the matched value to `n`
+#[derive(Clone, Debug)]
+pub struct BindingPattern {
+    pub id: Token,
+    pub pattern: Box<Pattern>
+}
+
 //================
 //   ListPattern
 //================
@@ -410,12 +674,54 @@ pub struct Attr {
     pub expr: AttrExpr
 }
 
+impl Attr {
+    //---------------------
+    //  name()
+    //---------------------
+    pub fn name(&self) -> &Token {
+        match &self.expr {
+            AttrExpr::Ref(name)
+            | AttrExpr::Call(AttrCall{name, ..}) => name
+        }
+    }
+
+    //---------------------
+    //  args()
+    //---------------------
+    pub fn args(&self) -> &[AttrArg] {
+        match &self.expr {
+            AttrExpr::Ref(_) => &[],
+            AttrExpr::Call(call) => &call.args
+        }
+    }
+}
+
 //================
 //   AttrExpr
 //================
 #[derive(Clone, Debug)]
 pub enum AttrExpr {
-    Ref(Token)
+    Ref(Token),             // `@web_server`
+    Call(AttrCall)          // `@route("/x", method: "GET")`
+}
+
+//================
+//   AttrCall
+//================
+#[derive(Clone, Debug)]
+pub struct AttrCall {
+    pub name: Token,
+    pub args: Vec<AttrArg>
+}
+
+//================
+//   AttrArg
+//================
+// `"/x"`, or `method: "GET"` when it is named
+#[derive(Clone, Debug)]
+pub struct AttrArg {
+    pub name: Option<Token>,
+    pub expr: Expr
 }
 
 //================
@@ -423,6 +729,7 @@ pub enum AttrExpr {
 //================
 #[derive(Clone, Debug)]
 pub struct Match {
+    pub token: Token,
     pub expr: Box<Expr>,
     pub arms: Vec<Arm>
 }
@@ -433,7 +740,9 @@ pub struct Match {
 //================
 #[derive(Clone, Debug)]
 pub struct Arm {
+    pub token: Token,       // the first token of the pattern
     pub pattern: Pattern,
+    pub guard: Option<Expr>,
     pub block: Vec<BlockElement>
 }
 
@@ -444,7 +753,8 @@ pub struct Arm {
 #[derive(Clone, Debug)]
 pub struct For {
     pub in_expr: InExpr,
-    pub block: Vec<BlockElement>
+    pub block: Vec<BlockElement>,
+    pub label: Option<Token>
 }
 
 //================
@@ -462,7 +772,47 @@ pub struct InExpr {
 #[derive(Clone, Debug)]
 pub struct While {
     pub expr: Box<Expr>,
-    pub block: Box<Vec<BlockElement>>
+    pub block: Box<Vec<BlockElement>>,
+    pub label: Option<Token>
+}
+
+//================
+//   Loop
+//================
+#[derive(Clone, Debug)]
+pub struct Loop {
+    pub block: Vec<BlockElement>,
+    pub label: Option<Token>
+}
+
+//================
+//   Ret
+//================
+// `ret x`, or the value a block ends with when there is no `token`
+#[derive(Clone, Debug)]
+pub struct Ret {
+    pub token: Option<Token>,
+    pub expr: Option<Box<Expr>>
+}
+
+//================
+//   Break
+//================
+// only a `loop` can be left with a value
+#[derive(Clone, Debug)]
+pub struct Break {
+    pub token: Token,
+    pub label: Option<Token>,
+    pub expr: Option<Box<Expr>>
+}
+
+//================
+//   Continue
+//================
+#[derive(Clone, Debug)]
+pub struct Continue {
+    pub token: Token,
+    pub label: Option<Token>
 }
 
 //================
@@ -22,25 +22,38 @@ impl fmt::Display for Expr {
             | Self::Ref(t) => write!(f, "{:?}", t.value ),
             Self::Char(t) => write!(f, "{}", t.value ),
             Self::Str(t) => write!(f, "{}", t.value ),
+            Self::GenericRef(e) => write!(f, "{:?}", e),
             Self::List(e) =>  write!(f, "{:?}", e),
+            Self::Map(e) =>  write!(f, "{:?}", e),
             Self::Tuple(e) =>  write!(f, "{:?}", e),
             Self::StructLiteral(e) => write!(f, "{:?}", e),
             Self::BinOp(e) => write!(f, "{:?}", e),
             Self::PreUniOp(e) => write!(f, "{:?}", e),
             Self::PostUniOp(e) => write!(f, "{:?}", e),
+            Self::Cast(e) => write!(f, "{:?}", e),
+            Self::Range(e) => write!(f, "{:?}", e),
+            Self::LetIn(e) => write!(f, "{:?}", e),
             Self::Fn(e) => write!(f, "{:?}", e),
             Self::Match(e) => write!(f, "{:?}", e),
             Self::For(e) => write!(f, "{:?}", e),
             Self::While(e) => write!(f, "{:?}", e),
+            Self::Loop(e) => write!(f, "{:?}", e),
+            Self::Break(e) => write!(f, "{:?}", e),
+            Self::Continue(e) => write!(f, "{:?}", e),
             Self::If(e) => write!(f, "{:?}", e),
             Self::Code(e) => write!(f, "{:?}", e),
-            Self::Ret(e) => write!(f, "return {:?}", e),
 
             Self::Ok(e) => write!(f, "Ok({:?})", e),
             Self::Err(e) => write!(f, "Err({:?})", e),
             Self::Some(e) => write!(f, "Some({:?})", e),
             Self::None => write!(f, "None"),            
 
+            Self::StrSlice(t) => write!(f, "{}", t.value ),
+            Self::Borrow(e) => write!(f, "&{}", e),
+            Self::Clone(e) => write!(f, "{}.clone()", e),
+            Self::Owned(e) => write!(f, "{}.to_string()", e),
+            Self::Block(e) => write!(f, "{:?}", e),
+
 
         }
     }
@@ -1,58 +1,512 @@
 use super::{
-    resolver::ResTab, 
-    syntax_tree::ast::ModElement,
+    resolver::ResTab,
+    token::{
+        Token,
+        TokenValue
+    },
+    syntax_tree::ast::*,
     error::{
         Error,
-    },    
+    },
 };
 
-pub struct TypeChecker {
+use crate::{
+    error,
+    transl::lexicon::Lexicon,
+    util::ar::to_western_num
+};
+
+pub struct TypeChecker<'a> {
     ast: Option<Vec<ModElement>>,
     restab: Option<ResTab>,
-    errors: Option<Vec<Error>>,    
+    errors: Option<Vec<Error>>,
+    lexicon: &'a Lexicon,
+    ret_types: Vec<Option<Type>>,       // the return types of the functions being checked, innermost last
+    var_types: Vec<Vec<(String, Option<Type>)>>,    // the declared types of their params and locals
 }
 
-impl TypeChecker {
+impl<'a> TypeChecker<'a> {
     //---------------------
     //  new()
-    //---------------------    
+    //---------------------
     pub fn new() -> Self {
         Self {
             ast: None,
             restab: None,
-            errors: None
+            errors: None,
+            lexicon: Lexicon::embedded(),
+            ret_types: vec![],
+            var_types: vec![]
         }
     }
 
+    //---------------------
+    //  lexicon()
+    //---------------------
+    pub fn lexicon(&mut self, lexicon: &'a Lexicon) {
+        self.lexicon = lexicon;
+    }
+
     //---------------------
     //  init()
-    //---------------------    
+    //---------------------
     pub fn init(
         &mut self,
-        ast: Vec<ModElement>,    
+        ast: Vec<ModElement>,
         restab: ResTab
     ) {
         self.ast = Some(ast);
         self.restab = Some(restab);
         self.errors = Some(vec![]);
-        
-    }    
+
+    }
 
 
     //---------------------
     //  infer()
-    //---------------------    
+    //---------------------
     pub fn check(
-        &mut self, 
-        ast: Vec<ModElement>, 
+        &mut self,
+        ast: Vec<ModElement>,
         restab: ResTab
     ) -> (Vec<ModElement>, ResTab, Vec<Error>)  {
         self.init(ast, restab);
 
+        let ast = self.ast.take().unwrap();
+        let mut errors = self.check_literals(&ast);
+        self.errors.as_mut().unwrap().append(&mut errors);
+
         (
-            self.ast.take().unwrap(),
+            ast,
             self.restab.take().unwrap(),
             self.errors.take().unwrap()
-        )        
+        )
+    }
+
+    //---------------------
+    //  check_literals()
+    //---------------------
+    // integer literals that do not fit in the type they are given ( including struct field defaults ) and
+    //  `ret` values that do not match the function, this check does not need the resolver so it runs on its own until the rest of
+    //  the type checker is turned on
+    pub fn check_literals(
+        &mut self,
+        ast: &Vec<ModElement>,
+    ) -> Vec<Error> {
+        let mut errors = vec![];
+        for el in ast {
+            match el {
+                ModElement::Decl(decl) => self.decl(decl, &mut errors),
+                ModElement::MainFn(_fn)
+                | ModElement::Fn(_fn) => self._fn(_fn, &mut errors),
+                ModElement::StructImpl(struct_impl) => {
+                    for _fn in &struct_impl.fns {
+                        self._fn(_fn, &mut errors);
+                    }
+                },
+                ModElement::Struct(_struct) => {
+                    for field in _struct.fields.iter().flatten() {
+                        if let Some(default) = &field.default {
+                            self.typed_expr(default, &field._type, &mut errors);
+                        }
+                    }
+                },
+                _ => ()
+            }
+        }
+        errors
+    }
+}
+
+//================
+//   _fn()
+//================
+impl<'a> TypeChecker<'a> {
+    fn _fn(
+        &mut self,
+        _fn: &Fn,
+        errors: &mut Vec<Error>
+    ) {
+        for decl in &_fn.where_decls {
+            self.decl(decl, errors);
+        }
+        self.ret_types.push(_fn.ret_type.clone());
+        self.var_types.push(
+            _fn.params.iter()
+                .filter_map(|param| match &param.pat {
+                    Pattern::Id(IdPattern{id}) => Some((id.value.to_string(), param._type.clone())),
+                    _ => None
+                })
+                .collect()
+        );
+        self.block(&_fn.block, errors);
+        self.var_types.pop();
+        self.ret_types.pop();
+        if let (Some(ret_type), Some(BlockElement::Ret(Ret{token: None, expr: Some(expr)}))) = (&_fn.ret_type, _fn.block.last()) {
+            self.typed_expr(expr, ret_type, errors);
+        }
+    }
+}
+
+//================
+//   block()
+//================
+// the locals declared in a block go out of scope at its end
+impl<'a> TypeChecker<'a> {
+    fn block(
+        &mut self,
+        block: &Vec<BlockElement>,
+        errors: &mut Vec<Error>
+    ) {
+        let scope_len = self.var_types.last().map(|vars| vars.len());
+        for el in block {
+            match el {
+                BlockElement::Decl(decl) => self.decl(decl, errors),
+                BlockElement::Expr(expr) => self.expr(expr, errors),
+                BlockElement::Ret(ret) => self.ret(ret, errors),
+                BlockElement::MainArgs => ()
+            }
+        }
+        if let (Some(len), Some(vars)) = (scope_len, self.var_types.last_mut()) {
+            vars.truncate(len);
+        }
+    }
+}
+
+//================
+//   ret()
+//================
+// an explicit `ret` has to agree with the return type of its function, a function without
+//  a return type is left to the inference
+impl<'a> TypeChecker<'a> {
+    fn ret(
+        &mut self,
+        ret: &Ret,
+        errors: &mut Vec<Error>
+    ) {
+        if let Some(expr) = &ret.expr {
+            self.expr(expr, errors);
+        }
+        let token = match &ret.token {
+            Some(token) => token,
+            None => return
+        };
+        let ret_type = match self.ret_types.last() {
+            Some(Some(ret_type)) => ret_type.clone(),
+            _ => return
+        };
+        match (&ret.expr, &ret_type) {
+            (Some(_), Type::UnitType) => errors.push(
+                error!(
+                    "`ret` with a value in a function that returns `()`".to_string(),
+                    token
+                )
+            ),
+            (None, Type::UnitType) => (),
+            (None, _) => errors.push(
+                error!(
+                    format!("`ret` without a value in a function that returns `{}`", ret_type),
+                    token
+                )
+            ),
+            (Some(expr), _) => {
+                if let Some(found) = self.mismatch(expr, &ret_type) {
+                    errors.push(
+                        error!(
+                            format!("mismatched types, the function returns `{}` but `ret` is given {}", ret_type, found),
+                            token
+                        )
+                    );
+                } else {
+                    self.typed_expr(expr, &ret_type, errors);
+                }
+            }
+        }
+    }
+}
+
+//================
+//   mismatch()
+//================
+// what an expression is when its shape alone shows it can not have the given type: a literal,
+//  a list, a tuple, an option, a comparison or a variable declared with another type
+impl<'a> TypeChecker<'a> {
+    fn mismatch(
+        &self,
+        expr: &Expr,
+        _type: &Type,
+    ) -> Option<String> {
+        if let Some(found) = self.literal_mismatch(expr, _type) {
+            return Some(found.to_string())
+        }
+        let expected = self.kind(_type)?;
+        let (found, kind) = match expr {
+            Expr::Unit => ("`()`".to_string(), "unit".to_string()),
+            Expr::List(_) => ("a list".to_string(), "list".to_string()),
+            Expr::Map(_) => ("a map".to_string(), "map".to_string()),
+            Expr::Tuple(tuple) => ("a tuple".to_string(), format!("tuple{}", tuple.items.len())),
+            Expr::Some(_) | Expr::None => ("an option".to_string(), "option".to_string()),
+            Expr::Ok(_) | Expr::Err(_) => ("a result".to_string(), "result".to_string()),
+            Expr::BinOp(bin_op) if is_bool_op(&bin_op.op.value) => ("a boolean".to_string(), "type.bool".to_string()),
+            Expr::PreUniOp(UniOp{op, ..}) if matches!(op.value, TokenValue::Not) => ("a boolean".to_string(), "type.bool".to_string()),
+            Expr::Ref(id) => {
+                let name = id.value.to_string();
+                let declared = self.var_types.last()?
+                    .iter()
+                    .rev()
+                    .find(|(var, _)| *var == name)
+                    .and_then(|(_, _type)| _type.clone())?;
+                (format!("`{}` of type `{}`", name, declared), self.kind(&declared)?)
+            },
+            _ => return None
+        };
+        if kind == expected { None } else { Some(found) }
     }
-}
\ No newline at end of file
+}
+
+//================
+//   kind()
+//================
+// the outer shape of a type, `None` for the types that need the resolver to compare: structs,
+//  named and generic types and functions
+impl<'a> TypeChecker<'a> {
+    fn kind(
+        &self,
+        _type: &Type,
+    ) -> Option<String> {
+        let kind = match _type {
+            Type::UnitType => "unit".to_string(),
+            Type::PrimitiveType(PrimitiveType{id}) => resolve(self.lexicon.concept_any("type", &id.value.to_string())?).to_string(),
+            Type::ListType(_) => "list".to_string(),
+            Type::MapType(_) => "map".to_string(),
+            Type::TupleType(TupleType{types}) => format!("tuple{}", types.len()),
+            Type::OptionType(_) => "option".to_string(),
+            Type::ResultType(_) => "result".to_string(),
+            _ => return None
+        };
+        Some(kind)
+    }
+}
+
+//================
+//   literal_mismatch()
+//================
+// the kind of a literal that can not have the given primitive type
+impl<'a> TypeChecker<'a> {
+    fn literal_mismatch(
+        &self,
+        expr: &Expr,
+        _type: &Type,
+    ) -> Option<&'static str> {
+        let concept = match _type {
+            Type::PrimitiveType(PrimitiveType{id}) => self.lexicon.concept_any("type", &id.value.to_string())?,
+            _ => return None
+        };
+        let (found, fits) = match expr {
+            Expr::Str(_) => ("a string", concept == "type.str"),
+            Expr::Char(_) => ("a character", concept == "type.char"),
+            Expr::Bool(_) => ("a boolean", concept == "type.bool"),
+            Expr::Float(_) => ("a float", is_float(concept)),
+            Expr::Int(_) => ("an integer", int_range(concept).is_some() || is_float(concept)),
+            _ => return None
+        };
+        if fits { None } else { Some(found) }
+    }
+}
+
+//================
+//   decl()
+//================
+impl<'a> TypeChecker<'a> {
+    fn decl(
+        &mut self,
+        decl: &Decl,
+        errors: &mut Vec<Error>
+    ) {
+        // a local without a type hides a param of the same name, its type is left to the inference
+        if let (Pattern::Id(IdPattern{id}), Some(vars)) = (&decl.pattern, self.var_types.last_mut()) {
+            vars.push((id.value.to_string(), decl._type.clone()));
+        }
+        if let Some(expr) = &decl.expr {
+            if let Some(_type) = &decl._type {
+                self.typed_expr(expr, _type, errors);
+            }
+            self.expr(expr, errors);
+        }
+    }
+}
+
+//================
+//   expr()
+//================
+// looks for declarations nested in blocks
+impl<'a> TypeChecker<'a> {
+    fn expr(
+        &mut self,
+        expr: &Expr,
+        errors: &mut Vec<Error>
+    ) {
+        match expr {
+            Expr::Fn(_fn) => self._fn(_fn, errors),
+            Expr::Match(_match) => {
+                for arm in &_match.arms {
+                    self.block(&arm.block, errors);
+                }
+            },
+            Expr::For(_for) => self.block(&_for.block, errors),
+            Expr::While(_while) => self.block(&_while.block, errors),
+            Expr::If(_if) => {
+                for branch in &_if.branches {
+                    self.block(&branch.block, errors);
+                }
+            },
+            Expr::BinOp(bin_op) => {
+                self.expr(&bin_op.l_opr, errors);
+                self.expr(&bin_op.r_opr, errors);
+            },
+            Expr::Loop(_loop) => self.block(&_loop.block, errors),
+            Expr::LetIn(let_in) => {
+                self.decl(&let_in.decl, errors);
+                self.expr(&let_in.expr, errors);
+            },
+            _ => ()
+        }
+    }
+}
+
+//================
+//   typed_expr()
+//================
+// an expression whose type is written next to it
+impl<'a> TypeChecker<'a> {
+    fn typed_expr(
+        &mut self,
+        expr: &Expr,
+        _type: &Type,
+        errors: &mut Vec<Error>
+    ) {
+        match (expr, _type) {
+            (Expr::Int(t), Type::PrimitiveType(PrimitiveType{id})) => {
+                self.int_literal(t, false, id, errors);
+            },
+            (Expr::PreUniOp(UniOp{opr, op}), Type::PrimitiveType(PrimitiveType{id}))
+                if matches!(op.value, TokenValue::Minus) => {
+                if let Expr::Int(t) = &**opr {
+                    self.int_literal(t, true, id, errors);
+                }
+            },
+            (Expr::List(list), Type::ListType(ListType{els_type})) => {
+                for item in &list.items {
+                    self.typed_expr(item, els_type, errors);
+                }
+            },
+            (Expr::Map(map), Type::MapType(MapType{key_type, value_type, ..})) => {
+                for (key, value) in &map.items {
+                    self.typed_expr(key, key_type, errors);
+                    self.typed_expr(value, value_type, errors);
+                }
+            },
+            (Expr::Tuple(tuple), Type::TupleType(TupleType{types})) => {
+                for (item, _type) in tuple.items.iter().zip(types.iter()) {
+                    self.typed_expr(item, _type, errors);
+                }
+            },
+            (Expr::Some(expr), Type::OptionType(opt_type)) => {
+                self.typed_expr(expr, &opt_type.some_type, errors);
+            },
+            _ => ()
+        }
+    }
+}
+
+//================
+//   int_literal()
+//================
+impl<'a> TypeChecker<'a> {
+    fn int_literal(
+        &mut self,
+        literal: &Token,
+        negative: bool,
+        type_id: &Token,
+        errors: &mut Vec<Error>
+    ) {
+        let type_name = type_id.value.to_string();
+        let range = match self.lexicon.concept_any("type", &type_name) {
+            Some(concept) => int_range(concept),
+            None => None
+        };
+        let (min, max) = match range {
+            Some(range) => range,
+            None => return      // not an integer type, left to the rest of the type checker
+        };
+
+        let value = to_western_num(&literal.value.to_string());
+        let fits = match value.parse::<u128>() {
+            Err(_) => false,
+            Ok(value) if negative => value <= min.unsigned_abs(),
+            Ok(value) => value <= max
+        };
+
+        if !fits {
+            let sign = if negative { "-" } else { "" };
+            errors.push(
+                error!(
+                    format!("literal out of range for `{}`: {}{}, the range is {} to {}", type_name, sign, literal.value, min, max),
+                    literal
+                )
+            );
+        }
+    }
+}
+
+//================
+//   is_bool_op()
+//================
+fn is_bool_op(op: &TokenValue) -> bool {
+    matches!(
+        op,
+        TokenValue::Eq | TokenValue::NE | TokenValue::GT | TokenValue::GE | TokenValue::LT | TokenValue::LE
+        | TokenValue::LogicalAnd | TokenValue::LogicalOr
+    )
+}
+
+//================
+//   is_float()
+//================
+fn is_float(concept: &str) -> bool {
+    matches!(concept, "type.float" | "type.f32" | "type.f64")
+}
+
+//================
+//   resolve()
+//================
+// the sized type a primitive type stands for, `int` and `i32` are the same type
+fn resolve(concept: &str) -> &str {
+    match concept {
+        "type.int" => "type.i32",
+        "type.float" => "type.f32",
+        "type.byte" => "type.u8",
+        _ => concept
+    }
+}
+
+//================
+//   int_range()
+//================
+// FIXME: usize assumes a 64 bit target
+pub fn int_range(concept: &str) -> Option<(i128, u128)> {
+    let range = match concept {
+        "type.i8" => (i8::MIN as i128, i8::MAX as u128),
+        "type.i16" => (i16::MIN as i128, i16::MAX as u128),
+        "type.int" | "type.i32" => (i32::MIN as i128, i32::MAX as u128),
+        "type.i64" => (i64::MIN as i128, i64::MAX as u128),
+        "type.i128" => (i128::MIN, i128::MAX as u128),
+        "type.u8" | "type.byte" => (0, u8::MAX as u128),
+        "type.u16" => (0, u16::MAX as u128),
+        "type.u32" => (0, u32::MAX as u128),
+        "type.u64" | "type.usize" => (0, u64::MAX as u128),
+        "type.u128" => (0, u128::MAX),
+        _ => return None
+    };
+    Some(range)
+}
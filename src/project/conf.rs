@@ -17,13 +17,15 @@ use clap::{
 use crate::lang::{
 	Lang,
 	compiler,
+	token::Token,
 	syntax_tree::ast::{
 		ModElement,
 		Fn,
 		List,
 		StructLiteral,
 		Expr,
-		BlockElement
+		BlockElement,
+		Ret
 	}
 };
 
@@ -31,9 +33,13 @@ use crate::project::{
     proj_dir,
 };
 
-use crate::transl::transl::Transl;
+use crate::transl::{
+	transl::Transl,
+	lexicon::Lexicon
+};
 
 use crate::tool::cargo::*;
+use crate::util::ar::to_western_num;
 
 //================
 //   Conf
@@ -42,6 +48,7 @@ use crate::tool::cargo::*;
 pub struct Conf {
 	pub proj_lang: Lang,
 	pub transl: Transl,
+	pub lexicon: Lexicon,
     // pub proj_name: String,
     // pub pre_build: PreBuild
 	pub data: Vec<ConfElement>
@@ -57,28 +64,37 @@ impl Conf {
 			Err(err) => panic!("{}", err) // FIXME panic!
 		};
 
-		let transl = Transl::new(&proj_lang);
+		let lexicon = lexicon(home, &proj_lang);
+
+		let transl = Transl::new(&proj_lang, &lexicon);
 
 		let data = Self::elements(&transl, &home);
 
         Self {
 			proj_lang,
 			transl,
+			lexicon,
 			data
             
         }
     }
 
+	//---------------------
+	//   allow_bidi()
+	//---------------------
+	pub fn allow_bidi(&self) -> bool {
+		self.data
+			.iter()
+			.any(|el| matches!(el, ConfElement::Main(Main{allow_bidi: true, ..})))
+	}
+
 	//---------------------
 	//   proj_lang()
 	//---------------------
 	pub fn proj_lang(home: &PathBuf) -> Result<Lang, String> {
-		if conf_ar(home).exists() {
-			Ok(Lang::Ar)
-		} else if conf_en(home).exists() {
-			Ok(Lang::En)
-		} else {
-			Err(Transl::missing_conf_err_en())     		// FIXME, if conf.seen is not available , check if any other files with an extension 
+		match Lang::all().into_iter().find(|lang| conf_path(home, lang).exists()) {
+			Some(lang) => Ok(lang),
+			None => Err(Transl::missing_conf_err_en())     		// FIXME, if conf.seen is not available , check if any other files with an extension 
 														//          is available to determine which language should be used to output the error message
 														//          if language cannot be determinded then output a bilingual message or have 2 separate seen tools
 														//          if it's the english one then default messages to english
@@ -88,23 +104,16 @@ impl Conf {
 	}
 
 	//---------------------
-	//   conf_ar()
-	//---------------------
-	pub fn conf_ar(home: &PathBuf) -> PathBuf {
-		let mut conf_ar = home.clone();
-		conf_ar.push(Transl::conf_ar());
-		conf_ar.set_extension(Transl::seen_ext_ar());
-		conf_ar
-	}
-
+	//   conf_path()
 	//---------------------
-	//   conf_en()
-	//---------------------
-	pub fn conf_en(home: &PathBuf) -> PathBuf {
-		let mut conf_en = home.clone();
-		conf_en.push(Transl::conf_en());
-		conf_en.set_extension(Transl::seen_ext_en());	
-		conf_en
+	pub fn conf_path(
+		home: &PathBuf,
+		lang: &Lang
+	) -> PathBuf {
+		let mut conf = home.clone();
+		conf.push(Transl::conf_of(lang));
+		conf.set_extension(Transl::seen_ext_of(lang));
+		conf
 	}
 
 	//---------------------
@@ -135,10 +144,12 @@ impl Conf {
 					let name = t.to_string();
 					if name == transl.deps() {
 						deps(&el, &transl, &stmts, &mut data);
-					} else if name == transl.rust() || name == transl.rs() {
+					} else if transl.is_rust(&name) {
 						rust(&el, &transl, &stmts, &mut data);
-					} else if name == transl.python() || name == transl.py() {
+					} else if transl.is_python(&name) {
 						python(&el, &transl, &stmts, &mut data);
+					} else if transl.is_profiles(&name) {
+						profiles(&el, &transl, &stmts, &mut data);
 					} else {
 						panic!("unexpected conf function: {:?}", t)	
 					}
@@ -174,9 +185,9 @@ fn pkg(
 	let mut main = Main::new();
 	for stmt in stmts {
 		match stmt {
-			BlockElement::Expr(Expr::Ret(_box)) => {
+			BlockElement::Ret(Ret{expr: Some(_box), ..}) => {
 				match &**_box {
-					Expr::StructLiteral(StructLiteral{items}) => {
+					Expr::StructLiteral(StructLiteral{items, ..}) => {
 						for item in items {
 							// match items.get(0).unwrap() {
 							match item {
@@ -189,6 +200,13 @@ fn pkg(
 												panic!("unknown item: {:?}", t)   
 											}
 										},
+										Expr::Bool(v) => {
+											if t.to_string() == transl.allow_bidi() {
+												main.allow_bidi = transl.is_true(&v.to_string());
+											} else {
+												panic!("unknown item: {:?}", t)   
+											}
+										},
 										x => panic!("unknown item: {:?}", x)
 									}
 								},
@@ -229,9 +247,9 @@ fn rust(
 	let mut rust = Rust::new();
 	for stmt in stmts {
 	   match stmt {
-		   BlockElement::Expr(Expr::Ret(_box)) => {
+		   BlockElement::Ret(Ret{expr: Some(_box), ..}) => {
 			   match &**_box {
-				   Expr::StructLiteral(StructLiteral{items}) => {
+				   Expr::StructLiteral(StructLiteral{items, ..}) => {
 					   for item in items {
 						   match item {
 							   (t, expr ) => {
@@ -266,7 +284,7 @@ fn rust_deps(
 	match expr.as_ref().unwrap() { // FIXME unwrap()
 		Expr::List(List{items}) => {	
 			for item in items {
-				if let Expr::StructLiteral(StructLiteral{items}) = item {
+				if let Expr::StructLiteral(StructLiteral{items, ..}) = item {
 					let mut dep = RustDep::new();
 					for struct_item in items {						
 						match struct_item {							
@@ -275,14 +293,14 @@ fn rust_deps(
 									Expr::Str(v) => {
 										if t.to_string() == transl.id() {
 											dep.id = v.to_string();	
-										} else if t.to_string() == transl.v() || t.to_string() == transl.version() {
+										} else if transl.is_version(&t.to_string()) {
 											dep.ver = v.to_string();	
 										} else  {
 											panic!("unknown item: {:?}", t)   
 										}
 									},
 									Expr::List(list) => {
-										if t.to_string() == transl.f() || t.to_string() == transl.features() {
+										if transl.is_features(&t.to_string()) {
 											dep.features = Some(list.to_owned());	
 										} else  {
 											panic!("unknown item: {:?}", t)   
@@ -305,6 +323,100 @@ fn rust_deps(
 }
 
 
+//================
+//   profiles()
+//================
+// profiles() -> {
+//     release: { opt_level: 3, lto: "fat", strip: true, debug: 0, panic: "abort", codegen_units: 1 }
+//     fast: { inherits: "release", opt_level: 2 }
+// }
+fn profiles(
+	el: &ModElement,
+	transl: &Transl,
+	stmts: &Vec<BlockElement>,
+	data: &mut Vec<ConfElement>
+) {
+	let mut profiles = vec![];
+	for stmt in stmts {
+		match stmt {
+			BlockElement::Ret(Ret{expr: Some(_box), ..}) => {
+				match &**_box {
+					Expr::StructLiteral(StructLiteral{items, ..}) => {
+						for (t, expr) in items {
+							match expr {
+								Some(Expr::StructLiteral(StructLiteral{items, ..})) => {
+									profiles.push(profile(&t.to_string(), items, transl));
+								},
+								x => panic!("expecting the settings of the profile `{}`: {:?}", t, x)
+							}
+						}
+					},
+					_ => panic!("unexpected statement: {:?}", el)
+				}
+			},
+			_ => panic!("unexpected statement: {:?}", el)
+		}
+	}
+	data.push( ConfElement::Profiles(profiles) );
+}
+
+//================
+//   profile()
+//================
+fn profile(
+	name: &str,
+	items: &Vec<(Token, Option<Expr>)>,
+	transl: &Transl
+) -> Profile {
+	let mut profile = Profile::new(&profile_name(transl, name));
+	for (t, expr) in items {
+		let word = t.to_string();
+		let key = if transl.is_inherits(&word) {
+			"inherits"
+		} else if transl.is_opt_level(&word) {
+			"opt-level"
+		} else if transl.is_lto(&word) {
+			"lto"
+		} else if transl.is_strip(&word) {
+			"strip"
+		} else if transl.is_debug(&word) {
+			"debug"
+		} else if transl.is_panic(&word) {
+			"panic"
+		} else if transl.is_codegen_units(&word) {
+			"codegen-units"
+		} else {
+			panic!("unknown item: {:?}", t)
+		};
+		let value = match expr.as_ref().unwrap() {		// FIXME unwrap()
+			Expr::Str(v) if key == "inherits" => format!("\"{}\"", profile_name(transl, &v.to_string())),
+			Expr::Str(v) => format!("\"{}\"", v),
+			Expr::Int(v) => to_western_num(&v.to_string()),
+			Expr::Bool(v) => transl.is_true(&v.to_string()).to_string(),
+			x => panic!("unknown item: {:?}", x)
+		};
+		profile.set(key, &value);
+	}
+	profile
+}
+
+//================
+//   profile_name()
+//================
+// the cargo name of a profile, `release` and `dev` can be written in the language of the project
+pub fn profile_name(
+	transl: &Transl,
+	name: &str
+) -> String {
+	if transl.is_release(name) {
+		String::from("release")
+	} else if transl.is_dev(name) {
+		String::from("dev")
+	} else {
+		name.to_string()
+	}
+}
+
 //================
 //   python()
 //================
@@ -318,9 +430,9 @@ fn python(
 	let mut python = Python::new();
 	for stmt in stmts {
 	   match stmt {
-		   BlockElement::Expr(Expr::Ret(_box)) => {
+		   BlockElement::Ret(Ret{expr: Some(_box), ..}) => {
 			   match &**_box {
-				   Expr::StructLiteral(StructLiteral{items}) => {
+				   Expr::StructLiteral(StructLiteral{items, ..}) => {
 					   for item in items {
 						   match item {
 							   (t, expr ) => {
@@ -369,7 +481,7 @@ fn python_deps(
 	match expr.as_ref().unwrap() { // FIXME unwrap()
 		Expr::List(List{items}) => {	
 			for item in items {
-				if let Expr::StructLiteral(StructLiteral{items}) = item {
+				if let Expr::StructLiteral(StructLiteral{items, ..}) = item {
 					for item in items {
 						match item {
 							(t, expr ) => {
@@ -403,12 +515,9 @@ fn python_deps(
 //   proj_lang()
 //================
 pub fn proj_lang(home: &PathBuf) -> Result<Lang, String> {
-	if conf_ar(home).exists() {
-		Ok(Lang::Ar)
-	} else if conf_en(home).exists() {
-		Ok(Lang::En)
-	} else {
-		Err(Transl::missing_conf_err_en())     		// FIXME, if conf.seen is not available , check if any other files with an extension 
+	match Lang::all().into_iter().find(|lang| conf_path(home, lang).exists()) {
+		Some(lang) => Ok(lang),
+		None => Err(Transl::missing_conf_err_en())     		// FIXME, if conf.seen is not available , check if any other files with an extension 
                                                     //          is available to determine which language should be used to output the error message
                                                     //          if language cannot be determinded then output a bilingual message or have 2 separate seen tools
                                                     //          if it's the english one then default messages to english
@@ -418,23 +527,29 @@ pub fn proj_lang(home: &PathBuf) -> Result<Lang, String> {
 }
 
 //================
-//   conf_ar()
+//   lexicon()
 //================
-pub fn conf_ar(home: &PathBuf) -> PathBuf {
-	let mut conf_ar = home.clone();
-	conf_ar.push(Transl::conf_ar());
-	conf_ar.set_extension(Transl::seen_ext_ar());
-	conf_ar
+pub fn lexicon(
+	home: &PathBuf,
+	proj_lang: &Lang
+) -> Lexicon {
+	match Lexicon::load(home, proj_lang) {
+		Ok(lexicon) => lexicon,
+		Err(err) => panic!("{}", err) // FIXME panic!
+	}
 }
 
 //================
-//   conf_en()
+//   conf_path()
 //================
-pub fn conf_en(home: &PathBuf) -> PathBuf {
-	let mut conf_en = home.clone();
-	conf_en.push(Transl::conf_en());
-	conf_en.set_extension(Transl::seen_ext_en());	
-	conf_en
+pub fn conf_path(
+	home: &PathBuf,
+	lang: &Lang
+) -> PathBuf {
+	let mut conf = home.clone();
+	conf.push(Transl::conf_of(lang));
+	conf.set_extension(Transl::seen_ext_of(lang));
+	conf
 }
 
 //================
@@ -453,10 +568,10 @@ pub fn proj_name(
 	match ast.get(0).unwrap() {
 		ModElement::MainFn(Fn{block: els,..}) => {
 			match els.get(0).unwrap() {	// assuming that the name is provided in the config file
-				BlockElement::Expr(Expr::Ret(_box)) => {
+				BlockElement::Ret(Ret{expr: Some(_box), ..}) => {
 					let expr = *_box.clone();
 						match expr {
-							Expr::StructLiteral(StructLiteral{items})  => {
+							Expr::StructLiteral(StructLiteral{items, ..})  => {
 								match items.get(0).unwrap() {
 									(t, expr ) => {
 										match expr.as_ref().unwrap() {
@@ -503,6 +618,7 @@ pub enum ConfElement {
 
 	Rust(Rust),
 	Python(Python),
+	Profiles(Vec<Profile>),
 	Prebuild(Fn)
 }
 
@@ -556,6 +672,43 @@ impl RustDep {
 }
 
 
+//================
+//   Profile
+//================
+// a `[profile.<name>]` of the generated Cargo.toml, the settings are cargo keys and toml values
+#[derive(Clone, Debug)]
+pub struct Profile {
+	pub name: String,
+	pub settings: Vec<(String, String)>
+}
+
+impl Profile {
+    //---------------------
+    //  new()
+    //---------------------  		
+	pub fn new(name: &str) -> Self {
+		Self {
+			name: name.to_string(),
+			settings: vec![]
+		}
+	}
+
+    //---------------------
+    //  set()
+    //---------------------  		
+	// replaces the value of a key that is already set, in its place
+	pub fn set(
+		&mut self,
+		key: &str,
+		value: &str
+	) {
+		match self.settings.iter_mut().find(|(k, _)| k == key) {
+			Some((_, v)) => *v = value.to_string(),
+			None => self.settings.push((key.to_string(), value.to_string()))
+		}
+	}
+}
+
 //================
 //   Python
 //================
@@ -608,7 +761,8 @@ impl PythonDep {
 //================
 #[derive(Debug)]
 pub struct Main {
-	pub proj_name: String
+	pub proj_name: String,
+	pub allow_bidi: bool
 } 
 
 impl Main {
@@ -617,7 +771,8 @@ impl Main {
     //---------------------  		
 	pub fn new() -> Self {
 		Self {
-			proj_name: String::from("")
+			proj_name: String::from(""),
+			allow_bidi: false
 		}
 
 	}
@@ -5,47 +5,9 @@ use std::{
     path::PathBuf
 };
 use crate::lang::Lang;
+use crate::transl::lexicon::Lexicon;
 
-use super::templates::{ar, en};
-
-// FIXME create a dictionary struct and store the translations in a data file instead of hardcoding and manual lookups
-//================
-//  Constants
-//================
-const CONF_DIR_EN: &str = "conf";
-const CONF_DIR_AR: &str = "هيئة";
-
-const PACKAGE_CFG_EN: &str = "pkg";
-const PACKAGE_CFG_AR: &str = "حزمة";
-
-
-const RES_DIR_EN: &str = "res";
-const RES_DIR_AR: &str = "موارد";
-
-const PAGES_DIR_EN: &str = "pages";
-const PAGES_DIR_AR: &str = "صفحات";
-
-const AUDIO_DIR_EN: &str = "audio";
-const AUDIO_DIR_AR: &str = "صوتي";
-
-
-const IMAGES_DIR_EN: &str = "images";
-const IMAGES_DIR_AR: &str = "صور";
-
-const VIDEO_DIR_EN: &str = "videos";
-const VIDEO_DIR_AR: &str = "مرئي";
-
-
-
-const SRC_DIR_EN: &str = "src";
-const SRC_DIR_AR: &str = "مصدر";
-
-const MAIN_FILE_EN: &str = "main";
-const MAIN_FILE_AR: &str = "رئيسي";
-
-const BUILD_DIR_EN: &str = "build";
-const BUILD_DIR_AR: &str = "بنية";
-
+use super::templates::{ar, en, fa};
 
 //================
 //  ProjDir
@@ -79,6 +41,8 @@ impl ProjDir {
             }
         };
 
+        let lexicon = Lexicon::embedded();
+
         let name = home.file_name()
                         .unwrap()
                         .to_os_string()
@@ -87,29 +51,14 @@ impl ProjDir {
 
         // FIXME switched from conf dir to conf.seen
         let mut conf = home.clone();
-        conf.push(
-            match lang { 
-                Lang::Ar => CONF_DIR_AR, 
-                Lang::En => CONF_DIR_EN
-            }
-        );
+        conf.push(lexicon.word(&lang, "conf.conf"));
 
 
         let mut path = home.clone();
-        path.push(
-            match lang {
-                Lang::Ar => RES_DIR_AR,
-                Lang::En => RES_DIR_EN
-            }
-        );        
+        path.push(lexicon.word(&lang, "dir.res"));        
 
         let mut pages = path.clone();
-        pages.push(
-            match lang {
-                Lang::Ar => PAGES_DIR_AR,
-                Lang::En => PAGES_DIR_EN
-            }
-        );
+        pages.push(lexicon.word(&lang, "dir.pages"));
 
         let res = ResDir {
             path: path,
@@ -118,20 +67,10 @@ impl ProjDir {
 
 
         let mut src_path = home.clone();
-        src_path.push(
-            match lang {
-                Lang::Ar => SRC_DIR_AR,
-                Lang::En => SRC_DIR_EN
-            }
-        );
+        src_path.push(lexicon.word(&lang, "conf.src"));
 
         let mut main_file = src_path.clone();
-        main_file.push(
-            match lang {
-                Lang::Ar=> MAIN_FILE_AR,
-                Lang::En=> MAIN_FILE_EN
-            }
-        );
+        main_file.push(lexicon.word(&lang, "conf.main"));
         main_file.set_extension(lang.ext());
 
 
@@ -141,12 +80,7 @@ impl ProjDir {
         };
     
         let mut build = home.clone();
-        build.push(
-            match lang {
-                Lang::Ar => BUILD_DIR_AR,
-                Lang::En => BUILD_DIR_EN
-            }
-        );
+        build.push(lexicon.word(&lang, "conf.build"));
 
         Self {
             name,
@@ -193,6 +127,15 @@ format!(r#"() -> {{
 // deps -> [
 //
 // ]
+"#, self.name),
+            Lang::Fa =>  
+format!(r#"() -> {{
+    نام: «{}»
+}}
+
+\\ وابستگی_ها -> [
+\\ 
+\\ ]
 "#, self.name)
         };
 
@@ -234,7 +177,8 @@ format!(r#"() -> {{
                 match name.as_str() {
                     "webserver" => match self.lang{
                         Lang::Ar => ar::web_server::code(),
-                        Lang::En => en::web_server::code()
+                        Lang::En => en::web_server::code(),
+                        Lang::Fa => fa::web_server::code()
                     },
                     _ => panic!("unsupported template")
                 }
@@ -242,7 +186,8 @@ format!(r#"() -> {{
             None => {
                 match self.lang {
                     Lang::Ar => ar::cli::code(),
-                    Lang::En => en::cli::code()
+                    Lang::En => en::cli::code(),
+                    Lang::Fa => fa::cli::code()
                 }
             }
         };
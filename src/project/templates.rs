@@ -1,2 +1,3 @@
 pub mod en;
-pub mod ar;
\ No newline at end of file
+pub mod ar;
+pub mod fa;
\ No newline at end of file
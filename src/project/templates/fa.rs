@@ -0,0 +1,2 @@
+pub mod cli;
+pub mod web_server;
\ No newline at end of file
@@ -0,0 +1,3 @@
+pub fn code() -> String {
+    String::from(r#"()-> چاپ_خط(«سلام دنیا»)"#)
+}
\ No newline at end of file
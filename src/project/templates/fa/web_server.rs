@@ -0,0 +1,15 @@
+pub fn code() -> String {
+    String::from(
+r#"⎔سرور_وب
+() -> 
+    داده:
+        تنظیمات:
+            نام_میزبان: «میزبان_محلی»
+            درگاه: ۸۹۸۹
+        
+        صفحه_اصلی: 
+            عنوان: «سلام دنیا»
+            محتوا: «سلام دنیا»
+        
+"#)
+}
\ No newline at end of file
@@ -1,4 +1,5 @@
-use crate::util::cli::*;
+use crate::util::cli::{self, *};
+use crate::lang::passes::PassOptions;
 
 //================
 //   seen()
@@ -8,22 +9,25 @@ pub fn seen() {
 	let cli = Cli::new();
 	
 	match cli.command {
-		Some(Commands::New(New{ar, name, template})) => {  
-			if let Err(err) = New::exec(ar, name, None, template){ 
+		Some(Commands::New(New{ar, fa, name, template})) => {  
+			if let Err(err) = New::exec(ar, fa, name, None, template){ 
 				eprintln!("{}", err);
 			} 
 		},
-		Some(Commands::Init(Init{ar, template})) => { 
-			if let Err(err) = Init::exec(ar, None, template) {
+		Some(Commands::Init(Init{ar, fa, template})) => { 
+			if let Err(err) = Init::exec(ar, fa, None, template) {
 				eprintln!("{}", err);
 			} 
 		},
-		Some(Commands::Compile(Compile{})) => Compile::exec(None),
-		Some(Commands::Build(Build{})) => { let _ = Build::exec(None, false); },
-		Some(Commands::Run(Run{})) => { let _ = Run::exec(None, false); },	
+		Some(Commands::Compile(Compile{no_pass, dump_ir})) => {
+			Compile::exec(None, &PassOptions{ disabled: no_pass, dump_ir })
+		},
+		Some(Commands::Build(Build{release, profile})) => { let _ = Build::exec(None, cli::profile(release, profile), false); },
+		Some(Commands::Run(Run{args, release, profile})) => { let _ = Run::exec(None, &args, cli::profile(release, profile), false); },	
 		Some(Commands::Clean(Clean{})) => todo!(),
 		Some(Commands::Update(Update{})) => todo!(),
 		Some(Commands::Check(Check{})) => todo!(),
+		Some(Commands::Lexicon(Lexicon{})) => Lexicon::exec(None),
 		Some(Commands::Editor(Editor{ar, path})) => Editor::exec(ar, path),
 
 		None => Cli::print_help()
@@ -0,0 +1 @@
+pub mod html_gen;
\ No newline at end of file
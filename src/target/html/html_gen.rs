@@ -0,0 +1,167 @@
+use std::{
+    fs,
+    fmt::{
+        Write,
+    }, 
+    path::PathBuf
+};
+
+
+use crate::lang::{
+    token::TokenValue,
+    Lang,
+    syntax_tree::ast::{
+        StructLiteral,
+        Expr
+    }
+};
+
+use crate::util::indent::Indent;
+use crate::transl::lexicon::Lexicon;
+
+//================
+//   Constants
+//================
+const HTML_EXT: &'static str = "html";
+
+//================
+//   Html
+//================
+pub struct Html<'a> {
+    src_lang: Lang,
+    lexicon: &'a Lexicon,
+    path: String,
+    seen_ext: String,
+    indent: Indent,
+    res: String
+}
+
+impl<'a> Html<'a> {
+    //---------------------
+    //  new()
+    //---------------------    
+    pub fn new (
+        src_lang: &Lang,
+        lexicon: &'a Lexicon,
+        path: &String, 
+        seen_ext: &str
+    ) -> Self {
+        Self {
+            src_lang: src_lang.clone(),
+            lexicon,
+            path: path.clone(),
+            seen_ext: seen_ext.to_string(),
+            indent: Indent::new(),
+            res: String::new()
+        }
+    }    
+}
+
+//================
+//   page()
+//================
+impl<'a> Html<'a> {     
+    pub fn page(
+        &mut self,
+        path: &mut PathBuf,
+        data: &StructLiteral
+    ) {
+
+        let _ = writeln!(
+            self.res, 
+            "{}",
+            if self.src_lang.is_rtl() {
+                "<html dir=\"rtl\">"
+            } else {
+                "<html>"
+            }
+        );
+        self.indent.inc();
+
+
+
+        let iter = if let Some((t, Some(expr))) = data.items.get(0) {
+
+            let mut iter = data.items.iter();
+            match &t.value  {
+                TokenValue::Id(x) => {
+                    if self.lexicon.is("web.data", &self.src_lang, x) {
+                        match expr {
+                            Expr::StructLiteral(literal) => {
+                                iter = literal.items.iter();
+                            }
+                            _ => ()
+                        }    
+                    }
+                },
+                _ => ()
+            }
+            iter
+    
+        } else {
+            data.items.iter()
+        };
+    
+
+
+        // for (k,v) in data.items.iter() {
+            for (k,v) in iter {
+            match self.lexicon.concept("web", &self.src_lang, &k.to_string()) {
+                Some("web.homepage") => {
+                    self.homepage(v);
+                    break;
+
+                },
+                _ => ()
+            };
+        }
+       
+        let _ = writeln!(self.res, "</html>");
+        
+        match fs::write(&path, &self.res){
+            Err(err) => panic!("{:?}", err),
+            Ok(_) => ()
+        }
+
+    }
+}
+
+
+//================
+//   homepage()
+//================
+impl<'a> Html<'a> {     
+    pub fn homepage(
+        &mut self,
+        data: &Option<Expr>
+    ) {
+
+        let data = match data { 
+            Some(Expr::StructLiteral(sruct_literal)) => sruct_literal,
+            _ => panic!("expecting homepage data")
+        };
+
+
+
+        for (k,v) in data.items.iter() {
+        // for (k,v) in iter {
+            match self.lexicon.concept("web", &self.src_lang, &k.to_string()) {
+                Some("web.title") => {
+                    if let Some(v) = v { let _ = writeln!(self.res, "{}<title>{}</title>", self.indent, v); }
+                },
+                Some("web.content") => {
+                    let _ = writeln!(self.res, "{}<body>", self.indent);
+                    self.indent.inc();
+                    if let Some(v) = v { let _ = writeln!(self.res, "{}{}", self.indent, v);}
+                                                
+                    self.indent.dec();
+                    let _ = writeln!(self.res, "{}</body>", self.indent);
+                    self.indent.dec();
+                    
+                },
+                _ => panic!("unsupported: {:?}", k)
+            }
+        }
+
+    }
+}
\ No newline at end of file
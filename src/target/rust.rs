@@ -0,0 +1,5 @@
+#![allow(warnings)]
+pub mod cargo_toml;
+pub mod rs_gen;
+pub mod rs_crate;
+pub mod source_map;
\ No newline at end of file
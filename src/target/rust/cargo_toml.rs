@@ -0,0 +1,298 @@
+use std::{
+    fs,
+    path::PathBuf,
+    fmt::Write,
+};
+
+use crate::{
+    util::indent::Indent, 
+    lang::{syntax_tree::ast::{self, ModElement}, compiler::Data}, project::conf::{SeenDep, Conf, ConfElement, Profile}
+};
+
+use super::rs_crate::Crate;
+
+
+//================
+//   Constants
+//================
+const TOML_EXT: &'static str = "toml";
+// the profiles cargo knows, the others must say which one they start from with `inherits`
+const CARGO_PROFILES: [&str; 4] = ["dev", "release", "test", "bench"];
+
+//================
+//  Package
+//================  
+pub struct Package {
+    pub name: String, 
+    pub version: String,
+    pub edition: String    
+}
+
+impl Package {
+    //---------------------
+    //  new()
+    //---------------------        
+    pub fn write(
+        &mut self,
+        res: &mut String
+    ) {
+        let _ = writeln!(res, "[package]");
+        let _ = writeln!(res, "name = \"{}\"", self.name);
+        let _ = writeln!(res, "version = \"{}\"", self.version);
+        let _ = writeln!(res, "edition = \"{}\"", self.edition);
+        let _ = writeln!(res, "\n# See more keys and their definitions at https://doc.rust-lang.org/cargo/reference/manifest.html\n");        
+    }
+}
+
+
+//================
+//  bin
+//================  
+pub struct Bin {
+    pub name: String, 
+    pub path: String,
+}
+
+impl Bin {
+    //---------------------
+    //  new()
+    //---------------------       
+    pub fn write(
+        &mut self,
+        res: &mut String
+    ) {
+        let _ = writeln!(res, "[[bin]]");
+        let _ = writeln!(res, "name = \"{}\"", self.name);
+        let _ = writeln!(res, "path = \"{}\"", self.path);
+        let _ = writeln!(res, "");        
+    }
+}
+
+//================
+//  Profiles
+//================  
+// the `profiles` of conf.seen, `release` starts with the settings seen always used
+pub struct Profiles {
+    items: Vec<Profile>
+}
+
+impl Profiles {
+    //---------------------
+    //  new()
+    //---------------------        
+    pub fn new() -> Self {
+        let mut release = Profile::new("release");
+        release.set("lto", "true");
+        release.set("opt-level", "1");
+        release.set("strip", "true");
+        Self {
+            items: vec![release]
+        }
+    }
+
+    //---------------------
+    //  set()
+    //---------------------        
+    // the settings of a profile that is already there are merged into it, a new profile of the project
+    //  inherits `release` unless it says otherwise
+    pub fn set(
+        &mut self,
+        profile: &Profile
+    ) {
+        match self.items.iter_mut().find(|p| p.name == profile.name) {
+            Some(p) => {
+                for (key, value) in profile.settings.iter() {
+                    p.set(key, value);
+                }
+            },
+            None => {
+                let mut profile = profile.clone();
+                let is_custom = !CARGO_PROFILES.contains(&profile.name.as_str());
+                if is_custom && !profile.settings.iter().any(|(key, _)| key == "inherits") {
+                    profile.settings.insert(0, (String::from("inherits"), String::from("\"release\"")));
+                }
+                self.items.push(profile)
+            }
+        }
+    }
+
+    //---------------------
+    //  write()
+    //---------------------        
+    pub fn write(
+        &mut self,
+        res: &mut String
+    ) {
+        for (i, profile) in self.items.iter().enumerate() {
+            if i > 0 {
+                let _ = writeln!(res, "");
+            }
+            let _ = writeln!(res, "[profile.{}]", profile.name);
+            for (key, value) in profile.settings.iter() {
+                let _ = writeln!(res, "{} = {}", key, value);
+            }
+        }
+    }
+}
+
+
+//================
+//  Deps
+//================  
+#[derive(Debug)]
+pub struct Deps {
+    items: Vec<Dep>
+}
+
+impl Deps {
+    //---------------------
+    //  new()
+    //---------------------        
+    pub fn write(
+        &mut self,
+        res: &mut String
+    ) {
+        let _ = writeln!(res, "[dependencies]");
+        for dep in self.items.iter_mut() {
+            dep.write(res);
+        }
+
+    }
+}
+
+//================
+//  Dep
+//================  
+#[derive(Debug)]
+pub struct Dep {
+    pub id: String, 
+    pub version: String,
+    pub features: Option<ast::List>
+}
+
+impl Dep {
+    //---------------------
+    //  new()
+    //---------------------        
+    pub fn write(
+        &mut self,
+        res: &mut String
+    ) {
+        if let Some(features) = &self.features {
+            let _ = write!(res, "{} = {{ version = \"{}\", features = [", self.id, self.version);        
+            for feature in features.items.iter() {
+                let _ = write!(res, "\"{}\", ", feature);    
+            }
+            let _ = writeln!(res, "] }}");
+        } else {
+            let _ = writeln!(res, "{} = \"{}\"", self.id, self.version);        
+        }
+    }
+}
+
+//================
+//  CargoToml
+//================  
+pub struct CargoToml {     
+    path: PathBuf,
+    pub package: Package,
+    pub bin: Bin,
+    pub profiles: Profiles,
+    pub deps: Deps,
+    indent: Indent,
+    res: String    
+}
+
+impl CargoToml {     
+    //---------------------
+    //  new()
+    //---------------------    
+    pub fn new(
+        name: &str,
+        path: &PathBuf,    
+        seen_conf: &Conf
+    ) -> Self {
+       
+        let mut deps = Deps { items: vec![]};
+        let mut profiles = Profiles::new();
+        for el in seen_conf.data.iter() {
+            match el {
+                ConfElement::Rust(rs) => {
+                    for rs_dep in rs.deps.iter() {
+                        let dep = Dep{ 
+                            id: rs_dep.id.clone(),
+                            version: rs_dep.ver.clone(),
+                            features: rs_dep.features.clone()
+                        };
+                        deps.items.push(dep);
+                    }
+                },
+                ConfElement::Profiles(items) => {
+                    for profile in items.iter() {
+                        profiles.set(profile);
+                    }
+                },
+                _ => ()
+            }
+        }
+
+        Self {
+            path: path.clone(),
+            package: Package {   // FIXME hardcoded
+                name: name.to_string(),
+                version: "0.1.0".to_string(),
+                edition: "2021".to_string()
+            },
+            bin: Bin {
+                name: name.to_string(),
+                path: "src/main.rs".to_string()
+            },
+            profiles,
+            deps,
+            indent: Indent::new(),
+            res: String::new()
+        }
+    }
+
+    //---------------------
+    //  add()
+    //---------------------   
+    pub fn add(
+        &mut self,
+        _crate: impl Crate
+    )  {
+        self.deps.items.push(
+            Dep { 
+                id: _crate.id().clone(), 
+                version: _crate.version().clone(),
+                features: _crate.features().clone()
+            }
+        );
+    }
+
+}
+
+//================
+//  generate()
+//================  
+impl CargoToml {
+    pub fn generate(&mut self) {
+        self.package.write(&mut self.res);
+
+        self.bin.write(&mut self.res);
+
+        self.profiles.write(&mut self.res);
+
+        let _ = writeln!(self.res, "");
+    
+        self.deps.write(&mut self.res);
+
+        self.path.push("Cargo");
+        self.path.set_extension(TOML_EXT);
+    
+        match fs::write(&self.path, &self.res){
+            Err(err) => panic!("{:?}", err),
+            Ok(_) => ()
+        }        
+    }
+}
\ No newline at end of file
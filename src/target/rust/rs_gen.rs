@@ -0,0 +1,2635 @@
+use std::{
+    fs,
+    fmt::{
+        Write
+    }, path::{Path, PathBuf},
+    collections::HashMap,
+};
+
+use crate::lang::{
+    Lang,
+    token::{
+        Token,
+        Location,
+        TokenValue
+    },
+    syntax_tree::ast::*, 
+    attrs::AttrRegistry,
+    hir::{
+        Hir,
+        Builtin,
+        NEUTRAL_LANG
+    },
+};
+
+use crate::transl::lexicon::Lexicon;
+
+use crate::util::{
+    ar::{
+        to_western_num,
+        to_western_digit,
+        is_eastern_digit
+    },
+    indent::Indent 
+
+};
+
+use crate::target::{
+    build::{
+        BuildDir
+    },
+    html::html_gen::Html,
+    rust::cargo_toml::CargoToml,
+    rust::rs_crate::{
+        actix_files::ActixFiles,
+        actix_web::ActixWeb
+    },
+    rust::source_map::{
+        self,
+        SourceMap
+    }
+};
+
+//================
+//   Constants
+//================
+const RS_EXT: &'static str = "rs";
+const INDEX_HTML: &'static str = "index.html";
+
+
+//================
+//   ModStructs
+//================
+// the fields of the structs of each module by its file name, a literal fills the fields it leaves out
+//  from them, also for a struct of an imported module: `lib.Point { x: 1 }`
+pub type ModStructs = HashMap<String, HashMap<String, StructFields>>;
+
+//================
+//   Rust
+//================
+pub struct Rust<'a> {
+    src_lang: Lang,             // only for the text of the generated program, the HIR is spelled in the neutral language
+    lexicon: &'a Lexicon,
+    attrs: &'a AttrRegistry,
+    path: String,
+    indent: Indent,
+    res: String,
+    html: Option<Html<'a>>,
+    proj_dir : &'a mut BuildDir,
+    cargo_toml : &'a mut CargoToml,
+    struct_generics: HashMap<String, Vec<GenericParam>>,     // the type parameters of each struct, repeated on its impl blocks
+    mod_structs: &'a ModStructs,
+    mod_name: String,                                       // the file name of the module being generated
+    import_mods: HashMap<String, String>,                   // the file name of each imported module by the name it is imported as
+    in_mod: Option<String>,                                 // the imported module a struct literal is accessed through
+    fn_types: HashMap<String, Type>,            // the declared return types of the functions of the module
+    var_types: Vec<Vec<(String, Type)>>,        // the declared types of the names in scope, innermost last, until the type checker is turned on
+    imports: Vec<String>,       // the names of the imported modules, their items are reached with `::` rather than `.`
+    source_map: SourceMap,      // the seen location of the generated lines, to point rustc diagnostics back at the source
+    rs_line: (usize, usize)     // the number of the line being written and the length of `res` it was counted at
+}
+
+impl <'a> Rust<'a> {
+    //---------------------
+    //  new()
+    //---------------------
+    pub fn new (
+        project_struct: &'a mut BuildDir,
+        cargo_toml: &'a mut CargoToml,
+        lexicon: &'a Lexicon,
+        attrs: &'a AttrRegistry,
+        mod_structs: &'a ModStructs
+    ) -> Self {
+        Self {
+            src_lang: Lang::Ar,
+            lexicon,
+            attrs,
+            path: String::new(),
+            indent: Indent::new(),
+            res: String::new(),
+            html: None,
+            proj_dir: project_struct,
+            cargo_toml,
+            struct_generics: HashMap::new(),
+            mod_structs,
+            mod_name: String::new(),
+            import_mods: HashMap::new(),
+            in_mod: None,
+            fn_types: HashMap::new(),
+            var_types: vec![vec![]],
+            imports: vec![],
+            source_map: SourceMap::default(),
+            rs_line: (1, 0)
+        }
+    }
+
+    //---------------------
+    //  generate()
+    //---------------------
+    pub fn generate(
+        &mut self,
+        path: &String,
+        hir: &Hir,
+        main_mods: &Vec<String>
+    ) {
+
+        let _ = writeln!(self.res, "#![allow(warnings)]\n");
+
+
+        self.src_lang = hir.lang.clone();
+        self.path = path.clone();
+        self.mod_name = Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.source_map = SourceMap::new(path);
+        self.html = Some(Html::new(
+            &self.src_lang,
+            self.lexicon,
+            &self.path, 
+            self.src_lang.ext()
+        ));
+        self.imports = hir.imports
+            .iter()
+            .map(|import| import.name.clone())
+            .collect();
+        self.import_mods = hir.imports
+            .iter()
+            .map(|import| (import.name.clone(), import.path.clone()))
+            .collect();
+
+        for import in hir.imports.iter() {
+            let _ = writeln!(self.res, "#[path = \"{}.rs\"]", import.path);
+            let _ = writeln!(self.res, "mod {};", import.name);
+        }
+
+        for el in hir.items.iter() {
+            match el {
+                ModElement::Struct(el) => {
+                    self.struct_generics.insert(el.name.to_string(), el.generics.clone());
+                },
+                ModElement::Fn(Fn{name: Some(name), ret_type: Some(ret_type), ..}) => {
+                    self.fn_types.insert(name.value.to_string(), ret_type.clone());
+                },
+                _ => ()
+            }
+        }
+
+        for el in hir.items.iter() {
+            match el {
+                ModElement::Decl(el) => self.decl(&el),
+                ModElement::MainFn(el) => self.main_fn(el, main_mods),
+                ModElement::Fn(el) => self._fn(el),
+                ModElement::Struct(el) =>  self._struct(el),
+                ModElement::StructImpl(el) => self.struct_impl(el),
+                ModElement::Trait(el) => self._trait(el),
+                ModElement::Enum(el) => self._enum(el),
+                ModElement::EnumImpl(el) => self.enum_impl(el),                
+            }
+        }
+        let mut path_buf = self.proj_dir.src.path.clone();
+        path_buf.push(&hir.name);
+        path_buf.set_extension("rs");
+        match fs::write(&path_buf, &self.res){
+            Err(err) => panic!("{:?}", err),
+            Ok(_) => ()
+        }            
+        self.source_map.save(&path_buf);
+    }
+}
+
+//================
+//   mark()
+//================
+// the line being written comes from `token`
+impl <'a> Rust<'a> {
+    fn mark(
+        &mut self,
+        token: Option<&Token>
+    ) {
+        if let Some(token) = token {
+            let (line, counted) = self.rs_line;
+            let rs_line = line + self.res[counted..].matches('\n').count();
+            self.rs_line = (rs_line, self.res.len());
+            // the line is marked before its indent is written
+            let rs_column = self.indent.to_string().len() + 1;
+            self.source_map.mark(rs_line, rs_column, &token.location);
+        }
+    }
+}
+
+// //================
+// //   asgmt()
+// //================
+// impl <'a> Rust<'a> {
+//     fn asgmt(
+//         &mut self, 
+//         asgmt: Decl 
+//     ) {
+//         todo!();    // TODO
+//     }
+// }
+    
+//================
+//   main_fn()
+//================
+impl <'a> Rust<'a> {
+    fn main_fn(
+        &mut self,
+        mut _fn: &Fn,
+        main_mods: &Vec<String>
+    ) {
+        if self.attr_fn(_fn) {
+            return
+        }
+        let _ = writeln!(self.res);
+        for _mod in main_mods {
+            if !_mod.is_ascii() {
+                let _ = writeln!(self.res, "#[path = \"{}.rs\"]", _mod);
+            }
+            let _ = writeln!(self.res, "mod {};", _mod);    
+        }
+        let _ = writeln!(self.res);
+        let _ = write!(self.res, "fn main()");
+        if self.fn_main_has_params(&_fn.params) {    // TODO: main with params is not tested
+            // _fn.block.insert(0, BlockElement::MainArgs); // FIXME: if args are passed and used, then should add args at the beginning of the main function 
+        }
+        self.var_types.push(vec![]);
+        self.fn_body(&_fn.block, &_fn.attrs);
+        self.var_types.pop();
+        let _ = writeln!(self.res);
+    }
+}
+
+//================
+//   fn_main_has_params()
+//================
+impl <'a> Rust<'a> {
+    fn fn_main_has_params(
+        &mut self,
+        params: &Vec<Param>,
+    ) -> bool {
+        // FIXME: currently, supporting only (args: [str]) -> ... , should be able to deconstruct using patterns
+        for param in params {
+            if is_main_param_pat_id(&param.pat) == is_main_param_list_str(&param._type) {
+                return true
+            } else {
+                return false
+            }
+        }
+        false
+    }
+
+}
+
+//================
+//   is_main_param_pat_id()
+//================
+fn is_main_param_pat_id(pat: &Pattern) -> bool {
+    match pat  {
+        Pattern::Id(_) => true,
+        _ => false
+    }
+}
+
+//================
+//   is_main_param_list_str()
+//================
+fn is_main_param_list_str(_type: &Option<Type>) -> bool {
+    match _type  {
+        Some(
+            Type::ListType(
+                ListType{ 
+                    els_type: els_type
+                }
+            )
+        ) => {
+            let els_type = *els_type.clone();
+            match els_type {
+                Type::PrimitiveType(
+                    PrimitiveType{
+                        id: Token { 
+                            value: TokenValue::Id(v), 
+                            .. 
+                        }
+                    }
+                ) if v == "str "=> true,
+                _ => false
+            }    
+        },
+        _ => false
+    }
+}
+
+
+//================
+//   _fn()
+//================
+impl <'a> Rust<'a> {  
+    fn _fn(
+        &mut self,
+        _fn: &Fn,  
+    ) {
+        
+        let name = if let Some(name) = &_fn.name {
+            match &name.value {
+                TokenValue::Id(id) => id.to_owned(),
+                _ => panic!()
+            }
+        } else {
+            String::from("")
+        }; 
+
+        let generics = self.generic_params(&_fn.generics);
+        self.rs_attrs(_fn.attrs.as_deref().unwrap_or(&[]));
+        self.mark(_fn.name.as_ref());
+        let _ = write!(self.res, "{}{}fn {}{}",self.indent , rs_vis(&_fn.vis), name, generics);
+        self.var_types.push(vec![]);
+        self.fn_params(&_fn.params, _fn.is_method, _fn.receiver);
+        self.fn_ret_type(&_fn.ret_type);
+        self.fn_body(&fn_block(_fn), &_fn.attrs);
+        self.var_types.pop();
+        let _ = writeln!(self.res);
+    }
+}
+
+//================
+//   rs_vis()
+//================
+// internal items are visible to the whole crate, private ones only to their module, as in seen
+fn rs_vis(vis: &Visibility) -> &'static str {
+    match vis {
+        Visibility::Public => "pub ",
+        Visibility::Internal => "pub(crate) ",
+        Visibility::Private => ""
+    }
+}
+
+//================
+//   fn_block()
+//================
+// the `where` bindings of a function are declared at the top of its body, in the order they are written
+fn fn_block(_fn: &Fn) -> Vec<BlockElement> {
+    _fn.where_decls
+        .iter()
+        .map(|decl| BlockElement::Decl(decl.clone()))
+        .chain(_fn.block.iter().cloned())
+        .collect()
+}
+
+
+//================
+//   generic_params()
+//================
+// `<T: Display + Clone, U>`, empty when there are no type parameters
+impl <'a> Rust<'a> {
+    fn generic_params(
+        &mut self,
+        generics: &Vec<GenericParam>,
+    ) -> String {
+        if generics.is_empty() { return String::new() }
+
+        let mut res = String::from("<");
+        for (i, param) in generics.iter().enumerate() {
+            let _ = write!(res, "{}", param.name);
+            for (j, bound) in param.bounds.iter().enumerate() {
+                let sep = if j == 0 { ": " } else { " + " };
+                let _ = write!(res, "{}{}", sep, self.rs_type(bound));
+            }
+            if i < generics.len() - 1 {
+                let _ = write!(res, ", ");
+            }
+        }
+        let _ = write!(res, ">");
+        res
+    }
+}
+
+//================
+//   generic_args()
+//================
+// the type parameters of a struct as arguments: `<T, U>`
+fn generic_args(generics: &Vec<GenericParam>) -> String {
+    if generics.is_empty() { return String::new() }
+    let args: Vec<String> = generics.iter().map(|param| param.name.to_string()).collect();
+    format!("<{}>", args.join(", "))
+}
+
+//================
+//   fn_params()
+//================
+impl <'a> Rust<'a> {
+    fn fn_params(
+        &mut self,
+        params: &Vec<Param>,
+        is_method: bool,
+        receiver: Option<Receiver>
+    ) {
+        let _ = write!(self.res, "(");  
+        if is_method {
+            // a method the mutability pass has not seen keeps the old `&mut self`
+            let receiver = match receiver {
+                Some(Receiver::Ref) => "&self",
+                Some(Receiver::MutRef) | None => "&mut self",
+                Some(Receiver::Owned) => "self",
+                Some(Receiver::MutOwned) => "mut self"
+            };
+            let _ = write!(self.res, "{}", receiver);
+            if !params.is_empty() {
+                let _ = write!(self.res, ", ");
+            }
+        }   
+        for (i, param) in params.iter().enumerate() {
+            match &param._type {
+                None => todo!("type inference"),
+                Some(_type) => {
+                    match &param.pat {
+                        Pattern::Id(pat) => {
+                            self.declare(&pat.id, _type);
+                            if param.is_mut {
+                                let _ = write!(self.res, "mut ");
+                            }
+                            let _ = write!(self.res, "{}", pat.id);
+                        },
+                        _ => todo!("only id pattern is implemented")
+                        
+                    }
+                    // a `str` param is borrowed, the ownership pass gives it an owned copy where it is given away
+                    let _type = if self.is_str(_type) { "&str".to_string() } else { self.impl_type(&_type) };
+                    let _ = write!(self.res, ": {}", _type);
+                    if i < params.len() - 1 {
+                        let _ = write!(self.res, ", ");
+                    }
+                }
+            }
+        }
+        let _ = write!(self.res, ") ");  
+    }
+}
+
+//================
+//   fn_ret_type()
+//================
+impl <'a> Rust<'a> {
+    fn fn_ret_type(
+        &mut self,
+        ret_type: &Option<Type>,
+    ) {
+        match ret_type {
+            // None => panic!("bug, should not pass the type checker"),
+            None => (),
+            Some(Type::UnitType) => (),
+            Some(_type) => {
+                let _type = self.impl_type(&_type);
+                let _ = write!(self.res, " -> {} ", _type);
+            }
+            
+        }
+    }
+}
+
+//================
+//   fn_body()
+//================
+// FIXME: no need to match BlockElement::Expr variants here, just extract the Expr and send it to self.expr(..)
+impl <'a> Rust<'a> {
+    fn fn_body(
+        &mut self,
+        els: &Vec<BlockElement>,
+        attrs: &Option<Vec<Attr>>
+    ) {
+        let _ = writeln!(self.res, "{{");    
+        self.indent.inc();
+        for (i, el) in els.iter().enumerate() {
+            self.mark(source_map::el_token(el));
+            let _ = write!(self.res, "{}", self.indent);    
+            match el {
+                BlockElement::Expr(Expr::BinOp(bin_op)) => {
+
+
+                    if let Some((name, args)) = &self.maybe_fn_call(&bin_op, attrs) {   
+                        self.temp_std(name, args); 
+                    } else if self.is_imported_module(&bin_op.l_opr) {   // FIXME this is a workaround , for now only importing modules in same dir are supported, `use` can be much more complex , handle all scenarios
+                        self.fix_import_path(&bin_op);
+                    }else {
+                        self.bin_op(&bin_op);
+                    }
+                    let _ = writeln!(self.res, ";");
+                },
+                BlockElement::Expr(Expr::Match(_match)) => self._match(&_match) ,
+                BlockElement::Expr(Expr::For(_for)) => self._for(&_for) ,
+                BlockElement::Expr(Expr::While(_while)) => self._while(&_while) ,
+                BlockElement::Expr(Expr::Loop(_loop)) => self._loop(&_loop) ,
+                BlockElement::Expr(Expr::If(_if)) => self._if(&_if) ,
+                BlockElement::Expr(Expr::Block(block)) => self.block(&block) ,
+                BlockElement::Expr(Expr::LetIn(let_in)) => {
+                    self.let_in(&let_in);
+                    let _ = write!(self.res, ";");
+                },
+                BlockElement::Decl(decl) => self.decl(&decl) ,
+
+                // FIXME temporary hardcoded variants
+                BlockElement::Expr(Expr::Ok(expr)) => {
+                    let _ = write!(self.res, "Ok(");
+                    self.expr(&expr);
+                    let _ = write!(self.res, ")");
+                },
+                BlockElement::Expr(Expr::Err(expr)) => {
+                    let _ = write!(self.res, "Err(");
+                    self.expr(&expr);
+                    let _ = write!(self.res, ")");
+                },
+                BlockElement::Expr(Expr::Some(expr)) => {
+                    let _ = write!(self.res, "Some(");
+                    self.expr(&expr);
+                    let _ = write!(self.res, ")");
+                },
+                BlockElement::Expr(Expr::None) => {
+                    let _ = write!(self.res, "None");
+                },
+                BlockElement::Expr(Expr::Int(num)) => {
+                    let _ = write!(self.res, "{}", num);
+                },
+                BlockElement::Expr(Expr::Float(num)) => {
+                    let _ = write!(self.res, "{}", num);
+                },                
+                BlockElement::Expr(Expr::Ref(name)) => {
+                    let _ = write!(self.res, "{}", name);
+                },             
+                BlockElement::Ret(ret) => {
+                    let is_tail = i == els.len() - 1 && !is_assign_ret(ret);
+                    self.ret(&ret, is_tail);
+                    if !is_tail {
+                        let _ = write!(self.res, ";");
+                    }
+                },
+                x => {
+                    todo!("{:?}", x) // TODO
+                }
+            }
+            let _ = writeln!(self.res, "");    
+            
+        }
+                
+        self.indent.dec();
+        let _ = writeln!(self.res, "{}}}", self.indent);
+    }
+}
+
+
+//================
+//   rs_attrs()
+//================
+// the rust attributes the handlers of the registry write above an item
+impl <'a> Rust<'a> {
+    fn rs_attrs(
+        &mut self,
+        attrs: &[Attr]
+    ) {
+        let registry = self.attrs;
+        for attr in attrs {
+            if let Some(handler) = registry.handler(attr, self.lexicon) {
+                for rs_attr in handler.rs_attrs(attr, self.lexicon) {
+                    let _ = writeln!(self.res, "{}{}", self.indent, rs_attr);
+                }
+            }
+        }
+    }
+}
+
+//================
+//   attr_fn()
+//================
+// lets the handlers of the attributes on a function write its whole item, e.g. `@web_server`
+impl <'a> Rust<'a> {
+    fn attr_fn(
+        &mut self,
+        _fn: &Fn
+    ) -> bool {
+        let registry = self.attrs;
+        for attr in _fn.attrs.as_deref().unwrap_or(&[]) {
+            if let Some(handler) = registry.handler(attr, self.lexicon) {
+                if handler.rs_fn(attr, _fn, self) {
+                    return true
+                }
+            }
+        }
+        false
+    }
+}
+
+//================
+//   maybe_fn_call()
+//================
+impl <'a> Rust<'a> {
+    fn maybe_fn_call(
+        &mut self,
+        op: &BinOp,
+        attrs: &Option<Vec<Attr>>
+    ) -> Option<(String, Tuple)> {                                  
+
+        match self.callee_name(&op.l_opr) {
+            Some(name) => {
+                match &*op.r_opr {
+                    Expr::Tuple(args) => {
+                        
+                        return Some((name, args.clone())) 
+                    },
+                    x => {
+                        None
+                    }
+                }
+            },
+            _ => None
+        }
+    }
+}
+
+
+//================
+//   maybe_struct_init()
+//================
+impl <'a> Rust<'a> {
+    fn maybe_struct_init(
+        &mut self,
+        op: &BinOp,
+        attrs: &Option<Vec<Attr>>
+    ) -> Option<(String, StructLiteral)> {                                  
+
+        match self.callee_name(&op.l_opr) {
+            Some(name) => {
+                match &*op.r_opr {
+                    Expr::StructLiteral(fields) => {
+                        
+                        return Some((name, fields.clone())) 
+                    },
+                    x => {
+                        None
+                    }
+                }
+            },
+            _ => None
+        }
+    }
+}
+
+
+//================
+//   callee_name()
+//================
+// the name of a called function / an initialized struct, with its explicit type arguments if any: `parse::<i32>`
+impl <'a> Rust<'a> {
+    fn callee_name(
+        &mut self,
+        expr: &Expr
+    ) -> Option<String> {
+        match expr {
+            Expr::Ref(t) => Some(t.to_string()),
+            Expr::GenericRef(generic_ref) => Some(self.generic_ref(generic_ref)),
+            _ => None
+        }
+    }
+}
+
+//================
+//   generic_ref()
+//================
+impl <'a> Rust<'a> {
+    fn generic_ref(
+        &mut self,
+        generic_ref: &GenericRef
+    ) -> String {
+        let args: Vec<String> = generic_ref.args.iter().map(|arg| self.rs_type(arg)).collect();
+        format!("{}::<{}>", generic_ref.id, args.join(", "))
+    }
+}
+
+
+//================
+//  struct_init()
+//================  
+// TODO: support multiple formats / indentation according to the number of fields and context
+//  e.g:
+//          let x = Struct{ a: int}
+//          let x = Struct {
+//                  a: 1,
+//                  b: 2
+//          }
+// ...etc
+impl <'a> Rust<'a> {     
+    pub fn struct_init(
+        &mut self, 
+        name: &String, 
+        fields: &StructLiteral
+    ) {
+        // taken before the values so a literal nested in them is looked up in this module
+        let _mod = self.in_mod.take().unwrap_or(self.mod_name.clone());
+        let _ = writeln!(self.res, "{} {{", name);
+        self.indent.inc();
+        for (field, expr) in fields.items.iter() {
+            let _ = write!(self.res, "{}", self.indent);
+            match expr {
+                Some(expr) => {
+                    let _ = write!(self.res, "{}: ", field);
+                    self.boxed_lambda(&expr, true);
+                },
+                None => {
+                    let _ = write!(self.res, "{}", field);
+                }
+            }
+            let _ = writeln!(self.res, ",");
+        };
+
+        match &fields.base {
+            Some(base) => {
+                let _ = write!(self.res, "{}..", self.indent);
+                self.expr(base);
+                let _ = writeln!(self.res, "");
+            },
+            None => self.omitted_fields(&_mod, name, fields)
+        }
+
+        self.indent.dec();
+        let _ = write!(self.res, "{}}}", self.indent);  
+    
+    }
+}
+
+
+//================
+//  omitted_fields()
+//================  
+// the fields a literal leaves out take their default, or `None` if they are optional, the rest are
+//  left for rustc to report as missing
+impl <'a> Rust<'a> {     
+    fn omitted_fields(
+        &mut self, 
+        _mod: &String,
+        name: &String, 
+        fields: &StructLiteral
+    ) {
+        // drop the type arguments of `Stack::<int>`
+        let name = name.split("::").next().unwrap_or(name);
+        let declared = self.mod_structs
+            .get(_mod)
+            .and_then(|structs| structs.get(name))
+            .cloned()
+            .unwrap_or(vec![]);
+        for field in declared.iter() {
+            if fields.items.iter().any(|(t, _)| t.value == field.name.value) { continue }
+            if field.default.is_none() && !field.is_optional() { continue }
+            let _ = write!(self.res, "{}{}: ", self.indent, field.name);
+            self.field_default(field);
+            let _ = writeln!(self.res, ",");
+        }
+    }
+}
+
+//================
+//  field_default()
+//================  
+impl <'a> Rust<'a> {     
+    fn field_default(
+        &mut self, 
+        field: &StructField
+    ) {
+        match &field.default {
+            Some(expr) => self.boxed_lambda(expr, true),
+            None => {
+                let _ = write!(self.res, "None");
+            }
+        }
+    }
+}
+
+//================
+//   _struct()
+//================
+impl <'a> Rust<'a> {
+    fn _struct(
+        &mut self,
+        _struct: &Struct,
+    ) {
+        let _  = writeln!(self.res, "#[derive(Debug, Clone)]"); // FIXME : add Debug/Clone by default for DEV , improve later by adding them as needed
+        self.rs_attrs(&_struct.attrs);
+        let generics = self.generic_params(&_struct.generics);
+        self.mark(Some(&_struct.name));
+        let _  = writeln!(self.res, "{}struct {}{} {{" , rs_vis(&_struct.vis), _struct.name, generics); 
+        if let Some(fields) = &_struct.fields {
+            self.indent.inc();
+            for field in fields.iter() {
+                let _ = write!(self.res, "{}", self.indent);
+                let _ = write!(self.res, "{}{}: ", rs_vis(&field.vis), field.name );
+                self._type(&field._type);
+                let _ = writeln!(self.res, ",");
+
+            }
+            self.indent.dec();
+            let _ = writeln!(self.res, "{}}}\n", self.indent);
+        } else {
+            let _ = writeln!(self.res, "}}\n");
+        }
+
+        // FIXME: for quicker DEV: implement Display for every struct by default to print the Debug impl
+        //          improve later
+        let _ = writeln!( 
+            self.res, 
+            "{}impl{} std::fmt::Display for {}{} where Self: std::fmt::Debug {{", 
+            self.indent, 
+            generics, 
+            _struct.name, 
+            generic_args(&_struct.generics)
+        );
+        self.indent.inc();
+        let _ = writeln!( self.res, "{}fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{", self.indent);
+        self.indent.inc();
+        let _ = writeln!( self.res, "{}write!(f, \"{{:#?}}\", self)", self.indent);
+        self.indent.dec();
+        let _ = writeln!(self.res, "{}}}", self.indent);
+        self.indent.dec();
+        let _ = writeln!(self.res, "{}}}\n", self.indent);
+
+        if let Some(fields) = &_struct.fields {
+            if fields.iter().any(|f| f.default.is_some()) 
+                && fields.iter().all(|f| f.default.is_some() || f.is_optional()) {
+                self.default_impl(_struct, fields);
+            }
+        }
+    }
+}
+
+//================
+//   default_impl()
+//================
+// written when every field has a default or is optional
+impl <'a> Rust<'a> {
+    fn default_impl(
+        &mut self,
+        _struct: &Struct,
+        fields: &StructFields
+    ) {
+        let generics = self.generic_params(&_struct.generics);
+        let _ = writeln!( 
+            self.res, 
+            "{}impl{} Default for {}{} {{", 
+            self.indent, 
+            generics, 
+            _struct.name, 
+            generic_args(&_struct.generics)
+        );
+        self.indent.inc();
+        let _ = writeln!( self.res, "{}fn default() -> Self {{", self.indent);
+        self.indent.inc();
+        let _ = writeln!( self.res, "{}Self {{", self.indent);
+        self.indent.inc();
+        for field in fields.iter() {
+            let _ = write!(self.res, "{}{}: ", self.indent, field.name);
+            self.field_default(field);
+            let _ = writeln!(self.res, ",");
+        }
+        self.indent.dec();
+        let _ = writeln!(self.res, "{}}}", self.indent);
+        self.indent.dec();
+        let _ = writeln!(self.res, "{}}}", self.indent);
+        self.indent.dec();
+        let _ = writeln!(self.res, "{}}}\n", self.indent);
+    }
+}
+
+//================
+//   struct_impl()
+//================
+// FIXME: code is assuming a single method/func impl blocks, later on when parser is updated for multi support, change the code here as well.
+impl <'a> Rust<'a> {
+    fn struct_impl(
+        &mut self,
+        struct_impl: &StructImpl,
+    ) {
+        let struct_generics = self.struct_generics
+            .get(&struct_impl.name.to_string())
+            .cloned()
+            .unwrap_or(vec![]);
+        let generics = self.generic_params(&struct_generics);
+        let _ = writeln!( 
+            self.res, 
+            "{}impl{} {}{} {{", 
+            self.indent, 
+            generics, 
+            struct_impl.name, 
+            generic_args(&struct_generics)
+        );
+        self.indent.inc();
+
+        for _fn in &struct_impl.fns {
+            self._fn(_fn);
+        }
+
+        self.indent.dec();
+        let _ = writeln!(self.res, "{}}}\n\n", self.indent);
+
+    }
+}
+
+//================
+//   _trait()
+//================
+impl <'a> Rust<'a> {
+    fn _trait(
+        &mut self,
+        _trait: &Trait,
+    ) {
+        todo!();    // TODO
+    }
+}
+
+//================
+//   _enum()
+//================
+impl <'a> Rust<'a> {
+    fn _enum(
+        &mut self,
+        _enum: &Enum,
+    ) {
+        let _  = writeln!(self.res, "#[derive(Debug, Clone)]"); // FIXME : same as structs, Debug/Clone by default for DEV
+        let _  = writeln!(self.res, "{}enum {} {{" , rs_vis(&_enum.vis), _enum.name); 
+        self.indent.inc();
+        for variant in _enum.variants.iter() {
+            let _ = write!(self.res, "{}{}", self.indent, variant.name);
+            if let Some(value) = &variant.value {
+                let _ = write!(self.res, " = ");
+                self.expr(value);
+            }
+            let _ = writeln!(self.res, ",");
+        }
+        self.indent.dec();
+        let _ = writeln!(self.res, "{}}}\n", self.indent);
+    }
+}
+
+//================
+//   enum_impl()
+//================
+impl <'a> Rust<'a> {
+    fn enum_impl(
+        &mut self,
+        enum_impl: &EnumImpl,
+    ) {
+        let _ = writeln!(self.res, "{}impl {} {{", self.indent, enum_impl.name);
+        self.indent.inc();
+
+        for _fn in &enum_impl.fns {
+            self._fn(_fn);
+        }
+
+        self.indent.dec();
+        let _ = writeln!(self.res, "{}}}\n\n", self.indent);
+    }
+}
+
+//================
+//   type_annotation()
+//================
+impl <'a> Rust<'a> {
+    fn type_annotation(
+        &mut self,
+        _type: &Type
+    ) {
+        let _ = write!(self.res, ": ");
+        self._type(_type);
+    }
+}
+
+//================
+//   rs_type()
+//================
+impl <'a> Rust<'a> {
+    fn rs_type(
+        &mut self,
+        _type: &Type
+    ) -> String {
+        let _type = match _type {
+            Type::OptionType(opt_type) => {
+                format!("Option<{}>", self.rs_type(&opt_type.some_type))
+            },
+            Type::ResultType(res_type) => {
+                format!("Result<{}, {}>",
+                 self.rs_type(&res_type.ok_type),
+                 self.rs_type(&res_type.err_type),
+                )
+
+            },            
+            Type::PrimitiveType(PrimitiveType{id}) => {
+                let _type = match self.lexicon.concept("type", &NEUTRAL_LANG, &id.value.to_string()) {
+                    Some("type.bool") => "bool",
+                    Some("type.char") => "char",
+                    Some("type.int") => "i32",
+                    Some("type.float") => "f32",
+                    Some("type.str") => "String",
+                    Some("type.byte") => "u8",
+                    Some(concept) => return self.lexicon.word(&Lang::En, concept),    // sized numbers are spelled as in rust
+                    _ => panic!("unkown primitive type {}", id.value)
+                };
+                _type.to_string()
+            },
+            Type::ListType(ListType{els_type}) => {
+                format!("Vec<{}>", self.rs_type(&els_type))
+            },
+            Type::MapType(MapType{key_type, value_type, sorted}) => {
+                format!("{}<{}, {}>", rs_map(*sorted), self.rs_type(&key_type), self.rs_type(&value_type))
+            },
+            Type::TupleType(TupleType{types}) => {
+                let mut res = String::from("(");
+                for (i,el_type) in types.iter().enumerate() {
+                    let _ = write!(res, "{}", self.rs_type(&el_type));
+                    if i < types.len() - 1 {
+                        let _ = write!(res, ", ");
+                    }
+                }
+
+                let _ = write!(res, ")");
+                res
+            },            
+            Type::FnType(fn_type) => {
+                format!("Box<dyn {}>", self.rs_fn_type(fn_type))
+            },
+            Type::NamedType(NamedType{name, args}) => {
+                let name = name.to_string();
+                let mut res = match self.lexicon.concept("trait", &NEUTRAL_LANG, &name) {
+                    Some(concept) => self.lexicon.word(&Lang::En, concept),
+                    None => name
+                };
+                if !args.is_empty() {
+                    let args: Vec<String> = args.iter().map(|arg| self.rs_type(arg)).collect();
+                    let _ = write!(res, "<{}>", args.join(", "));
+                }
+                res
+            },
+            _ => todo!()
+        };
+        _type
+    }    
+}
+
+
+//================
+//   rs_fn_type()
+//================
+// `Fn(i32) -> i32`
+// FIXME: lambdas that mutate their captures need FnMut, always Fn until the mutability of the captures is known
+impl <'a> Rust<'a> {
+    fn rs_fn_type(
+        &mut self,
+        fn_type: &FnType
+    ) -> String {
+        let params: Vec<String> = fn_type.params_types.iter().map(|param| self.rs_type(param)).collect();
+        let mut res = format!("Fn({})", params.join(", "));
+        match &fn_type.ret_type {
+            Some(ret_type) if !matches!(**ret_type, Type::UnitType) => {
+                let _ = write!(res, " -> {}", self.rs_type(&ret_type));
+            },
+            _ => ()
+        }
+        res
+    }
+}
+
+//================
+//   is_str()
+//================
+impl <'a> Rust<'a> {
+    fn is_str(
+        &self,
+        _type: &Type
+    ) -> bool {
+        match _type {
+            Type::PrimitiveType(PrimitiveType{id}) => self.lexicon.is("type.str", &NEUTRAL_LANG, &id.value.to_string()),
+            _ => false
+        }
+    }
+}
+
+//================
+//   impl_type()
+//================
+// params and return types take any function or lambda without boxing it
+impl <'a> Rust<'a> {
+    fn impl_type(
+        &mut self,
+        _type: &Type
+    ) -> String {
+        match _type {
+            Type::FnType(fn_type) => format!("impl {}", self.rs_fn_type(fn_type)),
+            _ => self.rs_type(_type)
+        }
+    }
+}
+
+//================
+//   _type()
+//================
+impl <'a> Rust<'a> {
+    fn _type(
+        &mut self,
+        _type: &Type
+    ) {
+        let rs_type = self.rs_type(&_type);
+        let _ = write!(self.res, "{}", rs_type); 
+    }    
+}
+
+//================
+//  expr()
+//================    
+impl <'a> Rust<'a> {         
+    fn expr(
+        &mut self,
+        _expr: &Expr
+    ) {
+
+        match _expr {
+            Expr::Bool(v) => {
+                let v = self.lexicon.is("kw.true", &NEUTRAL_LANG, &v.to_string());
+                let _ = write!(self.res, "{}", v);
+            },
+            Expr::Char(v)
+            | Expr::Str(v) => {
+                let _ = write!(self.res, "\"{}\"", v);
+                let _ = write!(self.res, ".to_string()");  // FIXME, for now treat all str as String
+            },
+            Expr::Int(v)
+            | Expr::Float(v) => {
+                let _ = write!(self.res, "{}", v);
+            },
+            Expr::Ref(id) => {
+                let _ = write!(self.res, "{}", id.to_string());
+            }
+            Expr::GenericRef(generic_ref) => {
+                let generic_ref = self.generic_ref(generic_ref);
+                let _ = write!(self.res, "{}", generic_ref);
+            }
+            Expr::List(l) => self.list(l),
+            Expr::Map(m) => self.map(m, false),
+            Expr::Tuple(tuple) => self.tuple(tuple),
+            Expr::PreUniOp(uni_op) => self.pre_uni_op(uni_op),
+            Expr::PostUniOp(uni_op) => self.post_uni_op(uni_op),
+            Expr::Cast(cast) => self.cast(cast),
+            Expr::Range(range) => self.range(range, true),
+            Expr::LetIn(let_in) => self.let_in(let_in),
+            Expr::Fn(_fn) => self.lambda(_fn, false),
+            Expr::BinOp(bin_op) => self.bin_op(bin_op),
+
+            Expr::Match(_match) => self._match(&_match) ,
+            Expr::For(_for) => self._for(&_for) ,
+            Expr::While(_while) => self._while(&_while) ,
+            Expr::Loop(_loop) => self._loop(&_loop) ,
+            Expr::Break(_break) => self._break(&_break) ,
+            Expr::Continue(_continue) => self._continue(&_continue) ,
+            Expr::If(_if) => self._if(&_if) ,
+            // FIXME temporary hardcoded variants
+            Expr::Ok(expr) => {
+                let _ = write!(self.res, "Ok(");
+                self.expr(&expr);
+                let _ = write!(self.res, ")");
+            },
+            Expr::Err(expr) => {
+                let _ = write!(self.res, "Err(");
+                self.expr(&expr);
+                let _ = write!(self.res, ")");
+            },
+            Expr::Some(expr) => {
+                let _ = write!(self.res, "Some(");
+                self.expr(&expr);
+                let _ = write!(self.res, ")");
+            },
+            Expr::None => {
+                let _ = write!(self.res, "None");
+            },
+            Expr::StrSlice(v) => {
+                let _ = write!(self.res, "\"{}\"", v);
+            },
+            Expr::Borrow(expr) => match &**expr {
+                Expr::BinOp(bin_op) if !matches!(bin_op.op.value, TokenValue::Dot | TokenValue::OpenBracket | TokenValue::OpenParen) => {
+                    let _ = write!(self.res, "&(");
+                    self.expr(&expr);
+                    let _ = write!(self.res, ")");
+                },
+                _ => {
+                    let _ = write!(self.res, "&");
+                    self.expr(&expr);
+                }
+            },
+            Expr::Clone(expr) => {
+                self.expr(&expr);
+                let _ = write!(self.res, ".clone()");
+            },
+            Expr::Owned(expr) => {
+                self.expr(&expr);
+                let _ = write!(self.res, ".to_string()");
+            },
+            Expr::Block(block) => self.block(&block),
+            Expr::Unit => {
+                let _ = write!(self.res, "()");
+            },
+
+            x => {
+                todo!("expr: {:?}", x)
+            }            
+        }
+    }    
+}
+
+//================
+// rs_bin_op()
+//================  
+impl <'a> Rust<'a> {     
+    pub fn rs_bin_op(
+        &mut self, 
+        op: &Token
+    ) {
+        let rs_bin_op = match &op.value {
+            TokenValue::BitwiseAnd => "&".to_string(),
+            TokenValue::BitwiseOr => "|".to_string(),
+            TokenValue::OpenParen  | TokenValue::OpenBracket=> "".to_string(),
+            x => x.to_string()
+        };
+        let _ = write!(self.res, " {} ", rs_bin_op);
+    }
+}
+
+//================
+//  pre_uni_op()
+//================  
+impl <'a> Rust<'a> {     
+    pub fn pre_uni_op(
+        &mut self, 
+        uni_op: &UniOp
+    ) {
+        match uni_op {
+            UniOp{ opr, op } => {
+                if matches!(op.value , TokenValue::Minus) {
+                    let _ = write!(self.res, "-(");
+                    self.expr(opr);
+                    let _ = write!(self.res, ")");  // FIXME use parens only if necessary, such as having a binary expression
+                }
+            },
+            _ => todo!()
+        }
+    }
+}
+
+//================
+//  post_uni_op()
+//================  
+impl <'a> Rust<'a> {     
+    pub fn post_uni_op(
+        &mut self, 
+        uni_op: &UniOp
+    ) {
+        match uni_op {
+            UniOp{ opr, op } => {
+                match &op.value  {
+                    TokenValue::Question => {
+                        let _ = write!(self.res, "(");
+                        self.expr(opr);
+                        let _ = write!(self.res, ")?");  // FIXME use parens only if necessary, such as having a binary expression    
+                    },
+                    TokenValue::Exclamation => {
+                        let _ = write!(self.res, "(");
+                        self.expr(opr);
+                        let _ = write!(self.res, ").unwrap()");  // FIXME use parens only if necessary, such as having a binary expression                            
+                    }
+                    _ => todo!()
+                }
+            },
+
+            _ => todo!()
+        }
+    }
+}
+
+//================
+//  cast()
+//================  
+impl <'a> Rust<'a> {     
+    pub fn cast(
+        &mut self, 
+        cast: &Cast
+    ) {
+        let _ = write!(self.res, "(");
+        self.expr(&cast.expr);
+        let _ = write!(self.res, " as ");
+        self._type(&cast._type);
+        let _ = write!(self.res, ")");  // FIXME use parens only if necessary
+    }
+}
+
+//================
+//  range()
+//================  
+impl <'a> Rust<'a> {     
+    pub fn range(
+        &mut self, 
+        range: &Range,
+        parens: bool
+    ) {
+        if parens {
+            let _ = write!(self.res, "(");
+        }
+        if let Some(start) = &range.start {
+            self.expr(start);
+        }
+        let op = if range.inclusive { "..=" } else { ".." };
+        let _ = write!(self.res, "{}", op);
+        if let Some(end) = &range.end {
+            self.expr(end);
+        }
+        if parens {
+            let _ = write!(self.res, ")");
+        }
+    }
+}
+
+//================
+//  bin_op()
+//================  
+impl <'a> Rust<'a> {     
+    pub fn bin_op(
+        &mut self, 
+        bin_op: &BinOp
+    ) {
+        
+        if self.is_imported_module(&bin_op.l_opr) {   // FIXME this is a workaround , for now only importing modules in same dir are supported, `use` can be much more complex , handle all scenarios
+            self.fix_import_path(&bin_op);
+        } else if matches!(bin_op.op.value, TokenValue::OpenBracket) {
+            self.index(&bin_op);
+        } else if bin_op.is_access() {
+            self.access(&bin_op);
+        } else if let (Expr::Fn(_fn), Expr::Tuple(args)) = (&*bin_op.l_opr, &*bin_op.r_opr) {
+            // a lambda called in place, e.g: `x |> (a) -> a * 2`
+            let _ = write!(self.res, "(");
+            self.lambda(_fn, false);
+            let _ = write!(self.res, ")");
+            self.tuple(args);
+        } else if let Some((name, args)) = &self.maybe_fn_call(&bin_op, &None) {
+            self.temp_std(name, args); 
+        } else if let Some((name, fields)) = &self.maybe_struct_init(&bin_op, &None) {
+            self.struct_init(name, fields); 
+        } else if let Some(index) = self.map_index(&bin_op.l_opr).filter(|_| is_assign(&bin_op.op.value)) {
+            self.map_assign(index, bin_op);
+        } else {    
+            self.expr(&bin_op.l_opr);
+            self.rs_bin_op(&bin_op.op);
+            self.expr(&bin_op.r_opr);
+        }
+    }
+}
+
+//================
+//  access()
+//================  
+// the parser nests chained accesses to the right: `l.map(f).len()` is `l . (map(f) . len())`,
+//  the segments are written one after the other, an iterator method turns the receiver into an iterator once,
+//  and the chain is collected back into a list before the first segment that is not an iterator method, or at its end
+impl <'a> Rust<'a> {     
+    fn access(
+        &mut self,
+        bin_op: &BinOp
+    ) {
+        self.expr(&bin_op.l_opr);
+        if self.access_segments(&bin_op.r_opr, false) {
+            self.collect();
+        }
+    }
+}
+
+//================
+//  access_segments()
+//================  
+// returns whether the chain written so far is an iterator
+impl <'a> Rust<'a> {     
+    fn access_segments(
+        &mut self,
+        expr: &Expr,
+        is_iter: bool
+    ) -> bool {
+        match expr {
+            Expr::BinOp(bin_op) if bin_op.is_access() => {
+                let is_iter = self.access_segment(&bin_op.l_opr, is_iter);
+                self.access_segments(&bin_op.r_opr, is_iter)
+            },
+            _ => self.access_segment(expr, is_iter)
+        }
+    }
+}
+
+//================
+//  access_segment()
+//================  
+impl <'a> Rust<'a> {     
+    fn access_segment(
+        &mut self,
+        expr: &Expr,
+        is_iter: bool
+    ) -> bool {
+        if let Expr::BinOp(call) = expr {
+            if let Some((name, args)) = self.maybe_fn_call(call, &None) {
+                if let Some(builtin @ (Builtin::Map | Builtin::Filter)) = Builtin::from_name(&name) {
+                    if !is_iter {
+                        let _ = write!(self.res, ".iter().cloned()");
+                    }
+                    self.iter_adapter(builtin, &args);
+                    return true
+                }
+            }
+        }
+        if is_iter {
+            self.collect();
+        }
+        let _ = write!(self.res, ".");
+        self.expr(expr);
+        false
+    }
+}
+
+//================
+//  iter_adapter()
+//================  
+// `l.map(f)` / `l.filter(f)` on lists, the lambda receives the elements by value
+// FIXME: assumes the receiver is a list, until the types of the receivers are known
+impl <'a> Rust<'a> {     
+    fn iter_adapter(
+        &mut self,
+        builtin: Builtin,
+        args: &Tuple
+    ) {
+        match builtin {
+            Builtin::Map => {
+                let _ = write!(self.res, ".map(");
+                self.expr(&args.items[0]);          // FIXME hardcoded / no error handling
+                let _ = write!(self.res, ")");
+            },
+            _ => {
+                let _ = write!(self.res, ".filter(|__el| (");
+                self.expr(&args.items[0]);
+                let _ = write!(self.res, ")(__el.clone()))");
+            }
+        }
+    }
+}
+
+//================
+//  collect()
+//================  
+impl <'a> Rust<'a> {     
+    fn collect(
+        &mut self
+    ) {
+        let _ = write!(self.res, ".collect::<Vec<_>>()");
+    }
+}
+
+//================
+//  lambda()
+//================  
+// a closure borrows what it captures, unless it outlives the scope it is created in ( returned or stored ), then it is a `move` closure
+impl <'a> Rust<'a> {     
+    fn lambda(
+        &mut self,
+        _fn: &Fn,
+        is_move: bool
+    ) {
+        if is_move {
+            let _ = write!(self.res, "move ");
+        }
+        let _ = write!(self.res, "|");
+        for (i, param) in _fn.params.iter().enumerate() {
+            self.pattern(&param.pat);
+            if let Some(_type) = &param._type {
+                self.type_annotation(&_type);
+            }
+            if i < _fn.params.len() - 1 {
+                let _ = write!(self.res, ", ");
+            }
+        }
+        let _ = write!(self.res, "| ");
+
+        match (&_fn.ret_type, _fn.block.as_slice()) {
+            (None, [BlockElement::Ret(Ret{token: None, expr: Some(expr)})]) => self.expr(&expr),   // one liner: `|x| x * 2`
+            _ => {
+                self.fn_ret_type(&_fn.ret_type);
+                self.fn_body(&_fn.block, &_fn.attrs);
+                let _ = write!(self.res, "{}", self.indent);
+            }
+        }
+    }
+}
+
+//================
+//  boxed_lambda()
+//================  
+// lambdas stored in struct fields and lists are boxed, their types are `Box<dyn Fn..>`
+impl <'a> Rust<'a> {     
+    fn boxed_lambda(
+        &mut self,
+        expr: &Expr,
+        is_move: bool
+    ) {
+        match expr {
+            Expr::Fn(_fn) => {
+                let _ = write!(self.res, "Box::new(");
+                self.lambda(_fn, is_move);
+                let _ = write!(self.res, ")");
+            },
+            _ => self.expr(expr)
+        }
+    }
+}
+
+//================
+//  ret()
+//================  
+// `ret` is written as `return` unless it is the last line of the function
+impl <'a> Rust<'a> {     
+    fn ret(
+        &mut self,
+        ret: &Ret,
+        is_fn_tail: bool
+    ) {
+        if ret.token.is_some() && !is_fn_tail {
+            let _ = write!(self.res, "return");
+            if ret.expr.is_some() {
+                let _ = write!(self.res, " ");
+            }
+        }
+        match ret.expr.as_deref() {
+            Some(Expr::Fn(_fn)) => self.lambda(_fn, true),
+            Some(expr) => self.expr(expr),
+            None => ()
+        }
+    }
+}
+
+//================
+//  let_in()
+//================  
+// `let x = e in body` is a block of its own: `{ let x = e; body }`
+impl <'a> Rust<'a> {     
+    fn let_in(
+        &mut self,
+        let_in: &LetIn
+    ) {
+        let _ = write!(self.res, "{{ ");
+        self.decl(&let_in.decl);
+        let _ = write!(self.res, " ");
+        self.expr(&let_in.expr);
+        let _ = write!(self.res, " }}");
+    }
+}
+
+//================
+//  index()
+//================  
+impl <'a> Rust<'a> {     
+    fn index(
+        &mut self,
+        bin_op: &BinOp
+    ) {
+        self.expr(&bin_op.l_opr);
+        let _ = write!(self.res, "[");
+        if let Expr::Range(range) = &*bin_op.r_opr {
+            // a slice is copied into a new list
+            self.range(range, false);
+            let _ = write!(self.res, "].to_vec()");
+            return
+        }
+        if self.is_map(&bin_op.l_opr) {
+            let _ = write!(self.res, "&");
+        }
+        self.expr(&bin_op.r_opr);
+        let _ = write!(self.res, "]");
+    }
+}
+
+//================
+//  map_index()
+//================  
+// `m[k]` when `m` is a map
+impl <'a> Rust<'a> {   
+    fn map_index<'b>(
+        &self,
+        expr: &'b Expr,
+    ) -> Option<&'b BinOp> { 
+        match expr {
+            Expr::BinOp(index) if matches!(index.op.value, TokenValue::OpenBracket) && self.is_map(&index.l_opr) => Some(index),
+            _ => None
+        }
+    }
+}
+
+//================
+//  map_assign()
+//================  
+// rust maps can not be assigned through an index, `m[k] = v` inserts the key and `m[k] += v` updates
+//  the value in place, panicking on a missing key as reading `m[k]` does
+impl <'a> Rust<'a> {   
+    fn map_assign(
+        &mut self,
+        index: &BinOp,
+        bin_op: &BinOp
+    ) { 
+        if matches!(bin_op.op.value, TokenValue::Equal) {
+            self.expr(&index.l_opr);
+            let _ = write!(self.res, ".insert(");
+            self.expr(&index.r_opr);
+            let _ = write!(self.res, ", ");
+            self.expr(&bin_op.r_opr);
+            let _ = write!(self.res, ")");
+        } else {
+            let _ = write!(self.res, "*");
+            self.expr(&index.l_opr);
+            let _ = write!(self.res, ".get_mut(&");
+            self.expr(&index.r_opr);
+            let _ = write!(self.res, ").unwrap()");
+            self.rs_bin_op(&bin_op.op);
+            self.expr(&bin_op.r_opr);
+        }
+    }
+}
+
+//================
+//  is_assign()
+//================  
+fn is_assign(op: &TokenValue) -> bool {
+    matches!(op,
+        TokenValue::Equal
+        | TokenValue::AddEqual
+        | TokenValue::SubEqual
+        | TokenValue::MulEqual
+        | TokenValue::DivEqual
+        | TokenValue::BitwiseAndEqual
+        | TokenValue::BitwiseOrEqual
+        | TokenValue::BitwiseXorEqual
+    )
+}
+
+//================
+//  is_assign_ret()
+//================  
+// a block that ends with an assignment ends with a statement, `m[k] = v` is `m.insert(k, v)` and its value is not `()`
+fn is_assign_ret(ret: &Ret) -> bool {
+    matches!(ret.expr.as_deref(), Some(Expr::BinOp(bin_op)) if is_assign(&bin_op.op.value))
+}
+
+//================
+//  is_map()
+//================  
+impl <'a> Rust<'a> {   
+    fn is_map(
+        &self,
+        expr: &Expr,
+    ) -> bool { 
+        matches!(self.expr_type(expr), Some(Type::MapType(_)))
+    }
+}
+
+//================
+//  expr_type()
+//================  
+// the type of an expression as far as the declarations tell, `None` when only the type checker can tell.
+//  the entries of a map literal are only typed when they are maps too, the others are left as `()`
+impl <'a> Rust<'a> {   
+    fn expr_type(
+        &self,
+        expr: &Expr,
+    ) -> Option<Type> { 
+        match expr {
+            Expr::Ref(id) => {
+                let name = id.value.to_string();
+                self.var_types
+                    .iter()
+                    .rev()
+                    .find_map(|scope| scope.iter().rev().find(|(var, _)| *var == name))
+                    .map(|(_, _type)| _type.clone())
+            },
+            Expr::Map(map) => {
+                let entry_type = |expr: Option<&Expr>| expr.and_then(|expr| self.expr_type(expr)).unwrap_or(Type::UnitType);
+                let first = map.items.first();
+                Some(Type::MapType(MapType{
+                    key_type: Box::new(entry_type(first.map(|(key, _)| key))),
+                    value_type: Box::new(entry_type(first.map(|(_, value)| value))),
+                    sorted: false
+                }))
+            },
+            Expr::BinOp(bin_op) => match (&bin_op.op.value, &*bin_op.l_opr) {
+                (TokenValue::OpenBracket, l_opr) => match self.expr_type(l_opr)? {
+                    Type::MapType(MapType{value_type, ..}) => Some(*value_type),
+                    Type::ListType(ListType{els_type}) => Some(*els_type),
+                    _ => None
+                },
+                (TokenValue::OpenParen, Expr::Ref(callee)) => self.fn_types.get(&callee.value.to_string()).cloned(),
+                _ => None
+            },
+            _ => None
+        }
+    }
+}
+
+//================
+//  declare()
+//================  
+impl <'a> Rust<'a> {   
+    fn declare(
+        &mut self,
+        name: &Token,
+        _type: &Type
+    ) { 
+        if let Some(scope) = self.var_types.last_mut() {
+            scope.push((name.value.to_string(), _type.clone()));
+        }
+    }
+}
+
+//================
+//  fix_import_path()
+//================  
+impl <'a> Rust<'a> {     
+    fn fix_import_path(
+        &mut self,
+        bin_op: &BinOp
+    ) {
+        self.expr(&bin_op.l_opr);
+        let _ = write!(self.res, "::");
+        if let (Expr::Ref(_mod), Expr::BinOp(BinOp{r_opr, ..})) = (&*bin_op.l_opr, &*bin_op.r_opr) {
+            if matches!(**r_opr, Expr::StructLiteral(_)) {
+                self.in_mod = self.import_mods.get(&_mod.value.to_string()).cloned();
+            }
+        }
+        self.expr(&bin_op.r_opr);
+    }
+}
+
+//================
+//  temp_std()
+//================  
+impl <'a> Rust<'a> {     
+    pub fn temp_std(
+        &mut self, 
+        name: &String, 
+        args: &Tuple
+    ) {
+        match Builtin::from_name(name) {
+            Some(Builtin::Println) => self.println(args),
+            Some(Builtin::Print) => self.print(args),
+            Some(Builtin::WebView) => self.web_view(args),
+            Some(Builtin::MobileView) => self.mobile_view(args),
+            Some(Builtin::GuiView) => self.gui_view(args),
+            _ => self.user_defined_fn(&name, args)
+        }
+    }
+}
+
+//================
+//  user_defined_fn()
+//================  
+impl <'a> Rust<'a> {   
+    pub fn user_defined_fn(
+        &mut self,
+        name: &String,
+        args: &Tuple
+    ) { 
+        let _ = write!(self.res, "{}", name); 
+        self.tuple(args);
+    }
+}
+//================
+//  is_import_module()
+//================  
+impl <'a> Rust<'a> {   
+    pub fn is_imported_module(
+        &mut self,
+        expr: &Expr,
+    ) -> bool { 
+        match expr {
+            Expr::Ref(_ref) => {
+                return self.imports.contains(&_ref.value.to_string());
+            }
+            _ =>  false // TODO : currently, only Id Pattern is supported
+        }
+    }
+}
+
+//================
+//  println()
+//================  
+
+        // FIXME,  no need for this function, call _macro() instead , lookup builtin["println!"].
+impl <'a> Rust<'a> {   
+    pub fn println(
+        &mut self,
+        args: &Tuple
+    ) { 
+        let _ = write!(self.res, "println!"); 
+        self.print_args(args);
+    }
+}
+
+
+//================
+//  print()
+//================  
+impl <'a> Rust<'a> {   
+    pub fn print(
+        &mut self,
+        args: &Tuple
+    ) { 
+        let _ = write!(self.res, "print!"); 
+        self.print_args(args);
+    }
+}
+
+//================
+//  print_args()
+//================  
+// FIXME, println! / print! can have a variable number of args, currently , this method only prints a single value   
+impl <'a> Rust<'a> {   
+    pub fn print_args(
+        &mut self,
+        args: &Tuple
+    ) { 
+        let _ = write!(self.res, "(\"{{}}\",");    
+        for (i, item) in args.items.iter().enumerate() {
+            self.expr(&item);
+            if i < args.items.len() - 1 {
+                let _ = write!(self.res, ", ");
+            }
+        }
+        let _ = write!(self.res, ")");    
+
+
+
+    }
+}
+
+
+
+//================
+//  list()
+//================  
+impl <'a> Rust<'a> {   
+    pub fn list(
+        &mut self,
+        list: &List
+    ) {
+        // FIXME, formatting lists / tuples with big expressions on multiple lines
+        // FIXME, vec![] is one of many cases for creating a list
+        let _ = write!(self.res, "vec![");    
+        for (i, item) in list.items.iter().enumerate() {
+            self.boxed_lambda(&item, false);
+            if i < list.items.len() - 1 {
+                let _ = write!(self.res, ", ");
+            }
+        }
+        let _ = write!(self.res, "]");    
+        
+    }
+}
+
+//================
+//  map()
+//================  
+impl <'a> Rust<'a> {   
+    pub fn map(
+        &mut self,
+        map: &Map,
+        sorted: bool
+    ) {
+        if map.items.is_empty() {
+            let _ = write!(self.res, "{}::new()", rs_map(sorted));
+            return
+        }
+        let _ = write!(self.res, "{}::from([", rs_map(sorted));
+        for (i, (key, value)) in map.items.iter().enumerate() {
+            let _ = write!(self.res, "(");
+            self.expr(&key);
+            let _ = write!(self.res, ", ");
+            self.expr(&value);
+            let _ = write!(self.res, ")");
+            if i < map.items.len() - 1 {
+                let _ = write!(self.res, ", ");
+            }
+        }
+        let _ = write!(self.res, "])");
+    }
+}
+
+//================
+//  rs_map()
+//================  
+fn rs_map(sorted: bool) -> &'static str {
+    if sorted {
+        "std::collections::BTreeMap"
+    } else {
+        "std::collections::HashMap"
+    }
+}
+
+//================
+//  tuple()
+//================  
+impl <'a> Rust<'a> {   
+    pub fn tuple(
+        &mut self,
+        tuple: &Tuple
+    ) {
+        let _ = write!(self.res, "(");    
+        for (i, item) in tuple.items.iter().enumerate() {
+            self.expr(&item);
+            if i < tuple.items.len() - 1 {
+                let _ = write!(self.res, ", ");
+            }
+        }
+        let _ = write!(self.res, ")");    
+        
+    }
+}
+
+//================
+//  block()
+//================  
+impl <'a> Rust<'a> {   
+    pub fn block(
+        &mut self,
+        block: &Vec<BlockElement>
+    ) {
+        let _ = writeln!(self.res, " {{",);
+        self.indent.inc();
+        self.var_types.push(vec![]);
+
+        for (i, el) in block.iter().enumerate() {
+            self.mark(source_map::el_token(el));
+            let _ = write!(self.res, "{}", self.indent);
+            self.block_element(el);
+            match el {
+                // the value the block ends with
+                BlockElement::Ret(ret @ Ret{token: None, ..}) if i == block.len() - 1 && !is_assign_ret(ret) => {
+                    let _ = writeln!(self.res, "");
+                },
+                BlockElement::Decl(_) => {
+                    let _ = writeln!(self.res, "");
+                },
+                _ => {
+                    let _ = writeln!(self.res, ";");
+                }
+            }
+        } 
+
+        self.var_types.pop();
+        self.indent.dec();
+        let _ = write!(self.res, "{}}}", self.indent);
+    }
+}
+
+//================
+//  _match()
+//================  
+impl <'a> Rust<'a> {   
+    pub fn _match(
+        &mut self,
+        _match: &Match
+    ) { 
+
+        let _ = write!(self.res, "{}match ",self.indent); 
+        self.expr(&_match.expr);
+        let _ = writeln!(self.res, " {{",);
+        self.indent.inc();
+        for (i, arm) in _match.arms.iter().enumerate() {
+            self.arm(&arm.pattern, &arm.guard, &arm.block);
+            if i < _match.arms.len() - 1 { 
+                let _ = writeln!(self.res, ",",);
+            } else {
+                let _ = writeln!(self.res, "");
+            }
+        }
+        self.indent.dec();
+        let _ = write!(self.res, "{}}}", self.indent);
+ 
+    }
+}
+
+//================
+//  arm()
+//================  
+impl <'a> Rust<'a> {   
+    pub fn arm(
+        &mut self,
+        pattern: &Pattern,
+        guard: &Option<Expr>,
+        block: &Vec<BlockElement>
+    ) { 
+        let _ = write!(self.res, "{}",self.indent ); 
+        self.pattern(&pattern);
+        if let Some(guard) = guard {
+            let _ = write!(self.res,  " if ");
+            self.expr(guard);
+            let _ = write!(self.res,  " ");
+        }
+        let _ = write!(self.res,  "=> ");
+        if block.len() == 1 {
+                self.block_element(&block[0]);
+        } else {
+            self.block(&block);
+        }
+
+    }
+}
+
+//================
+// _for()
+//================
+impl<'a> Rust<'a> {
+    fn _for(
+        &mut self,    
+        _for: &For,
+    ) {
+        self.label(&_for.label);
+        let _ = write!(self.res, "for ",); 
+        self.in_expr(&_for.in_expr);
+        self.block(&_for.block);
+    }
+}
+
+//================
+// in_expr()
+//================
+impl<'a> Rust<'a> {
+    fn in_expr(
+        &mut self,    
+        in_expr: &InExpr,
+    ) {
+        self.pattern(&in_expr.pattern);
+        let _ = write!(self.res, " in ",); 
+        if self.is_map(&in_expr.expr) {
+            let _ = write!(self.res, "&");
+        }
+        match &*in_expr.expr {
+            Expr::Range(range) => self.range(range, false),
+            expr => self.expr(expr)
+        }
+    }
+}
+
+//================
+// _while()
+//================
+impl<'a> Rust<'a> {
+    fn _while(
+        &mut self,    
+        _while: &While,
+    ) {
+        self.label(&_while.label);
+        let _ = write!(self.res, "while "); 
+        self.expr(&_while.expr);
+        self.block(&_while.block);
+    }
+}
+
+//================
+// _loop()
+//================
+impl<'a> Rust<'a> {
+    fn _loop(
+        &mut self,    
+        _loop: &Loop,
+    ) {
+        self.label(&_loop.label);
+        let _ = write!(self.res, "loop"); 
+        self.block(&_loop.block);
+    }
+}
+
+//================
+// label()
+//================
+// `$outer` is written `'outer`
+impl<'a> Rust<'a> {
+    fn label(
+        &mut self,    
+        label: &Option<Token>,
+    ) {
+        if let Some(label) = label {
+            let _ = write!(self.res, "'{}: ", label); 
+        }
+    }
+}
+
+//================
+// _break()
+//================
+impl<'a> Rust<'a> {
+    fn _break(
+        &mut self,    
+        _break: &Break,
+    ) {
+        let _ = write!(self.res, "break"); 
+        if let Some(label) = &_break.label {
+            let _ = write!(self.res, " '{}", label); 
+        }
+        if let Some(expr) = &_break.expr {
+            let _ = write!(self.res, " "); 
+            self.expr(expr);
+        }
+    }
+}
+
+//================
+// _continue()
+//================
+impl<'a> Rust<'a> {
+    fn _continue(
+        &mut self,    
+        _continue: &Continue,
+    ) {
+        let _ = write!(self.res, "continue"); 
+        if let Some(label) = &_continue.label {
+            let _ = write!(self.res, " '{}", label); 
+        }
+    }
+}
+
+//================
+// _if()
+//================
+impl<'a> Rust<'a> {
+    fn _if(
+        &mut self,    
+        _if: &If,
+    ) {
+        for (i, branch) in _if.branches.iter().enumerate() {
+            if i == 0 {
+                let _ = write!(self.res, "if ");   
+                let expr = branch.expr.as_ref().unwrap().clone();
+                self.expr(&expr);
+                self.block(&branch.block);
+            } else {
+                if branch.expr.is_none() {
+                    let _ = write!(self.res, " else ");         
+                    self.block(&branch.block);
+                } else {
+                    let _ = write!(self.res, " else if ");
+                    self.expr(&branch.expr.as_ref().unwrap().clone());
+                    self.block(&branch.block);                    
+                }
+            }
+        }
+        
+    }
+}
+
+
+//================
+//  pattern()
+//================  
+impl <'a> Rust<'a> {   
+    pub fn pattern(
+        &mut self,
+        pattern: &Pattern,
+    ) { 
+
+        match pattern {
+            Pattern::PrimitiveLiteral(expr) => {
+                self.expr(&expr);
+            },
+            Pattern::Id(IdPattern{id}) => {
+                let _ = write!(self.res, "{}", id);
+            },          
+            // FIXME: a Vec has to be matched through `.as_slice()`
+            Pattern::List(list_pat) => {
+                let _ = write!(self.res, "[");
+                for (i, item) in list_pat.items.iter().enumerate() {
+                    if i > 0 {
+                        let _ = write!(self.res, ", ");
+                    }
+                    self.pattern(&item);
+                }
+                let _ = write!(self.res, "]");
+            },
+            Pattern::Tuple(tuple_pat) => {
+                let _ = write!(self.res, "(");
+                for (i, item) in tuple_pat.items.iter().enumerate() {
+                    self.pattern(&item);
+                    if i < tuple_pat.items.len() - 1 {
+                        let _ = write!(self.res, ", ");
+                    }
+                }
+                let _ = write!(self.res, ")");
+            },
+            Pattern::Struct(struct_pat) => todo!(),
+            Pattern::Enum(enum_pat) => {
+                if let Some(name) = &enum_pat.name {
+                    let _ = write!(self.res, "{}::", name);
+                }
+                let _ = write!(self.res, "{}", enum_pat.variant.name);
+                if let Some(pattern) = &enum_pat.variant.pattern {
+                    let _ = write!(self.res, "(");
+                    self.pattern(pattern);
+                    let _ = write!(self.res, ")");
+                }
+            },
+            Pattern::Range(range) => {
+                if let Some(start) = &range.start {
+                    self.pattern_literal(start);
+                }
+                let op = if range.inclusive { "..=" } else { ".." };
+                let _ = write!(self.res, "{}", op);
+                if let Some(end) = &range.end {
+                    self.pattern_literal(end);
+                }
+            },
+            Pattern::Or(patterns) => {
+                for (i, p) in patterns.iter().enumerate() {
+                    if i > 0 {
+                        let _ = write!(self.res, " | ");
+                    }
+                    self.pattern(p);
+                }
+            },
+            Pattern::Binding(BindingPattern{id, pattern}) => {
+                let _ = write!(self.res, "{} @ ", id);
+                self.pattern(pattern);
+            },
+            Pattern::Rest => {
+                let _ = write!(self.res, "..");
+            },
+            Pattern::Wildcard  => {
+                let _ = write!(self.res, "_");
+            },
+            // FIXME temporary hardcoded variants
+            Pattern::Ok(pattern) => {
+                let _ = write!(self.res, "Ok(");
+                self.pattern(pattern);
+                let _ = write!(self.res, ")");
+            },
+            Pattern::Err(pattern) => {
+                let _ = write!(self.res, "Err(");
+                self.pattern(pattern);
+                let _ = write!(self.res, ")");
+            },
+            Pattern::Some(pattern) => {
+                let _ = write!(self.res, "Some(");
+                self.pattern(pattern);
+                let _ = write!(self.res, ")");
+            },
+            Pattern::None => {
+                let _ = write!(self.res, "None");
+            }
+        }
+
+    }
+}
+
+//================
+//  pattern_literal()
+//================  
+// characters are written as chars in patterns, not as strings
+impl <'a> Rust<'a> {   
+    fn pattern_literal(
+        &mut self,
+        expr: &Expr,
+    ) { 
+        match expr {
+            Expr::Char(c) => {
+                let _ = write!(self.res, "'{}'", c);
+            },
+            expr => self.expr(expr)
+        }
+    }
+}
+
+//================
+//  block_element()
+//================  
+impl <'a> Rust<'a> {   
+    pub fn block_element(
+        &mut self,
+        el: &BlockElement,
+    ) { 
+        match el {
+            BlockElement::MainArgs => {
+                self.main_args()
+            }
+            BlockElement::Decl(decl) => {
+                self.decl(&decl);
+            },
+            BlockElement::Expr(expr) => self.expr(&expr),
+            BlockElement::Ret(ret) => self.ret(&ret, false)
+        }
+    }
+}
+
+//================
+//  main_args()
+//================  
+impl <'a> Rust<'a> {   
+    pub fn main_args(
+        &mut self,
+    ) { 
+        let _ = writeln!(self.res, "let args: Vec<String> = std::env::args().collect();");         
+    }
+}
+
+//================
+//  decl()
+//================  
+impl <'a> Rust<'a> {   
+    pub fn decl(
+        &mut self,
+        decl: &Decl
+
+    ) { 
+        if let Pattern::Id(IdPattern{id}) = &decl.pattern {
+            let _type = match (&decl._type, &decl.expr) {
+                (Some(_type), _) => Some(_type.clone()),
+                (None, Some(expr)) => self.expr_type(expr),
+                (None, None) => None
+            };
+            if let Some(_type) = _type {
+                self.declare(id, &_type);
+            }
+        }
+        let _ = write!(self.res, "let ");         
+        self.decl_pattern(&decl.pattern, decl.is_mut);
+        match &decl._type {
+            None => (),
+            Some(Type::FnType(_)) => (),        // a lambda has its own anonymous type, let rustc infer it
+            Some(_type) => self.type_annotation(&_type)
+        }
+        
+        if let Some(expr) = &decl.expr {
+            let _ = write!(self.res, " = ");
+            match (expr, &decl._type) {
+                (Expr::Map(map), Some(Type::MapType(MapType{sorted, ..}))) => self.map(map, *sorted),
+                _ => self.expr(&expr)
+            }
+        }
+        let _ = write!(self.res, ";");
+    }
+}
+
+//================
+//  decl_pattern()
+//================
+// `let mut x`, `let (mut a, mut b)`, only the locals the mutability pass found to be mutated are `mut`
+impl <'a> Rust<'a> {
+    fn decl_pattern(
+        &mut self,
+        pattern: &Pattern,
+        is_mut: bool
+    ) {
+        match pattern {
+            Pattern::Id(_) if is_mut => {
+                let _ = write!(self.res, "mut ");
+                self.pattern(pattern);
+            },
+            Pattern::Tuple(tuple_pat) if is_mut => {
+                let _ = write!(self.res, "(");
+                for (i, item) in tuple_pat.items.iter().enumerate() {
+                    self.decl_pattern(item, is_mut);
+                    if i < tuple_pat.items.len() - 1 {
+                        let _ = write!(self.res, ", ");
+                    }
+                }
+                let _ = write!(self.res, ")");
+            },
+            _ => self.pattern(pattern)
+        }
+    }
+}
+
+//================
+//  web_server()
+//================  
+impl <'a> Rust<'a> { 
+    pub fn  web_server(
+        &mut self,
+        data: &StructLiteral
+    ) {
+        let mut path = self.proj_dir.res.pages.clone();
+        path.push(INDEX_HTML);
+        let index_html = self.html
+                        .as_mut()
+                        .unwrap()
+                        .page(&mut path, data);
+        self.actix(&path, data);
+
+    }
+}
+
+//================
+//  actix()
+//================  
+impl <'a> Rust<'a> {
+    pub fn  actix(
+        &mut self,
+        path: &PathBuf,
+        data: &StructLiteral
+
+    ) {
+
+    let actix_web = ActixWeb::new();
+    let actix_file = ActixFiles::new();
+
+    self.cargo_toml.add(actix_file);
+    self.cargo_toml.add(actix_web);
+
+    let res_dir = self.lexicon.word(&self.src_lang, "dir.res");
+
+    let pages_dir = self.lexicon.word(&self.src_lang, "dir.pages");
+
+    let server_start_msg = match self.src_lang {
+        Lang::Ar => "لقد تم تشغيل المخدم , العنوان : ",
+        Lang::En => "server started: ",
+        Lang::Fa => "سرور اجرا شد، نشانی: "
+    };
+
+    let mut settings = None;
+    
+    let iter = if let Some((t, Some(expr))) = data.items.get(0) {
+
+        let mut iter = data.items.iter();
+        match &t.value  {
+            TokenValue::Id(x) => {
+                if self.lexicon.is("web.data", &self.src_lang, x) {
+                    match expr {
+                        Expr::StructLiteral(literal) => {
+                            iter = literal.items.iter();
+                        }
+                        _ => ()
+                    }    
+                }
+            },
+            _ => ()
+        }
+        iter
+
+    } else {
+        data.items.iter()
+    };
+
+
+    for (k,v) in iter {
+        match self.lexicon.concept("web", &self.src_lang, &k.to_string()) {
+            Some("web.settings") => {
+                settings = self.server_settings(v);
+                break;
+            },
+            _ => panic!("expecting server settings")
+        }
+    };
+
+    let settings = settings.expect("expecting server settings");
+
+
+    let hostname = if self.lexicon.is("web.localhost", &self.src_lang, &settings.hostname) {
+        "localhost".to_string()
+    } else {
+        settings.hostname
+            .chars()
+            .map(|c| if is_eastern_digit(c) { to_western_digit(c) } else { c })
+            .collect()
+    };
+
+    let port = to_western_num(&settings.port.to_string());
+
+
+    
+
+    // FIXEME, hardcoding the example for demo
+    let _ = write!(self.res, 
+r#"use actix_web::{{App, HttpServer}};
+use actix_files::Files;
+
+const HOSTNAME: &str = "{hostname}";
+const PORT: u32 = {port};
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {{
+    let addr = format!("{{}}:{{}}", HOSTNAME, PORT);
+    let server = HttpServer::new(move || {{
+        App::new()            
+            .service(Files::new("/", "./{res_dir}/{pages_dir}").index_file("index.html"))  
+
+    }});
+
+    println!("{server_start_msg}\n\t\thttp://{{}}", addr);
+    server
+    .bind(addr)?
+    .run()
+    .await
+}}"#);
+        
+        match fs::write(&self.proj_dir.src.main, &self.res){
+            Err(err) => panic!("{:?}", err),
+            Ok(_) => ()
+        }
+    }
+}
+
+//================
+//   ServerSettings()
+//================
+pub struct ServerSettings {
+    pub hostname: String,
+    pub port: String
+}
+
+impl ServerSettings {
+    pub fn new() -> Self {
+        Self {
+            hostname : String::new(),
+            port: String::new()
+        }
+    }
+}
+
+//================
+//   server_settings()
+//================
+impl <'a> Rust<'a> {
+    pub fn server_settings (
+        &mut self,
+        data: &Option<Expr>
+    ) -> Option<ServerSettings> {
+        let mut settings = ServerSettings::new();
+
+        let data = match data {
+            Some(Expr::StructLiteral(sruct_literal)) => sruct_literal,
+            _ => return None
+        };
+
+        for (k,v) in data.items.iter() {
+            match self.lexicon.concept("web", &self.src_lang, &k.to_string()) {
+                Some("web.hostname") => {
+                    
+                    if let Some(v) = v { 
+                        match v {
+                            Expr::Str(v) => settings.hostname = v.value.to_string(),
+                            _ => panic!("unexpected hostname value")
+                        }
+                    }
+                    
+                },
+                Some("web.port") => {
+                    if let Some(v) = v { 
+                        match v {
+                            Expr::Int(v) => settings.port = v.value.to_string().parse().expect("port should be a number"),
+                            _ => panic!("unexpected port value")
+                        }
+                    }                    
+                },
+                _ => panic!("unsupported: {:?}", k)
+            }
+        }
+        Some(settings)
+    }
+}
+
+
+//================
+//  web_view()
+//================  
+impl <'a> Rust<'a> {
+    pub fn web_view(
+        &mut self,
+        args: &Tuple        
+    ) {
+        todo!();
+    }
+}
+
+
+//================
+//  mobile_view()
+//================  
+impl <'a> Rust<'a> { 
+    pub fn mobile_view(
+        &mut self,
+        args: &Tuple        
+    ) {
+        todo!();
+    }
+}
+
+//================
+//  gui_view()
+//================  
+impl <'a> Rust<'a> {   
+    pub fn gui_view(
+        &mut self,
+        args: &Tuple        
+    ) {
+        todo!();
+    }
+}
+
+
+
@@ -0,0 +1,309 @@
+use std::{
+    fs,
+    fmt,
+    path::{
+        Path,
+        PathBuf
+    },
+    collections::HashMap,
+};
+
+use regex::{
+    Captures,
+    Regex
+};
+
+use crate::lang::{
+    token::{
+        Token,
+        Location
+    },
+    syntax_tree::ast::*,
+};
+
+//================
+//   Constants
+//================
+const MAP_EXT: &'static str = "map";
+
+//================
+//   SourceMap
+//================
+// the seen location each generated statement comes from, written next to the generated file as `main.rs.map`:
+//  the first line is the seen file, then one `rs_line rs_column seen_line seen_column` per statement
+#[derive(Clone, Debug, Default)]
+pub struct SourceMap {
+    pub src: String,
+    lines: Vec<(usize, usize, Location)>    // sorted by the generated line, one mark per line
+}
+
+impl SourceMap {
+    //---------------------
+    //  new()
+    //---------------------
+    pub fn new(src: &str) -> Self {
+        Self {
+            src: src.to_string(),
+            lines: vec![]
+        }
+    }
+
+    //---------------------
+    //  mark()
+    //---------------------
+    // the text written at `rs_line:rs_column` and the lines after it come from `location`, until the next mark
+    pub fn mark(
+        &mut self,
+        rs_line: usize,
+        rs_column: usize,
+        location: &Location
+    ) {
+        match self.lines.last_mut() {
+            Some((line, ..)) if *line > rs_line => panic!("bug: source map marks out of order"),
+            Some((line, column, loc)) if *line == rs_line => {
+                *column = rs_column;
+                *loc = location.clone();
+            },
+            _ => self.lines.push((rs_line, rs_column, location.clone()))
+        }
+    }
+
+    //---------------------
+    //  lookup()
+    //---------------------
+    // on the marked line a column is moved by its distance from the mark, e.g. the argument of a call
+    //  written on one line, on the lines after it the location of the mark is kept
+    pub fn lookup(
+        &self,
+        rs_line: usize,
+        rs_column: usize
+    ) -> Option<Location> {
+        let i = self.lines.partition_point(|(line, ..)| *line <= rs_line);
+        if i == 0 { return None }
+        let (line, column, location) = &self.lines[i - 1];
+        if *line == rs_line && rs_column > *column {
+            Some(Location::new(location.line, location.column + rs_column - column))
+        } else {
+            Some(location.clone())
+        }
+    }
+
+    //---------------------
+    //  from_str()
+    //---------------------
+    pub fn from_str(v: &str) -> Option<Self> {
+        let mut lines = v.lines();
+        let mut map = Self::new(lines.next()?);
+        for line in lines {
+            let nums = line
+                .split_whitespace()
+                .map(|n| n.parse::<usize>().ok())
+                .collect::<Option<Vec<_>>>()?;
+            match nums[..] {
+                [rs_line, rs_column, line, column] => map.mark(rs_line, rs_column, &Location::new(line, column)),
+                _ => return None
+            }
+        }
+        Some(map)
+    }
+
+    //---------------------
+    //  save()
+    //---------------------
+    pub fn save(&self, rs_path: &Path) {
+        if let Err(err) = fs::write(map_path(rs_path), self.to_string()) {
+            panic!("{:?}", err);
+        }
+    }
+
+    //---------------------
+    //  load()
+    //---------------------
+    pub fn load(rs_path: &Path) -> Option<Self> {
+        let v = fs::read_to_string(map_path(rs_path)).ok()?;
+        Self::from_str(&v)
+    }
+}
+
+//================
+//   Display SourceMap
+//================
+impl fmt::Display for SourceMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.src)?;
+        for (rs_line, rs_column, location) in self.lines.iter() {
+            writeln!(f, "{} {} {} {}", rs_line, rs_column, location.line, location.column)?;
+        }
+        Ok(())
+    }
+}
+
+//================
+//   map_path()
+//================
+fn map_path(rs_path: &Path) -> PathBuf {
+    let mut path = rs_path.as_os_str().to_owned();
+    path.push(".");
+    path.push(MAP_EXT);
+    PathBuf::from(path)
+}
+
+//================
+//   SourceMaps
+//================
+// the maps of all the generated files of a crate, keyed by the file name, e.g. `main.rs`
+#[derive(Debug, Default)]
+pub struct SourceMaps {
+    maps: HashMap<String, SourceMap>
+}
+
+impl SourceMaps {
+    //---------------------
+    //  new()
+    //---------------------
+    pub fn new() -> Self {
+        Self {
+            maps: HashMap::new()
+        }
+    }
+
+    //---------------------
+    //  insert()
+    //---------------------
+    pub fn insert(
+        &mut self,
+        rs_file: &str,
+        map: SourceMap
+    ) {
+        self.maps.insert(rs_file.to_string(), map);
+    }
+
+    //---------------------
+    //  load()
+    //---------------------
+    // the maps written by the last compilation into the `src` dir of the crate, a file without a map is
+    //  left as rustc names it
+    pub fn load(src_dir: &Path) -> Self {
+        let mut maps = Self::new();
+        let Ok(entries) = fs::read_dir(src_dir) else { return maps };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "rs") {
+                if let (Some(map), Some(name)) = (SourceMap::load(&path), path.file_name()) {
+                    maps.insert(&name.to_string_lossy(), map);
+                }
+            }
+        }
+        maps
+    }
+
+    //---------------------
+    //  lookup()
+    //---------------------
+    // the seen file and location of a generated line and column, `rs_file` can be a path, e.g. `src/main.rs`
+    pub fn lookup(
+        &self,
+        rs_file: &str,
+        rs_line: usize,
+        rs_column: usize
+    ) -> Option<(&str, Location)> {
+        let name = Path::new(rs_file).file_name()?.to_string_lossy();
+        let map = self.maps.get(name.as_ref())?;
+        map.lookup(rs_line, rs_column).map(|location| (map.src.as_str(), location))
+    }
+
+    //---------------------
+    //  rewrite()
+    //---------------------
+    // `src/main.rs:12:5` in a rustc diagnostic, a panic message or a backtrace becomes `main.seen:3:9`
+    pub fn rewrite(&self, line: &str) -> String {
+        if self.maps.is_empty() { return line.to_string() }
+        rs_location_regex()
+            .replace_all(line, |caps: &Captures| {
+                let rs_line = caps[2].parse::<usize>().ok();
+                let rs_column = caps[3].parse::<usize>().ok();
+                match (self.maps.get(&caps[1]), rs_line, rs_column) {
+                    (Some(map), Some(rs_line), Some(rs_column)) => match map.lookup(rs_line, rs_column) {
+                        Some(location) => format!("{}:{}", map.src, location),
+                        None => caps[0].to_string()
+                    },
+                    _ => caps[0].to_string()
+                }
+            })
+            .into_owned()
+    }
+}
+
+//================
+//   rs_location_regex()
+//================
+// `src/main.rs:12:5`, `./src/main.rs:12:5` or `/home/x/proj/build/src/main.rs:12:5`
+fn rs_location_regex() -> Regex {
+    Regex::new(r"(?:[^\s:]*/)?src/([^\s:/]+\.rs):(\d+):(\d+)").unwrap()
+}
+
+//================
+//   el_token()
+//================
+// the first seen token of a statement, where its generated lines are mapped to
+pub fn el_token(el: &BlockElement) -> Option<&Token> {
+    match el {
+        BlockElement::Decl(decl) => decl_token(decl),
+        BlockElement::Expr(expr) => expr_token(expr),
+        BlockElement::Ret(Ret{token: Some(t), ..}) => Some(t),
+        BlockElement::Ret(Ret{expr: Some(expr), ..}) => expr_token(expr),
+        BlockElement::Ret(_)
+        | BlockElement::MainArgs => None
+    }
+}
+
+//================
+//   decl_token()
+//================
+fn decl_token(decl: &Decl) -> Option<&Token> {
+    match &decl.pattern {
+        Pattern::Id(IdPattern{id, ..}) => Some(id),
+        _ => decl.expr.as_ref().and_then(expr_token)
+    }
+}
+
+//================
+//   expr_token()
+//================
+pub fn expr_token(expr: &Expr) -> Option<&Token> {
+    match expr {
+        Expr::Bool(t)
+        | Expr::Int(t)
+        | Expr::Float(t)
+        | Expr::Char(t)
+        | Expr::Str(t)
+        | Expr::Ref(t)
+        | Expr::StrSlice(t) => Some(t),
+        Expr::GenericRef(generic_ref) => Some(&generic_ref.id),
+        Expr::BinOp(bin_op) => expr_token(&bin_op.l_opr).or(Some(&bin_op.op)),
+        Expr::PreUniOp(uni_op) => Some(&uni_op.op),
+        Expr::PostUniOp(uni_op) => expr_token(&uni_op.opr).or(Some(&uni_op.op)),
+        Expr::Cast(cast) => expr_token(&cast.expr),
+        Expr::LetIn(let_in) => decl_token(&let_in.decl),
+        Expr::Fn(_fn) => _fn.name.as_ref(),
+        Expr::Match(_match) => Some(&_match.token),
+        Expr::For(_for) => expr_token(&_for.in_expr.expr),
+        Expr::While(_while) => expr_token(&_while.expr),
+        Expr::Break(_break) => Some(&_break.token),
+        Expr::Continue(_continue) => Some(&_continue.token),
+        Expr::If(_if) => _if.branches
+            .first()
+            .and_then(|branch| branch.expr.as_deref())
+            .and_then(expr_token),
+        Expr::StructLiteral(literal) => literal.items.first().map(|(name, _)| name),
+        Expr::List(List{items})
+        | Expr::Tuple(Tuple{items}) => items.first().and_then(expr_token),
+        Expr::Block(block) => block.first().and_then(el_token),
+        Expr::Ok(expr)
+        | Expr::Err(expr)
+        | Expr::Some(expr)
+        | Expr::Borrow(expr)
+        | Expr::Clone(expr) => expr_token(expr),
+        _ => None
+    }
+}
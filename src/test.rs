@@ -1,2 +1,4 @@
 pub mod lang;
-pub mod test_run;
\ No newline at end of file
+pub mod test_run;
+pub mod target;
+pub mod tool;
\ No newline at end of file
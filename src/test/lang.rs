@@ -1,3 +1,10 @@
 pub mod test_lexer;
 pub mod test_parser;
-pub mod test_resolver;
\ No newline at end of file
+pub mod test_resolver;
+pub mod test_type_checker;
+pub mod test_exhaustiveness;
+pub mod test_attrs;
+pub mod test_mutability;
+pub mod test_ownership;
+pub mod test_hir;
+pub mod test_passes;
\ No newline at end of file
@@ -0,0 +1,157 @@
+use indoc::indoc;
+
+use crate::util::print::eprintln_red;
+
+use crate::lang::{
+	Lang,
+	script::Script,
+	lexer::Lexer,
+    parser::Parser,
+    syntax_tree::ast::Attr,
+    attrs::{
+        AttrRegistry,
+        AttrHandler
+    }
+};
+use crate::transl::lexicon::Lexicon;
+
+//================
+//   builtin_attrs()
+//================
+#[test]
+fn builtin_attrs() {	
+	apply_attrs_en( indoc!{r#"
+    @derive(Eq, Default)
+    Point {
+        x: int
+        y: int
+    }
+    @test
+    check() -> println("ok")
+    "#});
+}
+
+//================
+//   builtin_attrs_ar()
+//================
+#[test]
+fn builtin_attrs_ar() {	
+	apply_attrs_ar( indoc!{r#"
+    @اشتق(قابل_للمساواة)
+    النقطة {
+        س: صحيح
+        ص: صحيح
+    }
+    "#});
+}
+
+//================
+//   unknown_attr()
+//================
+#[test]
+#[should_panic(expected = "unknown attribute `@route`")]
+fn unknown_attr() {	
+	apply_attrs_en( indoc!{r#"
+    @route("/", method: "GET")
+    home() -> "hi"
+    "#});
+}
+
+//================
+//   derive_on_fn()
+//================
+#[test]
+#[should_panic(expected = "`@derive` can only be written on a struct")]
+fn derive_on_fn() {	
+	apply_attrs_en( indoc!{r#"
+    @derive(Eq)
+    f() -> 1
+    "#});
+}
+
+//================
+//   derive_default_with_field_defaults()
+//================
+#[test]
+#[should_panic(expected = "`Default` can not be derived for a struct with field defaults")]
+fn derive_default_with_field_defaults() {	
+	apply_attrs_en( indoc!{r#"
+    @derive(Default)
+    Point {
+        x: int = 1
+        y: int = 1
+    }
+    "#});
+}
+
+//================
+//   registered_attr()
+//================
+// a handler registered on the registry, for an attribute the lexicon does not know
+#[test]
+fn registered_attr() {	
+    let mut attrs = AttrRegistry::builtin();
+    attrs.register(Box::new(Route));
+	apply_attrs_with(&attrs, Lang::En, indoc!{r#"
+    @route("/", method: "GET")
+    home() -> "hi"
+    "#});
+}
+
+//================
+//   Route
+//================
+struct Route;
+
+impl AttrHandler for Route {
+    fn concept(&self) -> &'static str { "attr.route" }
+
+    fn rs_attrs(
+        &self,
+        attr: &Attr,
+        lexicon: &Lexicon
+    ) -> Vec<String> {
+        vec!["#[route]".to_string()]
+    }
+}
+
+//================
+//   apply_attrs_ar()
+//================
+fn apply_attrs_ar(src: &str) { apply_attrs(Lang::Ar, src)}
+//================
+//   apply_attrs_en()
+//================
+fn apply_attrs_en(src: &str) { apply_attrs(Lang::En, src)}
+
+//================
+//   apply_attrs()
+//================
+fn apply_attrs(
+    lang: Lang,
+    src: &str
+) {
+    apply_attrs_with(&AttrRegistry::builtin(), lang, src)
+}
+
+//================
+//   apply_attrs_with()
+//================
+fn apply_attrs_with(
+    attrs: &AttrRegistry,
+    lang: Lang,
+    src: &str
+) {
+	let script = Script::from_str(src);
+
+	let (mut tokens, errs) = Lexer::new().tokens(&lang, &script);
+    let mut parser = Parser::new();
+    let (mut ast, symtab, errs) = parser.parse(&mut tokens);
+    assert!(errs.is_empty());
+
+    let errs = attrs.apply(&mut ast, Lexicon::embedded());
+    if !errs.is_empty() {
+        eprintln_red(format!("{:#?}", errs).as_str());
+        panic!("{}", errs[0]);
+    }
+}
@@ -0,0 +1,174 @@
+use indoc::indoc;
+
+use crate::util::print::eprintln_red;
+
+use crate::lang::{
+	Lang,
+	script::Script,
+	lexer::Lexer,
+    parser::Parser,
+    exhaustiveness::Exhaustiveness
+};
+
+//================
+//   exhaustive()
+//================
+#[test]
+fn exhaustive() {	
+	check_matches_en( indoc!{"
+    () -> {
+        let a = match true {
+            true => 1
+            false => 0
+        }
+        let b = match Some(3) {
+            Some(0) | None => 0
+            Some(n @ 1..=9) => n
+            Some(_) => 10
+        }
+        let c = match (a, b) {
+            (0, _) => 0
+            (_, 0) => 0
+            (x, y) => x + y
+        }
+        let d = match [a, b] {
+            [] => 0
+            [x] => x
+            [x, ..] => x
+        }
+        let e = match a {
+            ..0 => -1
+            0 => 0
+            1.. => 1
+        }
+    }
+    "});
+}
+
+//================
+//   exhaustive_ar()
+//================
+#[test]
+fn exhaustive_ar() {	
+	check_matches_ar( indoc!{"
+    () -> {
+        ليكن س = طابق قيمة(٣) {
+            قيمة(٠) او بلا => ٠
+            قيمة(ن) => ن
+        }
+    }
+    "});
+}
+
+//================
+//   missing_none()
+//================
+#[test]
+#[should_panic(expected = "non-exhaustive match, `None` is not covered")]
+fn missing_none() {	
+	check_matches_en( indoc!{"
+    () -> {
+        let a = match Some(3) {
+            Some(n) => n
+        }
+    }
+    "});
+}
+
+//================
+//   missing_int()
+//================
+#[test]
+#[should_panic(expected = "non-exhaustive match, `0` is not covered")]
+fn missing_int() {	
+	check_matches_en( indoc!{"
+    () -> {
+        let a = match 3 {
+            ..0 => -1
+            1.. => 1
+        }
+    }
+    "});
+}
+
+//================
+//   guarded_arm_is_not_exhaustive()
+//================
+#[test]
+#[should_panic(expected = "non-exhaustive match, `false` is not covered")]
+fn guarded_arm_is_not_exhaustive() {	
+	check_matches_en( indoc!{"
+    () -> {
+        let a = match true {
+            true => 1
+            false if a > 0 => 0
+        }
+    }
+    "});
+}
+
+//================
+//   missing_tuple()
+//================
+#[test]
+#[should_panic(expected = "non-exhaustive match, `(false, false)` is not covered")]
+fn missing_tuple() {	
+	check_matches_en( indoc!{"
+    () -> {
+        let a = match (true, false) {
+            (true, _) => 1
+            (_, true) => 2
+        }
+    }
+    "});
+}
+
+//================
+//   unreachable_arm()
+//================
+#[test]
+#[should_panic(expected = "unreachable arm")]
+fn unreachable_arm() {	
+	check_matches_en( indoc!{"
+    () -> {
+        let a = match (1, true) {
+            (_, true) => 1
+            (_, false) => 0
+            (1, _) => 2
+        }
+    }
+    "});
+}
+
+//================
+//   check_matches_ar()
+//================
+fn check_matches_ar(src: &str) { check_matches(Lang::Ar, src)}
+//================
+//   check_matches_en()
+//================
+fn check_matches_en(src: &str) { check_matches(Lang::En, src)}
+
+//================
+//   check_matches()
+//================
+fn check_matches(
+    lang: Lang,
+    src: &str
+) {
+	let script = Script::from_str(src);
+
+	let (mut tokens, errs) = Lexer::new().tokens(&lang, &script);
+    let mut parser = Parser::new();
+    let (ast, symtab, errs) = parser.parse(&mut tokens);
+    if !errs.is_empty() {
+        eprintln_red(format!("{:#?}", errs).as_str());
+        panic!("parse error: {}", errs[0]);
+    }
+
+    let errs = Exhaustiveness::new().check(&ast);
+    if !errs.is_empty() {
+        eprintln_red(format!("{:#?}", errs).as_str());
+        panic!("{}", errs[0]);
+    }
+}
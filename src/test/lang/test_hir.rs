@@ -0,0 +1,103 @@
+use indoc::indoc;
+
+use crate::lang::{
+	Lang,
+	script::Script,
+	lexer::Lexer,
+    parser::Parser,
+    hir::{
+        Hir,
+        HirLowering
+    },
+    syntax_tree::ast::*
+};
+
+//================
+//   neutral_names_ar()
+//================
+#[test]
+fn neutral_names_ar() {	
+	let hir = lower("رئيسي.س", Lang::Ar, indoc!{r#"
+    م := احضر(«م.س»)
+
+    @اشتق(قابل_للمساواة)
+    النقطة {
+        س: صحيح
+    }
+
+    () -> {
+        ليكن ب = صواب
+        ليكن ن = ١٢
+        اطبع_سطر(ن)
+    }
+    "#});
+    assert_eq!(hir.name, "main");
+    assert!(hir.is_import("م"));
+    assert_eq!(hir.imports[0].path, "م");
+
+    let Some(ModElement::Struct(_struct)) = hir.items.iter().find(|el| matches!(el, ModElement::Struct(_))) else { panic!() };
+    assert_eq!(_struct.attrs[0].name().value.to_string(), "derive");
+    let Expr::Ref(_trait) = &_struct.attrs[0].args()[0].expr else { panic!() };
+    assert_eq!(_trait.value.to_string(), "PartialEq");
+    let Type::PrimitiveType(PrimitiveType{id}) = &_struct.fields.as_ref().unwrap()[0]._type else { panic!() };
+    assert_eq!(id.value.to_string(), "int");
+
+    let Some(ModElement::MainFn(main)) = hir.items.iter().find(|el| matches!(el, ModElement::MainFn(_))) else { panic!() };
+    let words = main.block
+        .iter()
+        .map(|el| match el {
+            BlockElement::Decl(Decl{expr: Some(Expr::Bool(t) | Expr::Int(t)), ..}) => t.value.to_string(),
+            BlockElement::Expr(expr) => callee(expr),
+            BlockElement::Ret(Ret{expr: Some(expr), ..}) => callee(expr),
+            _ => String::new()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(words, vec!["true", "12", "println"]);
+}
+
+//================
+//   neutral_names_en()
+//================
+#[test]
+fn neutral_names_en() {	
+	let hir = lower("util.seen", Lang::En, indoc!{r#"
+    m := import("m.rs")
+
+    f() -> println(true)
+    "#});
+    assert_eq!(hir.name, "util");
+    assert_eq!(hir.imports[0].path, "m");
+    assert_eq!(hir.items.len(), 1);
+}
+
+//================
+//   callee()
+//================
+fn callee(expr: &Expr) -> String {
+    match expr {
+        Expr::BinOp(BinOp{l_opr, ..}) => match &**l_opr {
+            Expr::Ref(t) => t.value.to_string(),
+            _ => String::new()
+        },
+        _ => String::new()
+    }
+}
+
+//================
+//   lower()
+//================
+fn lower(
+    path: &str,
+    lang: Lang,
+    src: &str
+) -> Hir {
+	let script = Script::from_str(src);
+
+	let (mut tokens, errs) = Lexer::new().tokens(&lang, &script);
+    let mut parser = Parser::new();
+    parser.lang(&lang);
+    let (ast, symtab, errs) = parser.parse(&mut tokens);
+    assert!(errs.is_empty());
+
+    HirLowering::new().lower(path, &lang, ast)
+}
@@ -68,6 +68,7 @@ fn infer(
 
 	let (mut tokens, errs) = Lexer::new().tokens(&lang, &script);
     let mut parser = Parser::new();
+    parser.lang(&lang);
     let (ast, symtab, errs) = parser.parse(&mut tokens);
     let mut resolver = Resolver::new();
     let (symtab, restab, errs) = resolver.resolve(symtab);
@@ -1,12 +1,17 @@
+use std::str::FromStr;
+
 use indoc::indoc;
 
 use crate::lang::{
 	Lang,
 	script::Script,
 	compiler::compile,
-	lexer::Lexer
+	lexer::Lexer,
+	token::TokenValue
 };
 
+use crate::transl::lexicon::Lexicon;
+
 use crate::util::print::eprintln_red;
 
 //================
@@ -93,6 +98,121 @@ fn invalid_en_num() {
 
 }
 
+//================
+//   valid_fa_num()
+//================
+#[test]
+fn valid_fa_num() {
+	scan_fa( indoc!{r#"
+		۱۲۳
+		۱۲۳٫۸۳
+		4334
+		9.34
+	"#});
+
+}
+
+//================
+//   invalid_fa_num()
+//================
+#[test]
+#[should_panic(expected = "you can either use native digits (۰ - ۹) or Western (0 - 9) but not a mix")]
+fn invalid_fa_num() {
+	scan_fa( indoc!{r#"
+			۱۲٣
+	"#});
+
+}
+
+//================
+//   fa_keywords()
+//================
+#[test]
+fn fa_keywords() {
+	let script = Script::from_str("اگر درست");
+	let (tokens, errs) = Lexer::new().tokens(&Lang::Fa, &script);
+	assert!(errs.is_empty());
+	assert!(matches!(tokens[0].value, TokenValue::If));
+	assert!(matches!(tokens[1].value, TokenValue::Bool(_)));
+}
+
+//================
+//   bidi_in_id()
+//================
+#[test]
+#[should_panic(expected = "bidirectional control character U+202E is not allowed")]
+fn bidi_in_id() {
+	scan_en( indoc!{"
+			access_level\u{202E}\u{2066}admin
+	"});
+}
+
+//================
+//   bidi_in_comment()
+//================
+#[test]
+#[should_panic(expected = "bidirectional control character U+202E is not allowed")]
+fn bidi_in_comment() {
+	scan_en( indoc!{"
+			x := 1 // \u{202E} } \u{2066}if is_admin\u{2069} \u{2066} begin admins only
+	"});
+}
+
+//================
+//   allowed_bidi_in_str()
+//================
+#[test]
+fn allowed_bidi_in_str() {
+	scan_allow_bidi(Lang::Ar, indoc!{"
+			س := «\u{2067}مرحبا\u{2069}»
+	"});
+}
+
+//================
+//   unbalanced_bidi_in_str()
+//================
+#[test]
+#[should_panic(expected = "unterminated bidirectional control character U+202E")]
+fn unbalanced_bidi_in_str() {
+	scan_allow_bidi(Lang::En, indoc!{"
+			s := \"\u{202E}hello\"
+	"});
+}
+
+//================
+//   lexicon_alias()
+//================
+#[test]
+fn lexicon_alias() {
+	let mut lexicon = Lexicon::embedded().clone();
+	lexicon.merge(Lexicon::from_str(indoc!{"
+			concept		ar		en
+			kw.let		دع		-
+	"}).unwrap());
+
+	let script = Script::from_str("دع س = ١");
+	let mut lexer = Lexer::new();
+	lexer.lexicon(&lexicon);
+	let (tokens, errs) = lexer.tokens(&Lang::Ar, &script);
+	assert!(errs.is_empty());
+	assert!(matches!(tokens[0].value, TokenValue::Let));
+}
+
+//================
+//   range()
+//================
+#[test]
+fn range() {
+	let script = Script::from_str("1..5 ١..=٥ 1.5");
+	let (tokens, errs) = Lexer::new().tokens(&Lang::Ar, &script);
+	assert!(errs.is_empty());
+	assert!(matches!(tokens[0].value, TokenValue::Int(_)));
+	assert!(matches!(tokens[1].value, TokenValue::DoubleDot));
+	assert!(matches!(tokens[2].value, TokenValue::Int(_)));
+	assert!(matches!(tokens[4].value, TokenValue::DoubleDotEqual));
+	assert!(matches!(tokens[6].value, TokenValue::Float(_)));
+}
+
 //================
 //   scan_ar()
 //================
@@ -103,6 +223,11 @@ fn scan_ar(src: &str) { scan(Lang::Ar, src); }
 //================
 fn scan_en(src: &str) { scan(Lang::En, src); }
 
+//================
+//   scan_fa()
+//================
+fn scan_fa(src: &str) { scan(Lang::Fa, src); }
+
 //================
 //   scan()
 //================
@@ -116,8 +241,28 @@ fn scan(
 	println!("{:#?}", errs);	
     if !errs.is_empty() {
         eprintln_red(format!("{:#?}", errs).as_str());
-        panic!();
+        panic!("{}", errs[0]);
     } else {
 		println!("{:?}", tokens);
 	}	
- }
\ No newline at end of file
+ }
+
+//================
+//   scan_allow_bidi()
+//================
+fn scan_allow_bidi(
+	lang: Lang,
+	src:&str
+) {
+	let script = Script::from_str(src);
+
+	let mut lexer = Lexer::new();
+	lexer.allow_bidi(true);
+	let (tokens, errs) = lexer.tokens(&lang, &script);
+    if !errs.is_empty() {
+        eprintln_red(format!("{:#?}", errs).as_str());
+        panic!("{}", errs[0]);
+    } else {
+		println!("{:?}", tokens);
+	}	
+}
@@ -0,0 +1,184 @@
+use indoc::indoc;
+
+use crate::util::print::eprintln_red;
+
+use crate::lang::{
+	Lang,
+	script::Script,
+	lexer::Lexer,
+    parser::Parser,
+    attrs::AttrRegistry,
+    mutability::Mutability,
+    syntax_tree::ast::*
+};
+use crate::transl::lexicon::Lexicon;
+
+//================
+//   receivers()
+//================
+#[test]
+fn receivers() {	
+	let ast = infer_en( indoc!{r#"
+    Counter {
+        n: int
+    }
+    Counter::get(): int -> self.n
+    Counter::inc() -> {
+        self.n += 1
+    }
+    Counter::reset() -> {
+        self.n = 0
+    }
+    Counter::inc_twice() -> {
+        self.inc()
+        self.inc()
+    }
+    Counter::done(): Counter -> {
+        ret self
+    }
+    "#});
+    assert_eq!(receiver(&ast, "get"), Receiver::Ref);
+    assert_eq!(receiver(&ast, "inc"), Receiver::MutRef);
+    assert_eq!(receiver(&ast, "reset"), Receiver::MutRef);
+    assert_eq!(receiver(&ast, "inc_twice"), Receiver::MutRef);
+    assert_eq!(receiver(&ast, "done"), Receiver::Owned);
+}
+
+//================
+//   receivers_ar()
+//================
+#[test]
+fn receivers_ar() {	
+	let ast = infer_ar( indoc!{r#"
+    العداد {
+        ع: صحيح
+    }
+    العداد::زد() -> {
+        هذا.ع += ١
+    }
+    "#});
+    assert_eq!(receiver(&ast, "زد"), Receiver::MutRef);
+}
+
+//================
+//   explicit_receiver()
+//================
+#[test]
+fn explicit_receiver() {	
+	let ast = infer_en( indoc!{r#"
+    Counter {
+        n: int
+    }
+    @own
+    Counter::get(): int -> self.n
+    "#});
+    assert_eq!(receiver(&ast, "get"), Receiver::Owned);
+}
+
+//================
+//   mutated_locals()
+//================
+#[test]
+fn mutated_locals() {	
+	let ast = infer_en( indoc!{r#"
+    () -> {
+        let x = 1
+        let y = 2
+        y += x
+        let l = [1]
+        l.push(2)
+    }
+    "#});
+    assert!(!is_mut(&ast, "x"));
+    assert!(is_mut(&ast, "y"));
+    assert!(is_mut(&ast, "l"));
+}
+
+//================
+//   mutated_ref_receiver()
+//================
+#[test]
+#[should_panic(expected = "`self` is changed in a method that takes it by reference, use `@mut_ref`")]
+fn mutated_ref_receiver() {	
+	infer_en( indoc!{r#"
+    Counter {
+        n: int
+    }
+    @ref
+    Counter::inc() -> {
+        self.n += 1
+    }
+    "#});
+}
+
+//================
+//   receiver()
+//================
+fn receiver(
+    ast: &Vec<ModElement>,
+    name: &str
+) -> Receiver {
+    ast.iter()
+        .find_map(|el| match el {
+            ModElement::StructImpl(struct_impl) => struct_impl.fns
+                .iter()
+                .find(|_fn| _fn.name.as_ref().is_some_and(|n| n.value.to_string() == name)),
+            _ => None
+        })
+        .and_then(|_fn| _fn.receiver)
+        .unwrap()
+}
+
+//================
+//   is_mut()
+//================
+// a local of the main function
+fn is_mut(
+    ast: &Vec<ModElement>,
+    name: &str
+) -> bool {
+    ast.iter()
+        .find_map(|el| match el {
+            ModElement::MainFn(_fn) => _fn.block
+                .iter()
+                .find_map(|el| match el {
+                    BlockElement::Decl(Decl{pattern: Pattern::Id(IdPattern{id}), is_mut, ..})
+                        if id.value.to_string() == name => Some(*is_mut),
+                    _ => None
+                }),
+            _ => None
+        })
+        .unwrap()
+}
+
+//================
+//   infer_ar()
+//================
+fn infer_ar(src: &str) -> Vec<ModElement> { infer(Lang::Ar, src)}
+//================
+//   infer_en()
+//================
+fn infer_en(src: &str) -> Vec<ModElement> { infer(Lang::En, src)}
+
+//================
+//   infer()
+//================
+fn infer(
+    lang: Lang,
+    src: &str
+) -> Vec<ModElement> {
+	let script = Script::from_str(src);
+
+	let (mut tokens, errs) = Lexer::new().tokens(&lang, &script);
+    let mut parser = Parser::new();
+    let (mut ast, symtab, errs) = parser.parse(&mut tokens);
+    assert!(errs.is_empty());
+
+    let mut errs = AttrRegistry::builtin().apply(&mut ast, Lexicon::embedded());
+    errs.extend(Mutability::new().check(&mut ast));
+    if !errs.is_empty() {
+        eprintln_red(format!("{:#?}", errs).as_str());
+        panic!("{}", errs[0]);
+    }
+    ast
+}
@@ -0,0 +1,202 @@
+use indoc::indoc;
+
+use crate::util::print::eprintln_red;
+
+use crate::lang::{
+	Lang,
+	script::Script,
+	lexer::Lexer,
+    parser::Parser,
+    attrs::AttrRegistry,
+    mutability::Mutability,
+    ownership::Ownership,
+    token::TokenValue,
+    syntax_tree::ast::*
+};
+use crate::transl::lexicon::Lexicon;
+
+//================
+//   reused_value_is_cloned()
+//================
+#[test]
+fn reused_value_is_cloned() {	
+	let ast = lower_en( indoc!{r#"
+    () -> {
+        let a = [1]
+        let b = a
+        println(a)
+    }
+    "#});
+    assert_eq!(clones(&ast), 1);
+}
+
+//================
+//   reused_value_is_cloned_ar()
+//================
+#[test]
+fn reused_value_is_cloned_ar() {	
+	let ast = lower_ar( indoc!{r#"
+    () -> {
+        ليكن س = "ن"
+        ليكن ص = س
+        اطبع_سطر(س)
+    }
+    "#});
+    assert_eq!(clones(&ast), 1);
+}
+
+//================
+//   last_use_is_moved()
+//================
+#[test]
+fn last_use_is_moved() {	
+	let ast = lower_en( indoc!{r#"
+    () -> {
+        let a = [1]
+        println(a)
+        let b = a
+    }
+    "#});
+    assert_eq!(clones(&ast), 0);
+}
+
+//================
+//   copy_value_is_not_cloned()
+//================
+#[test]
+fn copy_value_is_not_cloned() {	
+	let ast = lower_en( indoc!{r#"
+    () -> {
+        let n = 1
+        let m = n
+        let l: [int] = [n, m]
+        let k = l[0]
+        println(n)
+    }
+    "#});
+    assert_eq!(clones(&ast), 0);
+}
+
+//================
+//   moved_in_loop()
+//================
+#[test]
+fn moved_in_loop() {	
+	let ast = lower_en( indoc!{r#"
+    take(l: [int]) -> println(l)
+
+    () -> {
+        let a = [1]
+        for i in 0..3 {
+            take(a)
+        }
+    }
+    "#});
+    assert_eq!(clones(&ast), 1);
+}
+
+//================
+//   self_field_is_cloned()
+//================
+#[test]
+fn self_field_is_cloned() {	
+	let ast = lower_en( indoc!{r#"
+    Person {
+        name: str
+        age: int
+    }
+    Person::name(): str -> self.name
+    Person::age(): int -> self.age
+    "#});
+    assert_eq!(clones(&ast), 1);
+}
+
+//================
+//   read_only_strs()
+//================
+#[test]
+fn read_only_strs() {	
+	let ast = lower_en( indoc!{r#"
+    () -> {
+        let s = "a"
+        s.push_str("b")
+        println("c")
+    }
+    "#});
+    let slices = ["b", "c"]
+        .iter()
+        .all(|v| debug(&ast).contains(&format!("&{:?}", TokenValue::Str(v.to_string()))));
+    assert!(slices);
+    assert!(!debug(&ast).contains(&format!("&{:?}", TokenValue::Str("a".to_string()))));
+}
+
+//================
+//   used_after_own()
+//================
+#[test]
+#[should_panic(expected = "`c` is given to `done`, which owns it, and can not be used after it")]
+fn used_after_own() {	
+	lower_en( indoc!{r#"
+    Counter {
+        n: int
+    }
+    @own
+    Counter::done(): int -> self.n
+
+    () -> {
+        let c = Counter { n: 1 }
+        let n = c.done()
+        println(c.n)
+    }
+    "#});
+}
+
+//================
+//   debug()
+//================
+fn debug(ast: &Vec<ModElement>) -> String {
+    format!("{:?}", ast)
+}
+
+//================
+//   clones()
+//================
+fn clones(ast: &Vec<ModElement>) -> usize {
+    debug(ast).matches(".clone()").count()
+}
+
+//================
+//   lower_ar()
+//================
+fn lower_ar(src: &str) -> Vec<ModElement> { lower(Lang::Ar, src)}
+//================
+//   lower_en()
+//================
+fn lower_en(src: &str) -> Vec<ModElement> { lower(Lang::En, src)}
+
+//================
+//   lower()
+//================
+fn lower(
+    lang: Lang,
+    src: &str
+) -> Vec<ModElement> {
+	let script = Script::from_str(src);
+
+	let (mut tokens, errs) = Lexer::new().tokens(&lang, &script);
+    let mut parser = Parser::new();
+    let (mut ast, symtab, errs) = parser.parse(&mut tokens);
+    if !errs.is_empty() {
+        eprintln_red(format!("{:#?}", errs).as_str());
+        panic!("parse error: {}", errs[0]);
+    }
+
+    let mut errs = AttrRegistry::builtin().apply(&mut ast, Lexicon::embedded());
+    errs.extend(Mutability::new().check(&mut ast));
+    errs.extend(Ownership::new().lower(&mut ast));
+    if !errs.is_empty() {
+        eprintln_red(format!("{:#?}", errs).as_str());
+        panic!("{}", errs[0]);
+    }
+    ast
+}
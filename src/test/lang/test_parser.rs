@@ -525,6 +525,445 @@ fn struct_add_en() {
 }
 
 
+//================
+//   struct_defaults()
+//================
+#[test]
+fn struct_defaults() {
+	parse_en( indoc!{r#"
+    Conf {
+        host: str = "localhost"
+        port: u16 = 8080
+        user: str?
+    }
+    () -> {
+        port := 80
+        c1 := Conf { port, }
+        c2 := Conf { user: Some("me"), ..c1 }
+        c3 := Conf { ..c2 }
+        println(c3)
+    }
+    "#});
+}
+
+//================
+//   struct_defaults_ar()
+//================
+#[test]
+fn struct_defaults_ar() {
+	parse_ar( indoc!{r#"
+    النقطة {
+        س: صحيح = ٠
+        ص: صحيح = ٠
+        الاسم: سلسلة؟
+    }
+    () -> {
+        س := ١
+        ن := النقطة { س، ص: ٢ }
+        م := النقطة { ..ن }
+        اطبع(م)
+    }
+    "#});
+}
+
+//================
+//   struct_lone_shorthand()
+//================
+#[test]
+fn struct_lone_shorthand() {
+	parse_en( indoc!{r#"
+    Point {
+        x: int = 0
+        y: int
+    }
+    () -> {
+        y := 1
+        p := Point { y }
+        while y < 2 { y }
+        println(p)
+    }
+    "#});
+}
+
+//================
+//   visibility_on_decl()
+//================
+#[test]
+#[should_panic(expected = "a visibility can only be written on a function, a struct, a method or a field")]
+fn visibility_on_decl() {
+	parse_en( indoc!{r#"
+    pub x := 1
+    "#});
+}
+
+//================
+//   generic_fn()
+//================
+#[test]
+fn generic_fn() {
+	parse_en( indoc!{r#"
+    first<T: Clone + Display>(l: [T]): T? -> {
+        l.first()
+    }
+
+    ()-> {
+        x := first::<int>([1, 2, 3])
+    }
+    "#});
+}
+
+//================
+//   generic_struct()
+//================
+#[test]
+fn generic_struct() {
+	parse_en( indoc!{r#"
+    Stack<T> {
+        items: [T]
+    }
+
+    Stack::push(x: T) -> {
+        self.items.push(x)
+    }
+
+    ()-> {
+        let s: Stack<int> = Stack::<int> { items: [] }
+    }
+    "#});
+}
+
+//================
+//   generic_fn_ar()
+//================
+#[test]
+fn generic_fn_ar() {
+	parse_ar( indoc!{r#"
+    اول<ع: قابل_للنسخ>(ق: [ع]): ع؟ -> {
+        ق.first()
+    }
+    "#});
+}
+
+//================
+//   map()
+//================
+#[test]
+fn map() {
+	parse_en( indoc!{r#"
+    count(words: [str]): [str: int] -> {
+        let counts: [str: int] = [:]
+        for w in words {
+            counts[w] = counts[w] + 1
+        }
+        counts
+    }
+
+    ()-> {
+        ages := ["ali": 30, "sara": 25]
+        let sorted: sorted_map<str, int> = ["b": 2, "a": 1]
+        for (name, age) in ages {
+            println(name)
+        }
+    }
+    "#});
+}
+
+//================
+//   map_ar()
+//================
+#[test]
+fn map_ar() {
+	parse_ar( indoc!{r#"
+    ()-> {
+        اعمار := [«علي»: ٣٠, «سارة»: ٢٥]
+        ليكن ق: قاموس<سلسلة, صحيح> = [:]
+        اطبع_سطر(اعمار[«علي»])
+    }
+    "#});
+}
+
+//================
+//   sized_numbers()
+//================
+#[test]
+fn sized_numbers() {
+	parse_en( indoc!{r#"
+    hash(data: [byte]): u64 -> {
+        let h: u64 = 14695981039346656037
+        for b in data {
+            h = (h ^ b as u64) * 1099511628211
+        }
+        h
+    }
+    "#});
+}
+
+//================
+//   cast_ar()
+//================
+#[test]
+fn cast_ar() {
+	parse_ar( indoc!{r#"
+    ()-> {
+        ليكن س: صحيح٦٤ = ٣ كنوع صحيح٦٤ + ١
+        ليكن ع: عائم٦٤ = س كنوع عائم٦٤
+    }
+    "#});
+}
+
+//================
+//   higher_order_fn()
+//================
+#[test]
+fn higher_order_fn() {
+	parse_en( indoc!{r#"
+    apply(f: fn(int): int, x: int): int -> f(x)
+
+    adder(n: int): fn(int): int -> (x) -> x + n
+
+    ()-> {
+        add2 := adder(2)
+        println(apply(add2, 1))
+        evens := [1, 2, 3, 4].filter((x) -> x % 2 == 0).map((x) -> x * 10)
+    }
+    "#});
+}
+
+//================
+//   higher_order_fn_ar()
+//================
+#[test]
+fn higher_order_fn_ar() {
+	parse_ar( indoc!{r#"
+    طبق(د: دالة(صحيح): صحيح, س: صحيح): صحيح -> د(س)
+
+    ()-> {
+        ن := ٢
+        اطبع_سطر(طبق((س) -> س * ن, ٣))
+    }
+    "#});
+}
+
+//================
+//   pipe_forward()
+//================
+#[test]
+fn pipe_forward() {
+	parse_en( indoc!{r#"
+    ()-> {
+        total := [1, 2, 3]
+            |> .map((x) -> x * 2)
+            |> sum
+            |> clamp(0, 10)
+            |> ((x) -> x + 1)
+        println(total |> to_str)
+        y := "  seen  " |> .trim() |> text.upper
+    }
+    "#});
+}
+
+//================
+//   pipe_forward_missing_call()
+//================
+#[test]
+#[should_panic(expected = "expecting a function, a call or a lambda after `|>`")]
+fn pipe_forward_missing_call() {
+	parse_en( indoc!{r#"
+    ()-> {
+        x := 1 |> 2
+    }
+    "#});
+}
+
+//================
+//   range()
+//================
+#[test]
+fn range() {
+	parse_en( indoc!{r#"
+    ()-> {
+        for i in 0..10 {
+            println(i)
+        }
+        for i in 1..=n + 1 {
+            println(i)
+        }
+        l := [1, 2, 3, 4, 5]
+        head := l[..2]
+        tail := l[2..]
+        mid := l[1..=3]
+        all := l[..]
+        match x {
+            ..0 => println("negative")
+            0 => println("zero")
+            1..=9 => println("digit")
+            10.. => println("more")
+        }
+    }
+    "#});
+}
+
+//================
+//   range_ar()
+//================
+#[test]
+fn range_ar() {
+	parse_ar( indoc!{r#"
+    ()-> {
+        لكل ع في ٠..١٠ {
+            اطبع_سطر(ع)
+        }
+        ق := [١]
+        ر := ق[١..]
+    }
+    "#});
+}
+
+//================
+//   inclusive_range_without_end()
+//================
+#[test]
+#[should_panic(expected = "an inclusive range `..=` needs an end")]
+fn inclusive_range_without_end() {
+	parse_en( indoc!{r#"
+    ()-> {
+        l := [1, 2, 3]
+        x := l[1..=]
+    }
+    "#});
+}
+
+//================
+//   arm_guard_or_binding()
+//================
+#[test]
+fn arm_guard_or_binding() {
+	parse_en( indoc!{r#"
+    ()-> {
+        match x {
+            0 | 1 => println("bit")
+            n @ 2..=9 if n % 2 == 0 => println("even digit")
+            n if n < 0 => println("negative")
+            _ => println("other")
+        }
+    }
+    "#});
+}
+
+//================
+//   arm_guard_or_binding_ar()
+//================
+#[test]
+fn arm_guard_or_binding_ar() {
+	parse_ar( indoc!{r#"
+    ()-> {
+        طابق س {
+            ٠ او ١ => اطبع_سطر(«ثنائي»)
+            ن @ ٢..=٩ اذا ن > ٥ => اطبع_سطر(ن)
+            _ => اطبع_سطر(«غير ذلك»)
+        }
+    }
+    "#});
+}
+
+//================
+//   or_outside_patterns_ar()
+//================
+// `او` is an alternative in a pattern and an ordinary id elsewhere
+#[test]
+fn or_outside_patterns_ar() {
+	parse_ar( indoc!{r#"
+    ()-> {
+        او := ١
+        طابق او {
+            ٠ او ١ => اطبع_سطر(او)
+            _ => اطبع_سطر(«غير ذلك»)
+        }
+    }
+    "#});
+}
+
+//================
+//   early_ret()
+//================
+#[test]
+fn early_ret() {
+	parse_en( indoc!{r#"
+    find(l: [int], x: int): int -> {
+        for i in 0..l.len() {
+            if l[i] == x { ret i }
+        }
+        ret -1
+    }
+    log(msg: str) -> {
+        if msg == "" { ret }
+        println(msg)
+    }
+    "#});
+}
+
+//================
+//   early_ret_ar()
+//================
+#[test]
+fn early_ret_ar() {
+	parse_ar( indoc!{r#"
+    موجب(س: صحيح): منطقي -> {
+        اذا س > ٠ { ارجع صواب }
+        ارجع خطا
+    }
+    "#});
+}
+
+//================
+//   let_in_and_where()
+//================
+#[test]
+fn let_in_and_where() {
+	parse_en( indoc!{r#"
+    area(r: float): float -> pi * sq(r)
+        where pi = 3.14
+    hypot(a: float, b: float): float -> {
+        let s = sq(a) + sq(b) in s.sqrt()
+    } where {
+        sq = (x) -> x * x
+    }
+    () -> {
+        let x = 2 in println(x * x)
+        y := let (a, b) = (1, 2) in a + b
+    }
+    "#});
+}
+
+//================
+//   let_in_and_where_ar()
+//================
+#[test]
+fn let_in_and_where_ar() {
+	parse_ar( indoc!{r#"
+    مساحة(ن: عائم): عائم -> ط * ن * ن
+        حيث ط = ٣,١٤
+    () -> {
+        ليكن س = ٢ في اطبع_سطر(س * س)
+    }
+    "#});
+}
+
+//================
+//   attr_args()
+//================
+#[test]
+fn attr_args() {
+	parse_en( indoc!{r#"
+    @route("/users", method: "GET")
+    users() -> "[]"
+    @web_server
+    () -> {
+        data: "."
+    }
+    "#});
+}
+
 //================
 //   parse_ar()
 //================
@@ -545,11 +984,12 @@ fn parse(
 
 	let (mut tokens, errs) = Lexer::new().tokens(&lang, &script);
     let mut parser = Parser::new();
+    parser.lang(&lang);
     let (ast, symtab, errs) = parser.parse(&mut tokens);
 	println!("{:#?}", ast);
     if !errs.is_empty() {
         eprintln_red(format!("{:#?}", errs).as_str());
-        panic!();
+        panic!("{}", errs[0]);
     }
 	// assert_eq!(errs.len(), 0 );
 }
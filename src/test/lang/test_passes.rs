@@ -0,0 +1,297 @@
+use indoc::indoc;
+
+use crate::lang::{
+	Lang,
+	script::Script,
+	lexer::Lexer,
+    parser::Parser,
+    hir::{
+        Hir,
+        HirLowering
+    },
+    passes::{
+        PassOptions,
+        Pipeline
+    },
+    error::Error,
+    syntax_tree::ast::*
+};
+
+//================
+//   const_fold_ar()
+//================
+#[test]
+fn const_fold_ar() {
+	let (hir, warnings) = run("رئيسي.س", Lang::Ar, PassOptions::default(), indoc!{r#"
+    () -> {
+        ليكن ن = ١ + ٢ * ٣
+        ليكن ب = ن > ١ - ١
+        اطبع_سطر(ن)
+    }
+    "#});
+    assert!(warnings.is_empty());
+    assert_eq!(decl_values(&hir), vec!["7", "(ن > 0)"]);
+}
+
+//================
+//   const_fold_neg()
+//================
+#[test]
+fn const_fold_neg() {
+	let (hir, _) = run("main.seen", Lang::En, PassOptions::default(), indoc!{r#"
+    () -> {
+        let a = 2 - 5
+        let b = -a
+        let c = 1 / 0
+        let d = 2147483647 + 1
+        println(a)
+    }
+    "#});
+    assert_eq!(decl_values(&hir), vec!["-3", "-a", "(1 / 0)", "(2147483647 + 1)"]);
+}
+
+//================
+//   const_fold_typed()
+//================
+#[test]
+fn const_fold_typed() {
+	let (hir, _) = run("main.seen", Lang::En, PassOptions::default(), indoc!{r#"
+    () -> {
+        let a: i64 = 2147483647 + 1
+        let b: u8 = 200 + 100
+        let c: u8 = 1 - 2
+        let d: i8 = 100 + 27
+        println(a)
+    }
+    "#});
+    assert_eq!(decl_values(&hir), vec!["2147483648", "(200 + 100)", "(1 - 2)", "127"]);
+}
+
+//================
+//   dead_branch()
+//================
+#[test]
+fn dead_branch() {
+	let (hir, _) = run("main.seen", Lang::En, PassOptions::default(), indoc!{r#"
+    () -> {
+        if 1 > 2 {
+            println(1)
+        }
+        if false {
+            println(2)
+        } else if true {
+            println(3)
+        } else {
+            println(4)
+        }
+        println(5)
+    }
+    "#});
+    let main = main_fn(&hir);
+    assert_eq!(main.block.len(), 2);
+    let BlockElement::Expr(Expr::Block(block)) = &main.block[0] else { panic!("{:?}", main.block) };
+    assert_eq!(block.len(), 1);
+}
+
+//================
+//   dead_arm()
+//================
+#[test]
+fn dead_arm() {
+	let (hir, _) = run("main.seen", Lang::En, PassOptions::default(), indoc!{r#"
+    () -> {
+        match 1 + 1 {
+            1 => println(1)
+            2 | 3 => println(2)
+            _ => println(3)
+        }
+    }
+    "#});
+    let main = main_fn(&hir);
+    let block = match &main.block[0] {
+        BlockElement::Expr(Expr::Block(block)) => block,
+        BlockElement::Ret(Ret{expr: Some(expr), ..}) => match &**expr {
+            Expr::Block(block) => block,
+            x => panic!("{:?}", x)
+        },
+        x => panic!("{:?}", x)
+    };
+    assert_eq!(format!("{:?}", block), format!("{:?}", arm_block(2)));
+}
+
+//================
+//   unused_fn()
+//================
+#[test]
+fn unused_fn() {
+	let (hir, warnings) = run("main.seen", Lang::En, PassOptions::default(), indoc!{r#"
+    used() -> println(1)
+    only_by_unused() -> println(2)
+    unused() -> only_by_unused()
+    pub exported() -> println(3)
+    () -> used()
+    "#});
+    let names = hir.items
+        .iter()
+        .filter_map(|el| match el {
+            ModElement::Fn(Fn{name: Some(name), ..}) => Some(name.value.to_string()),
+            _ => None
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["used", "exported"]);
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings.iter().all(|w| w.is_warning()));
+}
+
+//================
+//   unused_fn_cycle()
+//================
+#[test]
+fn unused_fn_cycle() {
+	let (hir, warnings) = run("main.seen", Lang::En, PassOptions::default(), indoc!{r#"
+    ping(n: int) -> pong(n - 1)
+    pong(n: int) -> ping(n - 1)
+    countdown(n: int) -> countdown(n - 1)
+    () -> println(1)
+    "#});
+    assert_eq!(hir.items.len(), 1);
+    assert_eq!(warnings.len(), 3);
+}
+
+//================
+//   unreachable()
+//================
+#[test]
+fn unreachable() {
+	let (hir, warnings) = run("main.seen", Lang::En, PassOptions::default(), indoc!{r#"
+    f(x: int): int -> {
+        ret x
+        println(x)
+        x
+    }
+    () -> f(1)
+    "#});
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].to_string().starts_with("warning:"));
+}
+
+//================
+//   disabled_pass()
+//================
+#[test]
+fn disabled_pass() {
+	let options = PassOptions {
+        disabled: vec!["const_fold".to_string(), "unused_fn".to_string()],
+        dump_ir: false
+    };
+	let (hir, _) = run("main.seen", Lang::En, options, indoc!{r#"
+    unused() -> println(1)
+    () -> {
+        let n = 1 + 2
+        println(n)
+    }
+    "#});
+    assert_eq!(decl_values(&hir), vec!["(1 + 2)"]);
+    assert_eq!(hir.items.len(), 2);
+}
+
+//================
+//   unknown_pass()
+//================
+#[test]
+fn unknown_pass() {
+	let options = PassOptions {
+        disabled: vec!["inline".to_string()],
+        dump_ir: false
+    };
+    assert!(Pipeline::new(options).check_options().is_err());
+}
+
+//================
+//   arm_block()
+//================
+// the block of the second arm when nothing is folded
+fn arm_block(arm: usize) -> Vec<BlockElement> {
+	let options = PassOptions {
+        disabled: vec!["const_fold".to_string(), "dead_branch".to_string()],
+        dump_ir: false
+    };
+	let (hir, _) = run("main.seen", Lang::En, options, indoc!{r#"
+    () -> {
+        match 1 + 1 {
+            1 => println(1)
+            2 | 3 => println(2)
+            _ => println(3)
+        }
+    }
+    "#});
+    let main = main_fn(&hir);
+    let expr = match &main.block[0] {
+        BlockElement::Expr(expr) => expr,
+        BlockElement::Ret(Ret{expr: Some(expr), ..}) => &**expr,
+        x => panic!("{:?}", x)
+    };
+    let Expr::Match(_match) = expr else { panic!("{:?}", expr) };
+    _match.arms[arm - 1].block.clone()
+}
+
+//================
+//   main_fn()
+//================
+fn main_fn(hir: &Hir) -> &Fn {
+    hir.items
+        .iter()
+        .find_map(|el| match el {
+            ModElement::MainFn(_fn) => Some(_fn),
+            _ => None
+        })
+        .unwrap()
+}
+
+//================
+//   decl_values()
+//================
+fn decl_values(hir: &Hir) -> Vec<String> {
+    main_fn(hir).block
+        .iter()
+        .filter_map(|el| match el {
+            BlockElement::Decl(Decl{expr: Some(expr), ..}) => Some(show(expr)),
+            _ => None
+        })
+        .collect()
+}
+
+//================
+//   show()
+//================
+fn show(expr: &Expr) -> String {
+    match expr {
+        Expr::Bool(t)
+        | Expr::Int(t)
+        | Expr::Ref(t) => t.value.to_string(),
+        Expr::PreUniOp(UniOp{opr, op}) => format!("{:?}{}", op.value, show(opr)),
+        Expr::BinOp(BinOp{l_opr, r_opr, op}) => format!("({} {:?} {})", show(l_opr), op.value, show(r_opr)),
+        x => format!("{:?}", x)
+    }
+}
+
+//================
+//   run()
+//================
+fn run(
+    path: &str,
+    lang: Lang,
+    options: PassOptions,
+    src: &str
+) -> (Hir, Vec<Error>) {
+	let script = Script::from_str(src);
+
+	let (mut tokens, errs) = Lexer::new().tokens(&lang, &script);
+    let mut parser = Parser::new();
+    let (ast, symtab, errs) = parser.parse(&mut tokens);
+    assert!(errs.is_empty());
+
+    let mut hir = HirLowering::new().lower(path, &lang, ast);
+    let warnings = Pipeline::new(options).run(path, &mut hir);
+    (hir, warnings)
+}
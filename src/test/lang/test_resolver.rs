@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use indoc::indoc;
 
 use crate::util::print::eprintln_red;
@@ -7,8 +9,13 @@ use crate::lang::{
 	script::Script,
 	lexer::Lexer,
     parser::Parser,
-    resolver::Resolver
+    syntax_tree::ast::ModElement,
+    resolver::{
+        self,
+        Resolver
+    }
 };
+use crate::transl::lexicon::Lexicon;
 
 //================
 //   fib_ar()
@@ -50,6 +57,177 @@ fn fib_en() {
 
 
 
+//================
+//   loops()
+//================
+#[test]
+fn loops() {	
+	check_loops_en( indoc!{"
+    () -> {
+        $rows: for i in 0..3 {
+            for j in 0..3 {
+                if j == i { continue $rows }
+                if j > 2 { break }
+            }
+        }
+        n := loop {
+            break 10
+        }
+    }
+    "});
+}
+
+//================
+//   loops_ar()
+//================
+#[test]
+fn loops_ar() {	
+	check_loops_ar( indoc!{"
+    () -> {
+        $خارجي: كرر {
+            بينما صواب {
+                اقطع $خارجي
+            }
+        }
+    }
+    "});
+}
+
+//================
+//   break_outside_loop()
+//================
+#[test]
+#[should_panic(expected = "`break` outside of a loop")]
+fn break_outside_loop() {	
+	check_loops_en( indoc!{"
+    () -> {
+        if true { break }
+    }
+    "});
+}
+
+//================
+//   continue_in_lambda()
+//================
+#[test]
+#[should_panic(expected = "`continue` outside of a loop")]
+fn continue_in_lambda() {	
+	check_loops_en( indoc!{"
+    () -> {
+        for x in xs {
+            f := () -> continue
+        }
+    }
+    "});
+}
+
+//================
+//   undeclared_label()
+//================
+#[test]
+#[should_panic(expected = "undeclared label `$outer`")]
+fn undeclared_label() {	
+	check_loops_en( indoc!{"
+    () -> {
+        while true {
+            break $outer
+        }
+    }
+    "});
+}
+
+//================
+//   break_value_in_for()
+//================
+#[test]
+#[should_panic(expected = "only `loop` can be left with a value")]
+fn break_value_in_for() {	
+	check_loops_en( indoc!{"
+    () -> {
+        for x in xs {
+            break x
+        }
+    }
+    "});
+}
+
+//================
+//   visibility()
+//================
+#[test]
+fn visibility() {	
+	check_visibility_en(
+        indoc!{"
+        pub add(a: int, b: int): int -> a + b
+        sub(a: int, b: int): int -> a - b
+        internal Point {
+            pub x: int
+            internal y: int = 0
+        }
+        "},
+        indoc!{"
+        lib := import(\"lib.seen\")
+        () -> {
+            p := lib.Point { x: lib.add(1, 2) }
+            println(p)
+        }
+        "}
+    );
+}
+
+//================
+//   visibility_ar()
+//================
+#[test]
+fn visibility_ar() {	
+	check_visibility_ar(
+        indoc!{"
+        عام جمع(ا: صحيح، ب: صحيح): صحيح -> ا + ب
+        "},
+        indoc!{"
+        مك := احضر(«مكتبة.س»)
+        () -> اطبع_سطر(مك.جمع(١، ٢))
+        "}
+    );
+}
+
+//================
+//   private_fn()
+//================
+#[test]
+#[should_panic(expected = "`sub` is private to module `lib`")]
+fn private_fn() {	
+	check_visibility_en(
+        indoc!{"
+        sub(a: int, b: int): int -> a - b
+        "},
+        indoc!{"
+        lib := import(\"lib.seen\")
+        () -> println(lib.sub(2, 1))
+        "}
+    );
+}
+
+//================
+//   private_field()
+//================
+#[test]
+#[should_panic(expected = "`Point` has private fields, it can only be built in module `lib`")]
+fn private_field() {	
+	check_visibility_en(
+        indoc!{"
+        pub Point {
+            pub x: int
+            y: int = 0
+        }
+        "},
+        indoc!{"
+        lib := import(\"lib.seen\")
+        () -> println(lib.Point { x: 1 })
+        "}
+    );
+}
+
 //================
 //   resolve_ar()
 //================
@@ -70,6 +248,7 @@ fn resolve(
 
 	let (mut tokens, errs) = Lexer::new().tokens(&lang, &script);
     let mut parser = Parser::new();
+    parser.lang(&lang);
     let (ast, symtab, errs) = parser.parse(&mut tokens);
     
     let mut resolver = Resolver::new();
@@ -81,3 +260,85 @@ fn resolve(
     }
 	// assert_eq!(errs.len(), 0 );
 }
+
+//================
+//   check_loops_ar()
+//================
+fn check_loops_ar(src: &str) { check_loops(Lang::Ar, src)}
+//================
+//   check_loops_en()
+//================
+fn check_loops_en(src: &str) { check_loops(Lang::En, src)}
+
+//================
+//   check_loops()
+//================
+fn check_loops(
+    lang: Lang,
+    src: &str
+) {
+	let script = Script::from_str(src);
+
+	let (mut tokens, errs) = Lexer::new().tokens(&lang, &script);
+    let mut parser = Parser::new();
+    let (ast, symtab, errs) = parser.parse(&mut tokens);
+    assert!(errs.is_empty());
+
+    let errs = Resolver::new().check_loops(&ast);
+    if !errs.is_empty() {
+        eprintln_red(format!("{:#?}", errs).as_str());
+        panic!("{}", errs[0]);
+    }
+}
+
+//================
+//   check_visibility_ar()
+//================
+fn check_visibility_ar(lib: &str, src: &str) { check_visibility(Lang::Ar, lib, src)}
+//================
+//   check_visibility_en()
+//================
+fn check_visibility_en(lib: &str, src: &str) { check_visibility(Lang::En, lib, src)}
+
+//================
+//   check_visibility()
+//================
+// `src` imports `lib`
+fn check_visibility(
+    lang: Lang,
+    lib: &str,
+    src: &str
+) {
+    let (lib_ast, src_ast) = (parse(&lang, lib), parse(&lang, src));
+    let exports = resolver::exports(&lib_ast);
+    let imports = resolver::imports(&src_ast, &lang, Lexicon::embedded())
+        .into_iter()
+        .map(|(name, _)| (name, &exports))
+        .collect::<HashMap<_, _>>();
+    assert_eq!(imports.len(), 1);
+
+    let errs = Resolver::new().check_visibility(&src_ast, &imports);
+    if !errs.is_empty() {
+        eprintln_red(format!("{:#?}", errs).as_str());
+        panic!("{}", errs[0]);
+    }
+}
+
+//================
+//   parse()
+//================
+fn parse(
+    lang: &Lang,
+    src: &str
+) -> Vec<ModElement> {
+	let script = Script::from_str(src);
+
+	let (mut tokens, errs) = Lexer::new().tokens(lang, &script);
+    let mut parser = Parser::new();
+    let (ast, symtab, errs) = parser.parse(&mut tokens);
+    if !errs.is_empty() {
+        eprintln_red(format!("{:#?}", errs).as_str());
+        panic!("parse error: {}", errs[0]);
+    }
+    ast
+}
@@ -8,7 +8,7 @@ use crate::lang::{
 	lexer::Lexer,
     parser::Parser,
     resolver::Resolver,
-    infenrence::Inference,
+    inference::Inference,
     type_checker::TypeChecker
 };
 
@@ -47,6 +47,181 @@ fn fib_en() {
 }
 
 
+//================
+//   literal_in_range()
+//================
+#[test]
+fn literal_in_range() {	
+	check_literals_en( indoc!{"
+    () -> {
+        let a: u8 = 255
+        let b: i8 = -128
+        let c: [u16] = [0, 65535]
+        let d: u64 = 18446744073709551615
+    }
+    "});
+}
+
+//================
+//   literal_overflow()
+//================
+#[test]
+#[should_panic(expected = "literal out of range for `u8`: 256")]
+fn literal_overflow() {	
+	check_literals_en( indoc!{"
+    () -> {
+        let a: u8 = 256
+    }
+    "});
+}
+
+//================
+//   negative_unsigned_literal()
+//================
+#[test]
+#[should_panic(expected = "literal out of range for `usize`: -1")]
+fn negative_unsigned_literal() {	
+	check_literals_en( indoc!{"
+    f(): usize -> -1
+    "});
+}
+
+//================
+//   literal_overflow_ar()
+//================
+#[test]
+#[should_panic(expected = "literal out of range for `بايت`: ٣٠٠")]
+fn literal_overflow_ar() {	
+	check_literals( Lang::Ar, indoc!{"
+    () -> {
+        ليكن ب: [بايت] = [٣٠٠]
+    }
+    "});
+}
+
+//================
+//   ret_matches_fn()
+//================
+#[test]
+fn ret_matches_fn() {	
+	check_literals_en( indoc!{"
+    sign(n: int): int -> {
+        if n < 0 { ret -1 }
+        if n == 0 { ret 0 }
+        1
+    }
+    "});
+}
+
+//================
+//   ret_value_in_unit_fn()
+//================
+#[test]
+#[should_panic(expected = "`ret` with a value in a function that returns `()`")]
+fn ret_value_in_unit_fn() {	
+	check_literals_en( indoc!{"
+    f(): () -> {
+        ret 1
+    }
+    "});
+}
+
+//================
+//   ret_without_value()
+//================
+#[test]
+#[should_panic(expected = "`ret` without a value in a function that returns `int`")]
+fn ret_without_value() {	
+	check_literals_en( indoc!{"
+    f(n: int): int -> {
+        if n < 0 { ret }
+        n
+    }
+    "});
+}
+
+//================
+//   ret_mismatched_literal_ar()
+//================
+#[test]
+#[should_panic(expected = "mismatched types, the function returns `صحيح` but `ret` is given a string")]
+fn ret_mismatched_literal_ar() {	
+	check_literals( Lang::Ar, indoc!{"
+    د(س: صحيح): صحيح -> {
+        اذا س > ٠ { ارجع «موجب» }
+        س
+    }
+    "});
+}
+
+//================
+//   ret_mismatched_param()
+//================
+#[test]
+#[should_panic(expected = "mismatched types, the function returns `[int]` but `ret` is given `s` of type `str`")]
+fn ret_mismatched_param() {	
+	check_literals_en( indoc!{"
+    f(s: str, l: [int]): [int] -> {
+        if s == \"\" { ret s }
+        l
+    }
+    "});
+}
+
+//================
+//   ret_mismatched_comparison()
+//================
+#[test]
+#[should_panic(expected = "mismatched types, the function returns `int` but `ret` is given a boolean")]
+fn ret_mismatched_comparison() {	
+	check_literals_en( indoc!{"
+    f(n: int): int -> {
+        if n > 0 { ret n > 1 }
+        n
+    }
+    "});
+}
+
+//================
+//   ret_shadowed_param()
+//================
+#[test]
+fn ret_shadowed_param() {	
+	check_literals_en( indoc!{"
+    f(n: str): int -> {
+        let n = 1
+        ret n
+    }
+    "});
+}
+
+//================
+//   ret_sized_alias()
+//================
+#[test]
+fn ret_sized_alias() {	
+	check_literals_en( indoc!{"
+    f(n: i32): int -> {
+        if n > 0 { ret n }
+        n
+    }
+    "});
+}
+
+//================
+//   ret_block_local()
+//================
+#[test]
+fn ret_block_local() {	
+	check_literals_en( indoc!{"
+    f(n: int): int -> {
+        if n > 0 {
+            let n: str = \"positive\"
+        }
+        ret n
+    }
+    "});
+}
 
 //================
 //   type_check_ar()
@@ -68,12 +243,13 @@ fn type_check(
 
 	let (mut tokens, errs) = Lexer::new().tokens(&lang, &script);
     let mut parser = Parser::new();
+    parser.lang(&lang);
     let (ast, symtab, errs) = parser.parse(&mut tokens);
     let mut resolver = Resolver::new();
     let (symtab, restab, errs) = resolver.resolve(symtab);
     let mut inference = Inference::new();
-    let (ast, restab, errs) = inferences.infer(ast, restab);
-    let mut type_check = TypeChecker::new();
+    let (ast, restab, errs) = inference.infer(ast, restab);
+    let mut type_checker = TypeChecker::new();
     let (ast, restab, errs) = type_checker.check(ast, restab);
     if !errs.is_empty() {
         eprintln_red(format!("{:#?}", errs).as_str());
@@ -81,3 +257,28 @@ fn type_check(
     }
 	// assert_eq!(errs.len(), 0 );
 }
+
+//================
+//   check_literals_en()
+//================
+fn check_literals_en(src: &str) { check_literals(Lang::En, src)}
+
+//================
+//   check_literals()
+//================
+fn check_literals(
+    lang: Lang,
+    src: &str
+) {
+	let script = Script::from_str(src);
+
+	let (mut tokens, errs) = Lexer::new().tokens(&lang, &script);
+    let mut parser = Parser::new();
+    parser.lang(&lang);
+    let (ast, symtab, errs) = parser.parse(&mut tokens);
+    let errs = TypeChecker::new().check_literals(&ast);
+    if !errs.is_empty() {
+        eprintln_red(format!("{:#?}", errs).as_str());
+        panic!("{}", errs[0]);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod test_rs_gen;
+pub mod test_source_map;
+pub mod test_cargo_toml;
@@ -0,0 +1,46 @@
+use crate::project::conf::Profile;
+use crate::target::rust::cargo_toml::Profiles;
+
+//================
+//   profiles()
+//================
+#[test]
+fn profiles() {
+    let mut release = Profile::new("release");
+    release.set("opt-level", "3");
+    release.set("panic", "\"abort\"");
+    let mut fast = Profile::new("fast");
+    fast.set("inherits", "\"release\"");
+    fast.set("codegen-units", "1");
+    let mut small = Profile::new("small");
+    small.set("opt-level", "\"z\"");
+    let mut dev = Profile::new("dev");
+    dev.set("debug", "false");
+
+    let mut profiles = Profiles::new();
+    profiles.set(&release);
+    profiles.set(&fast);
+    profiles.set(&small);
+    profiles.set(&dev);
+
+    let mut res = String::new();
+    profiles.write(&mut res);
+    assert_eq!(res, concat!(
+        "[profile.release]\n",
+        "lto = true\n",
+        "opt-level = 3\n",
+        "strip = true\n",
+        "panic = \"abort\"\n",
+        "\n",
+        "[profile.fast]\n",
+        "inherits = \"release\"\n",
+        "codegen-units = 1\n",
+        "\n",
+        "[profile.small]\n",
+        "inherits = \"release\"\n",
+        "opt-level = \"z\"\n",
+        "\n",
+        "[profile.dev]\n",
+        "debug = false\n",
+    ));
+}
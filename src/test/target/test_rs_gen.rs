@@ -0,0 +1,294 @@
+use std::{
+    fs,
+    path::PathBuf,
+    process::Command
+};
+
+use indoc::indoc;
+
+use crate::lang::{
+    Lang,
+    script::Script,
+    lexer::Lexer,
+    parser::Parser,
+    attrs::AttrRegistry,
+    mutability::Mutability,
+    ownership::Ownership,
+    hir::HirLowering
+};
+use crate::project::conf::Conf;
+use crate::target::{
+    build::BuildDir,
+    rust::{
+        rs_gen::{Rust, ModStructs},
+        cargo_toml::CargoToml
+    }
+};
+use crate::transl::{
+    transl::Transl,
+    lexicon::Lexicon
+};
+
+//================
+//   map_index()
+//================
+#[test]
+fn map_index() {
+	let rs = generate(Lang::En, "map_index", indoc!{r#"
+    count(ns: [int]): [int: int] -> {
+        let counts: [int: int] = [:]
+        for n in ns {
+            counts[n] = 0
+        }
+        counts[1] = counts[1] + 1
+        counts[2] += 1
+        counts
+    }
+
+    ()-> {
+        total := count([1, 2])[1]
+        println(total)
+    }
+    "#});
+    assert!(rs.contains("counts.insert(n, 0)"));
+    assert!(rs.contains("counts.insert(1, counts[&1] + 1)"));
+    assert!(rs.contains("*counts.get_mut(&2).unwrap() += 1"));
+    assert!(rs.contains("[&1]"));
+    compile("map_index", &rs);
+}
+
+//================
+//   iter_chain()
+//================
+#[test]
+fn iter_chain() {
+    let rs = generate(Lang::En, "iter_chain", indoc!{r#"
+    ()-> {
+        l := [1, 2, 3, 4]
+        evens := l.map((x) -> x * 2).filter((x) -> x > 2)
+        println(evens.len())
+        println(l.filter((x) -> x > 2).len())
+    }
+    "#});
+    assert!(rs.contains("l.iter().cloned().map(|x| x * 2).filter(|__el| (|x| x > 2)(__el.clone())).collect::<Vec<_>>();"));
+    assert!(rs.contains(".collect::<Vec<_>>().len()"));
+    compile("iter_chain", &rs);
+}
+
+//================
+//   early_ret()
+//================
+#[test]
+fn early_ret() {
+    let rs = generate(Lang::En, "early_ret", indoc!{r#"
+    sign(n: int): int -> {
+        if n < 0 { ret -1 }
+        ret 1
+        0
+    }
+
+    ()-> {
+        println(sign(2))
+    }
+    "#});
+    assert!(rs.contains("return 1;"));
+    compile("early_ret", &rs);
+}
+
+//================
+//   web_server()
+//================
+#[test]
+fn web_server() {
+    let rs = generate(Lang::En, "web_server", indoc!{r#"
+    @web_server
+    () -> {
+        data: {
+            settings: {
+                hostname: "localhost",
+                port: 8080
+            }
+        }
+    }
+    "#});
+    assert!(rs.contains("const PORT: u32 = 8080;"));
+    assert!(rs.contains("HttpServer::new"));
+}
+
+//================
+//   struct_lone_shorthand()
+//================
+#[test]
+fn struct_lone_shorthand() {
+    let rs = generate(Lang::En, "struct_lone_shorthand", indoc!{r#"
+    Point {
+        y: int
+    }
+
+    pick(ok: bool, y: int): int -> {
+        if ok { y } else { 0 }
+    }
+
+    ()-> {
+        y := pick(true, 1)
+        p := Point { y }
+        println(p.y)
+    }
+    "#});
+    assert!(rs.contains("Point {\n        y,\n    }"));
+    compile("struct_lone_shorthand", &rs);
+}
+
+//================
+//   ownership()
+//================
+#[test]
+fn ownership() {
+    let rs = generate(Lang::En, "ownership", indoc!{r#"
+    Point {
+        name: str
+        tags: [str]
+    }
+
+    Point::label(): str -> self.name
+
+    words(): [str] -> {
+        ["a", "b"]
+    }
+
+    make(): Point -> {
+        Point { name: "p", tags: words() }
+    }
+
+    greet(name: str): str -> {
+        "hi " + name
+    }
+
+    ()-> {
+        p := make()
+        name := "x"
+        for i in 0..2 {
+            t := make().name
+            l := p.label()
+            w := words()[0]
+            s := "a b".split(" ").count()
+            println(t)
+            println(l)
+            println(w)
+            println(s)
+            println(greet(name))
+        }
+        println(greet("y"))
+        println(p.name)
+    }
+    "#});
+    assert!(rs.contains("name: &str"));
+    assert!(rs.contains("greet(&name)"));
+    assert!(rs.contains(r#"greet("y")"#));
+    assert!(rs.contains("words()[0].clone()"));
+    assert!(rs.contains(r#""a b".split(" ")"#));
+    assert!(!rs.contains("make().clone()"));
+    assert!(!rs.contains("p.label().clone()"));
+    compile("ownership", &rs);
+}
+
+//================
+//   neutral_names_ar()
+//================
+// an arabic module is generated from its HIR, the functions it declares keep their names
+#[test]
+fn neutral_names_ar() {
+    let rs = generate(Lang::Ar, "neutral_names_ar", indoc!{r#"
+    @اشتق(قابل_للمساواة)
+    النقطة {
+        س: صحيح
+    }
+
+    حول(ن: صحيح): صحيح -> ن + ١
+
+    () -> {
+        ب := صواب
+        ن := حول(١٢)
+        ل := [١، ٢].حول((س) -> س * ٢)
+        اطبع_سطر(ن)
+        اطبع_سطر(ب)
+    }
+    "#});
+    assert!(rs.contains("#[derive(PartialEq"));
+    assert!(rs.contains("fn حول(ن: i32)"));
+    assert!(rs.contains("حول(12)"));
+    assert!(rs.contains(".map("));
+    assert!(rs.contains("true"));
+    compile("neutral_names_ar", &rs);
+}
+
+//================
+//   generate()
+//================
+// the rust generated for `src` as the main module of a project in a temp dir
+fn generate(
+    lang: Lang,
+    name: &str,
+    src: &str
+) -> String {
+    let script = Script::from_str(src);
+    let (mut tokens, errs) = Lexer::new().tokens(&lang, &script);
+    assert!(errs.is_empty(), "{:?}", errs);
+    let mut parser = Parser::new();
+    parser.lang(&lang);
+    let (mut ast, _, errs) = parser.parse(&mut tokens);
+    assert!(errs.is_empty(), "{:?}", errs);
+    let mut mutability = Mutability::new();
+    let errs = mutability.check(&mut ast);
+    assert!(errs.is_empty(), "{:?}", errs);
+    let errs = Ownership::new().lower(&mut ast);
+    assert!(errs.is_empty(), "{:?}", errs);
+
+    let home = project_dir(name);
+    let _ = fs::remove_dir_all(&home);
+    let conf = Conf {
+        proj_lang: lang.clone(),
+        transl: Transl::new(&lang, Lexicon::embedded()),
+        lexicon: Lexicon::embedded().clone(),
+        data: vec![]
+    };
+    let mut build_dir = BuildDir::new(&lang, &name.to_string(), Some(home.clone()));
+    build_dir.create_dir_all();
+    let mut cargo_toml = CargoToml::new(name, &home, &conf);
+    let attrs = AttrRegistry::builtin();
+    let mod_structs = ModStructs::new();
+    let mut lowering = HirLowering::new();
+    lowering.lexicon(&conf.lexicon);
+    let hir = lowering.lower("main.seen", &lang, ast);
+    Rust::new(&mut build_dir, &mut cargo_toml, &conf.lexicon, &attrs, &mod_structs)
+        .generate(&"main.seen".to_string(), &hir, &vec![]);
+    fs::read_to_string(home.join("src").join("main.rs")).unwrap()
+}
+
+//================
+//   compile()
+//================
+// type checks the generated rust with rustc, without building a binary
+fn compile(
+    name: &str,
+    rs: &str
+) {
+    let dir = project_dir(name).join("rustc");
+    fs::create_dir_all(&dir).unwrap();
+    let main = dir.join("main.rs");
+    fs::write(&main, rs).unwrap();
+    let output = Command::new("rustc")
+        .args(["--edition", "2021", "--emit=metadata", "--crate-name", name, "--out-dir"])
+        .arg(&dir)
+        .arg(&main)
+        .output()
+        .expect("rustc is not installed");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+//================
+//   project_dir()
+//================
+fn project_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join("seen_test_rs_gen").join(name)
+}
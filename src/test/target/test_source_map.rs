@@ -0,0 +1,61 @@
+use crate::lang::token::Location;
+use crate::target::rust::source_map::{
+    SourceMap,
+    SourceMaps
+};
+
+//================
+//   lookup()
+//================
+#[test]
+fn lookup() {
+    let map = map();
+    assert_eq!(map.lookup(3, 1), None);
+    assert_eq!(map.lookup(4, 1), Some(Location::new(1, 1)));
+    assert_eq!(map.lookup(5, 13), Some(Location::new(2, 9)));
+    assert_eq!(map.lookup(5, 2), Some(Location::new(2, 5)));
+    assert_eq!(map.lookup(6, 20), Some(Location::new(2, 5)));
+    assert_eq!(map.lookup(40, 9), Some(Location::new(3, 5)));
+}
+
+//================
+//   round_trip()
+//================
+#[test]
+fn round_trip() {
+    let map = map();
+    let v = map.to_string();
+    assert_eq!(v, "رئيسي.س\n4 1 1 1\n5 9 2 5\n7 9 3 5\n");
+    assert_eq!(SourceMap::from_str(&v).unwrap().to_string(), v);
+    assert!(SourceMap::from_str("main.seen\n4 x 1 1").is_none());
+    assert!(SourceMap::from_str("main.seen\n4 1 1").is_none());
+}
+
+//================
+//   rewrite()
+//================
+#[test]
+fn rewrite() {
+    let mut maps = SourceMaps::new();
+    maps.insert("main.rs", map());
+    assert_eq!(maps.rewrite("  --> src/main.rs:5:13"), "  --> رئيسي.س:2:9");
+    assert_eq!(
+        maps.rewrite("thread 'main' panicked at src/main.rs:8:9:"),
+        "thread 'main' panicked at رئيسي.س:3:5:"
+    );
+    assert_eq!(maps.rewrite("             at ./src/main.rs:6:1"), "             at رئيسي.س:2:5");
+    assert_eq!(maps.rewrite("  --> src/other.rs:5:13"), "  --> src/other.rs:5:13");
+    assert_eq!(maps.rewrite("  --> src/main.rs:1:1"), "  --> src/main.rs:1:1");
+}
+
+//================
+//   map()
+//================
+fn map() -> SourceMap {
+    let mut map = SourceMap::new("رئيسي.س");
+    map.mark(4, 1, &Location::new(1, 1));
+    map.mark(5, 5, &Location::new(2, 1));
+    map.mark(5, 9, &Location::new(2, 5));
+    map.mark(7, 9, &Location::new(3, 5));
+    map
+}
@@ -5,8 +5,12 @@ use indoc::indoc;
 
 use crate::project::build;
 use crate::tool::cargo::Cargo;
-use crate::transl::transl::Transl;
+use crate::transl::{
+	transl::Transl,
+	lexicon::Lexicon
+};
 use crate::util::cli::Compile;
+use crate::lang::passes::PassOptions;
 use crate::util::print::eprintln_red;
 
 use crate::lang::{
@@ -88,14 +92,14 @@ fn run(
     // };
     // let lang = conf::proj_lang(&home).expect("");
     let redirect = true;
-    let transl = Transl::new(&lang);
+    let transl = Transl::new(&lang, Lexicon::embedded());
     // let proj_name = conf::proj_name(&transl, &home);
     let home = PathBuf::from("");
     let proj_name = String::from("test_run.rs");
     let build_path = build::build_path(&transl, &home, &proj_name);
     let work_dir = format!("{}", build_path.display());
-    Compile::exec(Some(home));
-    Cargo::new().run(&work_dir, &vec![], redirect);
+    Compile::exec(Some(home), &PassOptions::default());
+    Cargo::new(transl).run(&work_dir, &vec![], None, redirect);
 }
 
 
@@ -0,0 +1,2 @@
+pub mod test_cargo_msg;
+pub mod test_cargo;
\ No newline at end of file
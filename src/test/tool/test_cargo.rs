@@ -0,0 +1,26 @@
+use std::sync::mpsc;
+
+use crate::tool::cargo::{
+    read_stdout,
+    Output
+};
+
+//================
+//   program_json()
+//================
+#[test]
+fn program_json() {
+    let stdout = concat!(
+        r#"{"reason":"compiler-artifact","target":{"name":"main"}}"#, "\n",
+        r#"{"reason":"build-finished","success":true}"#, "\n",
+        r#"{"name": "demo"}"#, "\n",
+        "done\n"
+    );
+    let (tx, rx) = mpsc::channel();
+    read_stdout(stdout.as_bytes(), tx);
+    let outputs = rx.iter().collect::<Vec<_>>();
+    assert!(matches!(&outputs[0], Output::Json(_)));
+    assert!(matches!(&outputs[1], Output::Json(_)));
+    assert_eq!(outputs[2], Output::Stdout(b"{\"name\": \"demo\"}\n".to_vec()));
+    assert_eq!(outputs[3], Output::Stdout(b"done\n".to_vec()));
+}
@@ -0,0 +1,70 @@
+use crate::lang::Lang;
+use crate::tool::cargo_msg::{
+    parse,
+    Message
+};
+use crate::transl::{
+    transl::Transl,
+    lexicon::Lexicon
+};
+
+//================
+//   compiler_message()
+//================
+#[test]
+fn compiler_message() {
+    let line = r#"{"reason":"compiler-message","package_id":"main 0.1.0","target":{"name":"main"},"message":{"rendered":"error[E0308]: mismatched types\n --> src/main.rs:5:13\n","$message_type":"diagnostic","children":[],"code":{"code":"E0308","explanation":null},"level":"error","message":"mismatched types","spans":[{"byte_end":80,"byte_start":75,"column_end":18,"column_start":13,"expansion":null,"file_name":"src/main.rs","is_primary":true,"label":"expected `i32`","line_end":5,"line_start":5,"suggested_replacement":null,"suggestion_applicability":null,"text":[]}]}}"#;
+    let Some(Message::CompilerMessage{message}) = parse(line) else { panic!() };
+    assert!(!message.is_warning());
+    let span = message.primary_span().unwrap();
+    assert_eq!((span.file_name.as_str(), span.line_start, span.column_start), ("src/main.rs", 5, 13));
+}
+
+//================
+//   macro_span()
+//================
+// a message inside `println!` points at the line of the generated crate that calls it
+#[test]
+fn macro_span() {
+    let line = r#"{"reason":"compiler-message","package_id":"main 0.1.0","target":{"name":"main"},"message":{"rendered":null,"children":[],"code":null,"level":"error","message":"`S` doesn't implement `std::fmt::Display`","spans":[{"byte_end":10,"byte_start":1,"column_end":40,"column_start":30,"expansion":{"def_site_span":null,"macro_decl_name":"$crate::format_args_nl!","span":{"byte_end":80,"byte_start":75,"column_end":30,"column_start":5,"expansion":{"def_site_span":null,"macro_decl_name":"println!","span":{"byte_end":80,"byte_start":75,"column_end":30,"column_start":9,"expansion":null,"file_name":"src/main.rs","is_primary":false,"label":null,"line_end":7,"line_start":7,"suggested_replacement":null,"suggestion_applicability":null,"text":[]}},"file_name":"/rustc/library/std/src/macros.rs","is_primary":false,"label":null,"line_end":143,"line_start":143,"suggested_replacement":null,"suggestion_applicability":null,"text":[]}},"file_name":"/rustc/library/core/src/fmt/mod.rs","is_primary":true,"label":null,"line_end":1,"line_start":1,"suggested_replacement":null,"suggestion_applicability":null,"text":[]}]}}"#;
+    let Some(Message::CompilerMessage{message}) = parse(line) else { panic!() };
+    assert!(message.is_error());
+    let span = message.primary_span().unwrap();
+    assert_eq!((span.file_name.as_str(), span.line_start, span.column_start), ("src/main.rs", 7, 9));
+}
+
+//================
+//   levels()
+//================
+#[test]
+fn levels() {
+    let transl = Transl::new(&Lang::En, Lexicon::embedded());
+    assert_eq!(transl.level("note"), "note");
+    assert_eq!(transl.level("help"), "help");
+    assert_eq!(transl.level("failure-note"), "failure-note");
+    assert_eq!(transl.level("error: internal compiler error"), "error");
+}
+
+//================
+//   progress()
+//================
+#[test]
+fn progress() {
+    let artifact = r#"{"reason":"compiler-artifact","package_id":"libc 0.2.0","target":{"kind":["lib"],"name":"libc","src_path":"/x/lib.rs"},"profile":{},"features":[],"filenames":[],"executable":null,"fresh":false}"#;
+    let Some(Message::CompilerArtifact{target}) = parse(artifact) else { panic!() };
+    assert_eq!(target.name, "libc");
+
+    let Some(Message::BuildFinished{success}) = parse(r#"{"reason":"build-finished","success":false}"#) else { panic!() };
+    assert!(!success);
+
+    assert!(matches!(parse(r#"{"reason":"build-script-executed","package_id":"x"}"#), Some(Message::Other)));
+}
+
+//================
+//   program_output()
+//================
+#[test]
+fn program_output() {
+    assert!(parse("hello").is_none());
+    assert!(parse("{ not json").is_none());
+}
@@ -1,2 +1,3 @@
 #![allow(warnings)]
-pub mod cargo;
\ No newline at end of file
+pub mod cargo;
+pub mod cargo_msg;
\ No newline at end of file
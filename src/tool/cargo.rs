@@ -1,23 +1,52 @@
 use std::{
-	process::{Command, Stdio, Child},
-    io::{BufReader, BufRead, Read}
+    fs,
+    process::{self, Command, Stdio, Child},
+    io::{self, BufReader, BufRead, IsTerminal, Read, Write},
+    path::Path,
+    sync::mpsc::{self, Sender},
+    thread
 };
 
 use crate::util::fmt::*;
+use crate::lang::{
+    Lang,
+    script::Script,
+    error::{
+        self,
+        Error
+    }
+};
+use crate::target::rust::source_map::SourceMaps;
+use crate::transl::transl::Transl;
+use crate::tool::cargo_msg::{
+    self,
+    Message,
+    Diagnostic
+};
+
+//================
+//   Constants
+//================
+// cargo prints its messages as json on stdout and nothing else, the program keeps its own stdout
+const MESSAGE_ARGS: [&str; 2] = ["--quiet", "--message-format=json"];
 
 //================
 //   cargo()
 //================
 
-pub struct Cargo {}
+pub struct Cargo {
+    transl: Transl      // the diagnostics and the progress are shown in the language of the project
+}
 
 impl Cargo {
     //---------------------
     //  new()
     //---------------------		    
-    pub fn new() -> Self {
+    pub fn new(transl: Transl) -> Self {
         Self::v(); // run `cargo -v` to make sure that cargo is installed
-        Self {}
+        Self {
+            transl
+        }
     }    
 
     //---------------------
@@ -39,12 +68,15 @@ impl Cargo {
     pub fn build(
         &self,
         work_dir: &String,
+        profile: Option<&str>,
         redirect: bool
     ) -> Option<Child> {
+        let mut args = vec!["build"];
+        args.extend(profile_args(profile));
 
         self.spawn(
             "cargo",
-            &["build"],
+            &args,
             work_dir,
             redirect
         )
@@ -57,15 +89,17 @@ impl Cargo {
         &self,
         work_dir: &String,
         cli_args: &Vec<String>,
+        profile: Option<&str>,
         redirect: bool
     ) -> Option<Child> {
-        let mut args = vec!["run", "--"];
+        // everything after `--` is given to the program, not to cargo
+        let mut args = vec!["run"];
+        args.extend(profile_args(profile));
+        args.push("--");
         for arg in cli_args {
             args.push(arg.as_str());
         }
 
-        let args = ["run"];     // FIXME: try comment this line out and run the bootsrap-seen-compiler project, it will give an error
-                                //    for now pass only "run" as an arg and fix the cargo run -- [cli_args]  later
         self.spawn(
             "cargo",
             &args,  
@@ -77,6 +111,8 @@ impl Cargo {
     //---------------------
     //  spawn()
     //---------------------		        
+    // a redirected command keeps the plain output of cargo, for whoever reads it. otherwise the output is
+    //  shown here, the program reads the stdin of seen and seen exits with the exit code of the command
     fn spawn( 
         &self,
         cmd: &str,
@@ -84,9 +120,14 @@ impl Cargo {
         work_dir: &String,
         redirect: bool
     ) -> Option<Child>{
+        let (subcommand, args) = args.split_first().expect("bug: missing cargo subcommand");
+        let message_args: &[&str] = if redirect { &[] } else { &MESSAGE_ARGS };
         match Command::new(cmd)
+            .arg(subcommand)
+            .args(message_args)
             .args(args)
             .current_dir(&work_dir)
+            .stdin(Stdio::inherit())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn() {
@@ -98,7 +139,11 @@ impl Cargo {
                     if redirect {   
                         Some(cmd)
                     } else { 
-                        self.stdio(&mut cmd);
+                        let maps = SourceMaps::load(&Path::new(work_dir).join("src"));
+                        let code = self.stdio(&mut cmd, &maps);
+                        if code != 0 {
+                            process::exit(code);
+                        }
                         None
                     }
                 }
@@ -108,23 +153,222 @@ impl Cargo {
     //---------------------
     //  stdio()
     //---------------------		        
+    // both pipes are read at the same time and shown in the order they are written, the generated files
+    //  named in the output are replaced by the seen files they come from. returns the exit code
     fn stdio(
         &self,
-        cmd: &mut Child
+        cmd: &mut Child,
+        maps: &SourceMaps
+    ) -> i32 {
+        let (tx, rx) = mpsc::channel();
+        let mut readers = vec![];
+        if let Some(stdout) = cmd.stdout.take() {
+            let tx = tx.clone();
+            readers.push(thread::spawn(move || read_stdout(stdout, tx)));
+        }
+        if let Some(stderr) = cmd.stderr.take() {
+            let tx = tx.clone();
+            readers.push(thread::spawn(move || {
+                for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                    if tx.send(Output::Stderr(line)).is_err() { break }
+                }
+            }));
+        }
+        drop(tx);
+
+        let mut progress = Progress::new(&self.transl);
+        for output in rx {
+            match output {
+                Output::Json(line) => match cargo_msg::parse(line.trim_end()) {
+                    Some(Message::CompilerArtifact{target}) => progress.compiled(&target.name),
+                    Some(Message::CompilerMessage{message}) => {
+                        progress.clear();
+                        self.diagnostic(&message, maps);
+                    },
+                    Some(Message::BuildFinished{success}) => progress.finish(success),
+                    Some(Message::Other) => (),
+                    None => {
+                        progress.clear();
+                        print!("{}", line);
+                        let _ = io::stdout().flush();
+                    }
+                },
+                Output::Stdout(bytes) => {
+                    progress.clear();
+                    let mut stdout = io::stdout();
+                    let _ = stdout.write_all(&bytes);
+                    let _ = stdout.flush();
+                },
+                Output::Stderr(line) => {
+                    progress.clear();
+                    eprintln!("{}", maps.rewrite(&line));
+                }
+            }
+        }
+        for reader in readers {
+            let _ = reader.join();
+        }
+
+        match cmd.wait() {
+            Ok(status) => status.code().unwrap_or(1),
+            Err(_) => 1
+        }
+    }
+
+    //---------------------
+    //  diagnostic()
+    //---------------------		        
+    // a message on a generated line is shown on the seen line it comes from, with the renderer of the
+    //  front end, the others as rustc renders them
+    fn diagnostic(
+        &self,
+        diagnostic: &Diagnostic,
+        maps: &SourceMaps
     ) {
-        if let Some(stdout) = cmd.stdout.as_mut() {
-            for line in BufReader::new(stdout).lines() {
-                println!("{}", line.unwrap());
-            }     
+        let label = self.transl.level(&diagnostic.level);
+        let mapped = diagnostic.primary_span()
+            .and_then(|span| maps.lookup(&span.file_name, span.line_start, span.column_start));
+        let script = mapped.as_ref().and_then(|(src, _)| {
+            let content = fs::read_to_string(src).ok()?;
+            let mut script = Script::from_str(&content);
+            script.path = src.to_string();
+            Some(script)
+        });
+
+        match (mapped, script) {
+            (Some((_, location)), Some(script)) => {
+                let msg = diagnostic.message.clone();
+                let err = if diagnostic.is_error() {
+                    Error::new(location.clone(), location.clone(), msg)
+                } else {
+                    Error::warning(location.clone(), location.clone(), msg)
+                };
+                error::print_errors_labeled(&vec![err], &script, |_| label.clone());
+            },
+            _ if diagnostic.spans.is_empty() => eprintln!("{}: {}", label, diagnostic.message),
+            _ => match &diagnostic.rendered {
+                Some(rendered) => {
+                    for line in rendered.lines() {
+                        eprintln!("{}", maps.rewrite(line));
+                    }
+                },
+                None => eprintln!("{}: {}", label, diagnostic.message)
+            }
         }
+    }
+}
 
-        if let Some(stderr) = cmd.stderr.as_mut() {
-            let mut err = String::new();
-            let _ = stderr.read_to_string(&mut err);
-            if err != "" {
-                println!("{}", &err.to_string());
+//================
+//   profile_args()
+//================
+// `--profile release` is the same as `--release`, no profile is the `dev` one
+fn profile_args(profile: Option<&str>) -> Vec<&str> {
+    match profile {
+        Some(profile) => vec!["--profile", profile],
+        None => vec![]
+    }
+}
+
+//================
+//   Output
+//================
+#[derive(Debug, PartialEq)]
+pub enum Output {
+    Json(String),       // a line that can be a cargo message
+    Stdout(Vec<u8>),    // what the program prints, a prompt without a newline is shown right away
+    Stderr(String)
+}
+
+//================
+//   read_stdout()
+//================
+// cargo messages are whole lines that start with `{` until the build is finished, after it the program
+//  runs and everything is its own output, given as soon as it is read
+pub fn read_stdout(
+    stdout: impl Read,
+    tx: Sender<Output>
+) {
+    let mut reader = BufReader::new(stdout);
+    let mut building = true;
+    loop {
+        let buf = match reader.fill_buf() {
+            Ok(buf) if !buf.is_empty() => buf,
+            _ => break
+        };
+        let output = if building && buf[0] == b'{' {
+            let mut line = String::new();
+            if reader.read_line(&mut line).is_err() { break }
+            if let Some(Message::BuildFinished{..}) = cargo_msg::parse(line.trim_end()) {
+                building = false;
             }
-        }                            
+            Output::Json(line)
+        } else {
+            let len = buf.iter().position(|b| *b == b'\n').map_or(buf.len(), |i| i + 1);
+            let bytes = buf[..len].to_vec();
+            reader.consume(len);
+            Output::Stdout(bytes)
+        };
+        if tx.send(output).is_err() { break }
+    }
+}
+
+//================
+//   Progress
+//================
+// one line on stderr that is rewritten as the crates are compiled, cleared before anything else is printed
+// nothing is shown when stderr is not a terminal, e.g. redirected to a file
+struct Progress<'a> {
+    transl: &'a Transl,
+    count: usize,
+    shown: bool,
+    is_terminal: bool
+}
+
+impl<'a> Progress<'a> {
+    //---------------------
+    //  new()
+    //---------------------		        
+    fn new(transl: &'a Transl) -> Self {
+        Self {
+            transl,
+            count: 0,
+            shown: false,
+            is_terminal: io::stderr().is_terminal()
+        }
+    }
+
+    //---------------------
+    //  compiled()
+    //---------------------		        
+    fn compiled(&mut self, name: &str) {
+        self.count += 1;
+        if !self.is_terminal { return }
+        self.clear();
+        eprint!("{} [{}] {}", self.transl.compiling(), self.count, name);
+        let _ = io::stderr().flush();
+        self.shown = true;
     }
 
-}
\ No newline at end of file
+    //---------------------
+    //  clear()
+    //---------------------		        
+    fn clear(&mut self) {
+        if self.shown {
+            eprint!("\r\x1b[2K");
+            let _ = io::stderr().flush();
+            self.shown = false;
+        }
+    }
+
+    //---------------------
+    //  finish()
+    //---------------------		        
+    fn finish(&mut self, success: bool) {
+        self.clear();
+        if success {
+            eprintln!("{}", self.transl.build_done());
+        } else {
+            eprintln!("{}", Color::red(&self.transl.build_failed()));
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use serde::Deserialize;
+
+//================
+//   Constants
+//================
+const SRC_DIR: &str = "src/";         // the generated files, relative to the crate
+
+//================
+//   Message
+//================
+// one line of `cargo --message-format=json`, only the fields seen shows are read
+#[derive(Debug, Deserialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+pub enum Message {
+    CompilerMessage {
+        message: Diagnostic
+    },
+    CompilerArtifact {
+        target: Target
+    },
+    BuildFinished {
+        success: bool
+    },
+    #[serde(other)]
+    Other
+}
+
+//================
+//   Diagnostic
+//================
+#[derive(Debug, Deserialize)]
+pub struct Diagnostic {
+    pub message: String,
+    pub level: String,          // `error`, `warning`, `note`, `help`, ...
+    pub spans: Vec<Span>,
+    pub rendered: Option<String>
+}
+
+impl Diagnostic {
+    //---------------------
+    //  primary_span()
+    //---------------------
+    // a span inside a macro, e.g. `println!`, is followed back to where the macro is called
+    pub fn primary_span(&self) -> Option<&Span> {
+        self.spans.iter().find(|span| span.is_primary).map(Span::call_site)
+    }
+
+    //---------------------
+    //  is_warning()
+    //---------------------
+    pub fn is_warning(&self) -> bool {
+        self.level == "warning"
+    }
+
+    //---------------------
+    //  is_error()
+    //---------------------
+    // `error` and `error: internal compiler error`, the notes and helps do not fail the build
+    pub fn is_error(&self) -> bool {
+        self.level.starts_with("error")
+    }
+}
+
+//================
+//   Span
+//================
+// the lines and columns start at 1
+#[derive(Debug, Deserialize)]
+pub struct Span {
+    pub file_name: String,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub column_start: usize,
+    pub column_end: usize,
+    pub is_primary: bool,
+    pub expansion: Option<Box<Expansion>>   // the macro the span is written by
+}
+
+impl Span {
+    //---------------------
+    //  call_site()
+    //---------------------
+    // the outermost span of the generated crate, rather than one in the std macros
+    pub fn call_site(&self) -> &Span {
+        let mut span = self;
+        while let Some(expansion) = &span.expansion {
+            if span.file_name.starts_with(SRC_DIR) { break }
+            span = &expansion.span;
+        }
+        span
+    }
+}
+
+//================
+//   Expansion
+//================
+#[derive(Debug, Deserialize)]
+pub struct Expansion {
+    pub span: Span
+}
+
+//================
+//   Target
+//================
+#[derive(Debug, Deserialize)]
+pub struct Target {
+    pub name: String
+}
+
+//================
+//   parse()
+//================
+// `None` for the lines that are not cargo messages, e.g. what the program prints on `cargo run`
+pub fn parse(line: &str) -> Option<Message> {
+    if !line.starts_with('{') { return None }
+    serde_json::from_str(line).ok()
+}
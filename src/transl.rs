@@ -1,2 +1,3 @@
 pub mod transl;
+pub mod lexicon;
 mod text;
\ No newline at end of file
@@ -0,0 +1,329 @@
+use std::{
+    fs,
+    fmt::Write,
+    path::{
+        Path,
+        PathBuf
+    },
+    str::FromStr,
+    sync::OnceLock,
+    collections::HashMap,
+};
+
+use crate::lang::Lang;
+
+//================
+//   Constants
+//================
+const EMBEDDED: &str = include_str!("lexicon.txt");
+const LEXICON_EXT: &str = "txt";
+const HEADER: &str = "concept";
+const NO_WORD: &str = "-";
+const ALIAS_SEP: char = '|';
+const COMMENT: char = '#';
+
+static EMBEDDED_LEXICON: OnceLock<Lexicon> = OnceLock::new();
+
+//================
+//   Lexicon
+//================
+// maps every concept ( keyword, builtin, type, conf word ...etc ) to its spellings in each language,
+//  concepts are namespaced: `kw.let`, `type.int`, `fn.println` ...
+#[derive(Clone, Debug, Default)]
+pub struct Lexicon {
+    concepts: Vec<String>,                                  // declaration order, used by doc()
+    words: HashMap<String, HashMap<Lang, Vec<String>>>,
+    index: HashMap<(String, Lang, String), String>,         // (namespace, lang, word) -> concept
+}
+
+impl Lexicon {
+    //---------------------
+    //  new()
+    //---------------------
+    pub fn new() -> Self {
+        Self {
+            concepts: vec![],
+            words: HashMap::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    //---------------------
+    //  embedded()
+    //---------------------
+    pub fn embedded() -> &'static Lexicon {
+        EMBEDDED_LEXICON.get_or_init(|| {
+            match Self::from_str(EMBEDDED) {
+                Ok(lexicon) => lexicon,
+                Err(err) => panic!("bug: invalid embedded lexicon: {}", err)
+            }
+        })
+    }
+
+    //---------------------
+    //  load()
+    //---------------------
+    // the embedded lexicon, with the project's lexicon file (if any) applied on top of it
+    pub fn load(
+        home: &Path,
+        proj_lang: &Lang
+    ) -> Result<Self, String> {
+        let mut lexicon = Self::embedded().clone();
+        let path = Self::path(home, proj_lang);
+        if path.exists() {
+            let src = match fs::read_to_string(&path) {
+                Ok(src) => src,
+                Err(err) => return Err(format!("{}: {}", path.display(), err))
+            };
+            match Self::from_str(&src) {
+                Ok(project) => lexicon.merge(project),
+                Err(err) => return Err(format!("{}: {}", path.display(), err))
+            }
+        }
+        Ok(lexicon)
+    }
+
+    //---------------------
+    //  path()
+    //---------------------
+    pub fn path(
+        home: &Path,
+        proj_lang: &Lang
+    ) -> PathBuf {
+        let mut path = home.to_path_buf();
+        path.push(Self::embedded().word(proj_lang, "conf.lexicon"));
+        path.set_extension(LEXICON_EXT);
+        path
+    }
+
+    //---------------------
+    //  merge()
+    //---------------------
+    // spellings in `other` replace the ones already defined for the same concept and language
+    pub fn merge(
+        &mut self,
+        other: Lexicon
+    ) {
+        for concept in &other.concepts {
+            for (lang, words) in &other.words[concept] {
+                if !words.is_empty() {
+                    self.insert(concept, lang, words.clone());
+                }
+            }
+        }
+        self.reindex();
+    }
+
+    //---------------------
+    //  insert()
+    //---------------------
+    fn insert(
+        &mut self,
+        concept: &str,
+        lang: &Lang,
+        words: Vec<String>
+    ) {
+        if !self.words.contains_key(concept) {
+            self.concepts.push(concept.to_string());
+        }
+        self.words
+            .entry(concept.to_string())
+            .or_default()
+            .insert(lang.clone(), words);
+    }
+
+    //---------------------
+    //  reindex()
+    //---------------------
+    fn reindex(&mut self) {
+        self.index = HashMap::new();
+        for concept in &self.concepts {
+            let ns = namespace(concept).to_string();
+            for (lang, words) in &self.words[concept] {
+                for word in words {
+                    self.index.insert(
+                        (ns.clone(), lang.clone(), word.clone()),
+                        concept.clone()
+                    );
+                }
+            }
+        }
+    }
+
+    //---------------------
+    //  word()
+    //---------------------
+    // the primary spelling, falls back to any language that spells the concept
+    pub fn word(
+        &self,
+        lang: &Lang,
+        concept: &str
+    ) -> String {
+        let words = match self.words.get(concept) {
+            Some(words) => words,
+            None => panic!("bug: unknown lexicon concept `{}`", concept)
+        };
+        if let Some(word) = words.get(lang).and_then(|w| w.first()) {
+            return word.clone()
+        }
+        for concept_lang in Lang::all() {
+            if let Some(word) = words.get(&concept_lang).and_then(|w| w.first()) {
+                return word.clone()
+            }
+        }
+        String::new()
+    }
+
+    //---------------------
+    //  words()
+    //---------------------
+    pub fn words(
+        &self,
+        lang: &Lang,
+        concept: &str
+    ) -> Vec<String> {
+        self.words
+            .get(concept)
+            .and_then(|words| words.get(lang))
+            .cloned()
+            .unwrap_or(vec![])
+    }
+
+    //---------------------
+    //  concept()
+    //---------------------
+    pub fn concept(
+        &self,
+        ns: &str,
+        lang: &Lang,
+        word: &str
+    ) -> Option<&str> {
+        self.index
+            .get(&(ns.to_string(), lang.clone(), word.to_string()))
+            .map(|concept| concept.as_str())
+    }
+
+    //---------------------
+    //  concept_any()
+    //---------------------
+    // same as concept(), for the passes that do not know the source language
+    pub fn concept_any(
+        &self,
+        ns: &str,
+        word: &str
+    ) -> Option<&str> {
+        Lang::all()
+            .iter()
+            .find_map(|lang| self.concept(ns, lang, word))
+    }
+
+    //---------------------
+    //  is()
+    //---------------------
+    pub fn is(
+        &self,
+        concept: &str,
+        lang: &Lang,
+        word: &str
+    ) -> bool {
+        self.concept(namespace(concept), lang, word) == Some(concept)
+    }
+
+    //---------------------
+    //  doc()
+    //---------------------
+    // markdown table of all the concepts
+    pub fn doc(&self) -> String {
+        let langs = Lang::all();
+        let mut res = String::new();
+
+        let _ = write!(res, "| concept |");
+        for lang in &langs {
+            let _ = write!(res, " {} |", lang);
+        }
+        let _ = writeln!(res);
+        let _ = write!(res, "|---|");
+        for _ in &langs {
+            let _ = write!(res, "---|");
+        }
+        let _ = writeln!(res);
+
+        for concept in &self.concepts {
+            let _ = write!(res, "| `{}` |", concept);
+            for lang in &langs {
+                let words = self.words(lang, concept);
+                if words.is_empty() {
+                    let _ = write!(res, " {} |", NO_WORD);
+                } else {
+                    let _ = write!(res, " {} |", words.join(", "));
+                }
+            }
+            let _ = writeln!(res);
+        }
+        res
+    }
+}
+
+impl FromStr for Lexicon {
+    type Err = String;
+
+    //---------------------
+    //  from_str()
+    //---------------------
+    fn from_str(src: &str) -> Result<Self, String> {
+        let mut lexicon = Self::new();
+        let mut langs: Option<Vec<Lang>> = None;
+
+        for (i, line) in src.lines().enumerate() {
+            let line = match line.find(COMMENT) {
+                Some(index) => &line[..index],
+                None => line
+            };
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            if columns.is_empty() { continue }
+
+            match &langs {
+                None => {
+                    if columns[0] != HEADER {
+                        return Err(format!("line {}: expecting the header `{} <lang> <lang> ...`", i + 1, HEADER))
+                    }
+                    let mut header = vec![];
+                    for column in &columns[1..] {
+                        match Lang::try_from_str(column) {
+                            Some(lang) => header.push(lang),
+                            None => return Err(format!("line {}: unknown language `{}`", i + 1, column))
+                        }
+                    }
+                    langs = Some(header);
+                },
+                Some(langs) => {
+                    if columns.len() != langs.len() + 1 {
+                        return Err(format!("line {}: expecting {} columns, found {}", i + 1, langs.len() + 1, columns.len()))
+                    }
+                    let concept = columns[0];
+                    for (lang, words) in langs.iter().zip(&columns[1..]) {
+                        let words = if *words == NO_WORD {
+                            vec![]
+                        } else {
+                            words.split(ALIAS_SEP).map(|w| w.to_string()).collect()
+                        };
+                        lexicon.insert(concept, lang, words);
+                    }
+                }
+            }
+        }
+
+        lexicon.reindex();
+        Ok(lexicon)
+    }
+}
+
+//================
+//   namespace()
+//================
+fn namespace(concept: &str) -> &str {
+    match concept.find('.') {
+        Some(index) => &concept[..index],
+        None => concept
+    }
+}
@@ -1,23 +1,72 @@
+use std::collections::HashMap;
+
+use crate::lang::Lang;
+use crate::transl::lexicon::Lexicon;
+
 //================
 //   Text
 //================
+// all the spellings of a term in each language, the first one is used when generating text
 #[derive(Debug)]
 pub struct Text {
-    pub ar: String,
-    pub en: String
+    words: HashMap<Lang, Vec<String>>
 }
 
 impl Text {
     //---------------------
     //  new()
     //---------------------     
-    pub fn new(
-        ar: &str, 
-        en: &str
+    pub fn new(words: &[(Lang, &str)]) -> Self {
+        Self {
+            words: words
+                .iter()
+                .map(|(lang, word)| (lang.clone(), vec![word.to_string()]))
+                .collect()
+        }
+    }
+
+    //---------------------
+    //  from_lexicon()
+    //---------------------     
+    pub fn from_lexicon(
+        lexicon: &Lexicon, 
+        concept: &str
     ) -> Self {
         Self {
-            ar: ar.to_string(),
-            en: en.to_string()    
+            words: Lang::all()
+                .into_iter()
+                .map(|lang| {
+                    let words = spellings(lexicon, &lang, concept);
+                    (lang, words)
+                })
+                .collect()
         }
     }
-}
\ No newline at end of file
+
+    //---------------------
+    //  get()
+    //---------------------     
+    // falls back to English for the languages the text is not translated to
+    pub fn get(&self, lang: &Lang) -> &Vec<String> {
+        match self.words.get(lang) {
+            Some(words) => words,
+            None => &self.words[&Lang::En]
+        }
+    }
+}
+
+//================
+//   spellings()
+//================
+// the primary word first ( falls back to another language if the concept is not spelled in `lang` ), then the aliases
+fn spellings(
+    lexicon: &Lexicon,
+    lang: &Lang,
+    concept: &str
+) -> Vec<String> {
+    let mut words = vec![lexicon.word(lang, concept)];
+    for word in lexicon.words(lang, concept) {
+        if !words.contains(&word) { words.push(word) }
+    }
+    words
+}
@@ -1,28 +1,44 @@
-// FIXME: translations should be externalized to data files
-//          for example if we decide to add new package manager / langauge support to the conf files, we shouldn't need to recompile the source code ( like py->... ),
-//          just add it to the data files 
-//          also, we can have duplicates in multiple translation files , seen can get rid of redundancy , flag difference, or use different terms / translations for different content
-// FIXME: multiple terms, 
-//              for example we can have python as both python / py in english... but in arabic it would be only بايثون
-//              but we could also have a short/long form for both languages, such as :  install / i 
-//              another case is having aliases,  we can have a package name that is readable in english but not in arabic, so we can give it an alias in addition to its original name
-//              also what if the same word in a langauge have multiple meanings and different translations in the other language? 
+// the terms are read from the lexicon ( lexicon.txt ), only messages are kept here.
+// FIXME: what if the same word in a langauge have multiple meanings and different translations in the other language? 
 // FIXME: add citation
 
 use crate::lang::Lang;
-use crate::transl::text::Text;
+use crate::transl::{
+    text::Text,
+    lexicon::Lexicon
+};
 
 //================
 //   Constants
 //================
-const CONF_AR :&str = "هيئة";
-const CONF_EN :&str = "conf";
-
-const SEEN_EXT_AR :&str = "س";
-const SEEN_EXT_EN :&str = "seen";
-
 const MISSING_CONF_ERR_AR : &str = "ملف هيئة.س مفقود";
 const MISSING_CONF_ERR_EN : &str = "missing conf.seen";
+const MISSING_CONF_ERR_FA : &str = "فایل پیکربندی.سین پیدا نشد";
+
+const ERROR_AR : &str = "خطأ";
+const ERROR_EN : &str = "error";
+const ERROR_FA : &str = "خطا";
+const WARNING_AR : &str = "تحذير";
+const WARNING_EN : &str = "warning";
+const WARNING_FA : &str = "هشدار";
+const NOTE_AR : &str = "ملاحظة";
+const NOTE_EN : &str = "note";
+const NOTE_FA : &str = "یادداشت";
+const HELP_AR : &str = "مساعدة";
+const HELP_EN : &str = "help";
+const HELP_FA : &str = "راهنما";
+const FAILURE_NOTE_AR : &str = "ملاحظة الفشل";
+const FAILURE_NOTE_EN : &str = "failure-note";
+const FAILURE_NOTE_FA : &str = "یادداشت خطا";
+const COMPILING_AR : &str = "يترجم";
+const COMPILING_EN : &str = "compiling";
+const COMPILING_FA : &str = "در حال کامپایل";
+const BUILD_DONE_AR : &str = "تم البناء";
+const BUILD_DONE_EN : &str = "built";
+const BUILD_DONE_FA : &str = "ساخته شد";
+const BUILD_FAILED_AR : &str = "فشل البناء";
+const BUILD_FAILED_EN : &str = "build failed";
+const BUILD_FAILED_FA : &str = "ساخت ناموفق بود";
 
 //================
 //   Transl
@@ -42,18 +58,28 @@ pub struct Transl {
     id: Text,
     prebuild: Text,
     rust: Text,
-    rs: Text,
     python: Text,
-    py: Text,
     missing_conf_err: Text,
+    allow_bidi: Text,
+    _true: Text,
 
     // conf.seen -> rust
     features: Text,
-    f: Text,
     version: Text, 
-    v: Text,
     
 
+    // conf.seen -> profiles
+    profiles: Text,
+    release: Text,
+    dev: Text,
+    inherits: Text,
+    opt_level: Text,
+    lto: Text,
+    strip: Text,
+    debug: Text,
+    panic: Text,
+    codegen_units: Text,
+
     // conf.seen -> python
     py_path: Text,
     pkg_man: Text, 
@@ -88,71 +114,105 @@ pub struct Transl {
     // html
     label: Text,
 
+    // cargo
+    error: Text,
+    warning: Text,
+    note: Text,
+    help: Text,
+    failure_note: Text,
+    compiling: Text,
+    build_done: Text,
+    build_failed: Text,
+
 }
 
 impl Transl {
     //---------------------
     //  new()
     //---------------------     
-    pub fn new(_proj_lang: &Lang) -> Self {
+    // the terms of the project's lexicon, a project can respell the conf words too
+    pub fn new(
+        _proj_lang: &Lang,
+        lexicon: &Lexicon
+    ) -> Self {
+        let text = |concept: &str| Text::from_lexicon(lexicon, concept);
         Self {
             _proj_lang: _proj_lang.clone(),
             // project
-            seen_ext:           Text::new(      SEEN_EXT_AR,                  SEEN_EXT_EN                     ),
-            src:                Text::new(      "مصدر",                       "src"                           ),
-            main:               Text::new(      "رئيسي",                      "main"                          ),            
-            build:              Text::new(      "بنية",                       "build"                         ),
+            seen_ext:           Text::new(&[(Lang::Ar, Lang::Ar.ext()), (Lang::En, Lang::En.ext()), (Lang::Fa, Lang::Fa.ext())]),
+            src:                text("conf.src"),
+            main:               text("conf.main"),
+            build:              text("conf.build"),
 
             // conf.seen
-            conf:               Text::new(      CONF_AR,                      CONF_EN                         ),            
-            name:               Text::new(      "الاسم",                       "name"                          ),
-            deps:               Text::new(      "تبعيات",                     "deps"                          ),
-            id:                 Text::new(      "معرف",                       "id"                            ),
-            prebuild:           Text::new(      "قبل_البناء",                 "prebuild"                      ),
-            rust:               Text::new(      "رست",                        "rust"                          ),            
-            rs:                 Text::new(      "رست",                        "rs"                            ),            
-            python:             Text::new(      "بايثون",                     "python"                        ),
-            py:                 Text::new(      "بايثون",                     "py"                            ),
-            missing_conf_err:   Text::new(      MISSING_CONF_ERR_AR,          MISSING_CONF_ERR_EN             ),
+            conf:               text("conf.conf"),
+            name:               text("conf.name"),
+            deps:               text("conf.deps"),
+            id:                 text("conf.id"),
+            prebuild:           text("conf.prebuild"),
+            rust:               text("conf.rust"),
+            python:             text("conf.python"),
+            missing_conf_err:   Text::new(&[(Lang::Ar, MISSING_CONF_ERR_AR), (Lang::En, MISSING_CONF_ERR_EN), (Lang::Fa, MISSING_CONF_ERR_FA)]),
+            allow_bidi:         text("conf.allow_bidi"),
+            _true:              text("kw.true"),
 
             // conf.seen -> rust
-            features:           Text::new(      "سمات",                       "features"                      ),
-            f:                  Text::new(      "سمات",                       "f"                             ),        // FIXME: using f, while it's actually features, should it be feats? 
-            version:            Text::new(      "اصدار",                      "version"                       ),
-            v:                  Text::new(      "اصدار",                      "v"                             ),
+            features:           text("conf.features"),
+            version:            text("conf.version"),
+
+            // conf.seen -> profiles
+            profiles:           text("conf.profiles"),
+            release:            text("conf.release"),
+            dev:                text("conf.dev"),
+            inherits:           text("conf.inherits"),
+            opt_level:          text("conf.opt_level"),
+            lto:                text("conf.lto"),
+            strip:              text("conf.strip"),
+            debug:              text("conf.debug"),
+            panic:              text("conf.panic"),
+            codegen_units:      text("conf.codegen_units"),
 
             // conf.seen -> python
-            py_path:            Text::new(      "مسار_بايثون",               "py_path"                        ),
-            pkg_man:            Text::new(      "مدير_الحزم",                "pkg_man"                        ),
+            py_path:            text("conf.py_path"),
+            pkg_man:            text("conf.pkg_man"),
 
             // plotting example
-            plot:               Text::new(      "رسم_بياني",                  "plot"                          ),
-            legend:             Text::new(      "مفتاح",                      "legend"                        ),
-            data:               Text::new(      "بيانات",                     "data"                          ),
-            size:               Text::new(      "حجم",                        "size"                          ), 
-            label_x:            Text::new(      "لصيقة_س",                    "label_x"                       ),
-            label_y:            Text::new(      "لصيقة_ص",                    "label_y"                       ),
-            title:              Text::new(      "عنوان",                      "title"                         ),
+            plot:               text("plot.plot"),
+            legend:             text("plot.legend"),
+            data:               text("plot.data"),
+            size:               text("plot.size"),
+            label_x:            text("plot.label_x"),
+            label_y:            text("plot.label_y"),
+            title:              text("plot.title"),
 
             // pip.seen
-            exec:               Text::new(      "شغل",                        "exec"                          ),
-            install:            Text::new(      "ثبت",                        "install"                       ),
+            exec:               text("conf.exec"),
+            install:            text("conf.install"),
 
             // translate.seen
-            translate:          Text::new(      "ترجمة",                       "translate"                    ),
+            translate:          text("conf.translate"),
 
             // web_server
-            web_server:         Text::new(      "مخدم_شع",                    "web_server"                    ),
-            settings:           Text::new(      "اعدادات",                    "settings"                      ),
-            hostname:           Text::new(      "اسم_المضيف",                 "hostname"                      ),
-            port:               Text::new(      "منفذ",                       "port"                          ),
-            localhost:          Text::new(      "المضيف_المحلي",              "localhost"                     ),
-            // title:              Text::new(      "عنوان",                      "title"                          ),
-            homepage:           Text::new(      "ص_رئيسية",                   "homepage"                      ),
-            content:            Text::new(      "محتوى",                      "content"                       ),
+            web_server:         text("web.web_server"),
+            settings:           text("web.settings"),
+            hostname:           text("web.hostname"),
+            port:               text("web.port"),
+            localhost:          text("web.localhost"),
+            homepage:           text("web.homepage"),
+            content:            text("web.content"),
 
             // html
-            label:              Text::new(      "لصيقة",                      "label"                         ),
+            label:              text("html.label"),
+
+            // cargo
+            error:              Text::new(&[(Lang::Ar, ERROR_AR), (Lang::En, ERROR_EN), (Lang::Fa, ERROR_FA)]),
+            warning:            Text::new(&[(Lang::Ar, WARNING_AR), (Lang::En, WARNING_EN), (Lang::Fa, WARNING_FA)]),
+            note:               Text::new(&[(Lang::Ar, NOTE_AR), (Lang::En, NOTE_EN), (Lang::Fa, NOTE_FA)]),
+            help:               Text::new(&[(Lang::Ar, HELP_AR), (Lang::En, HELP_EN), (Lang::Fa, HELP_FA)]),
+            failure_note:       Text::new(&[(Lang::Ar, FAILURE_NOTE_AR), (Lang::En, FAILURE_NOTE_EN), (Lang::Fa, FAILURE_NOTE_FA)]),
+            compiling:          Text::new(&[(Lang::Ar, COMPILING_AR), (Lang::En, COMPILING_EN), (Lang::Fa, COMPILING_FA)]),
+            build_done:         Text::new(&[(Lang::Ar, BUILD_DONE_AR), (Lang::En, BUILD_DONE_EN), (Lang::Fa, BUILD_DONE_FA)]),
+            build_failed:       Text::new(&[(Lang::Ar, BUILD_FAILED_AR), (Lang::En, BUILD_FAILED_EN), (Lang::Fa, BUILD_FAILED_FA)]),
 
         }
     }
@@ -164,10 +224,19 @@ impl Transl {
         &self, 
         text: &Text
     ) -> String {
-        match self._proj_lang {
-            Lang::Ar => text.ar.clone(),
-            Lang::En => text.en.clone()
-        }
+        text.get(&self._proj_lang)[0].clone()
+    }
+
+    //---------------------
+    //  is()
+    //--------------------- 
+    // true if the word is any of the spellings ( including aliases ) of the text
+    pub fn is(
+        &self, 
+        text: &Text,
+        word: &str
+    ) -> bool {
+        text.get(&self._proj_lang).iter().any(|w| w == word)
     }
 
 }
@@ -185,14 +254,9 @@ impl Transl {
 
 
     //---------------------
-    //  seen_ext_ar()
+    //  seen_ext_of()
     //--------------------- 
-    pub fn seen_ext_ar() -> String { SEEN_EXT_AR.to_string() }        
-
-    //---------------------
-    //  seen_ext_en()
-    //--------------------- 
-    pub fn seen_ext_en() -> String { SEEN_EXT_EN.to_string() }            
+    pub fn seen_ext_of(lang: &Lang) -> String { lang.ext().to_string() }        
 
     //---------------------
     //  src()
@@ -225,14 +289,9 @@ impl Transl {
     pub fn conf(&self) -> String { self.to_str(&self.conf) }    
 
     //---------------------
-    //  conf_ar()
+    //  conf_of()
     //--------------------- 
-    pub fn conf_ar() -> String { CONF_AR.to_string() }    
-
-    //---------------------
-    //  conf_en()
-    //--------------------- 
-    pub fn conf_en() -> String { CONF_EN.to_string() }    
+    pub fn conf_of(lang: &Lang) -> String { Lexicon::embedded().word(lang, "conf.conf") }    
 
 
     //---------------------
@@ -261,9 +320,9 @@ impl Transl {
     pub fn rust(&self) -> String { self.to_str(&self.rust) }     
 
     //---------------------
-    //  rs()
+    //  is_rust()
     //--------------------- 
-    pub fn rs(&self) -> String { self.to_str(&self.rs) }     
+    pub fn is_rust(&self, word: &str) -> bool { self.is(&self.rust, word) }     
 
     //---------------------
     //  python()
@@ -271,9 +330,9 @@ impl Transl {
     pub fn python(&self) -> String { self.to_str(&self.python) }     
 
     //---------------------
-    //  py()
+    //  is_python()
     //--------------------- 
-    pub fn py(&self) -> String { self.to_str(&self.py) }     
+    pub fn is_python(&self, word: &str) -> bool { self.is(&self.python, word) }     
 
     //---------------------
     //  missing_conf_err()
@@ -290,6 +349,16 @@ impl Transl {
     //---------------------     
     pub fn missing_conf_err_en() -> String { MISSING_CONF_ERR_EN.to_string() }     
 
+    //---------------------
+    //  allow_bidi()
+    //--------------------- 
+    pub fn allow_bidi(&self) -> String { self.to_str(&self.allow_bidi) }     
+
+    //---------------------
+    //  is_true()
+    //--------------------- 
+    pub fn is_true(&self, word: &str) -> bool { self.is(&self._true, word) }     
+
 
 }
 
@@ -307,9 +376,9 @@ impl Transl {
     pub fn features(&self) -> String { self.to_str(&self.features) }   
 
     //---------------------
-    //  f()
+    //  is_features()
     //--------------------- 
-    pub fn f(&self) -> String { self.to_str(&self.f) }   
+    pub fn is_features(&self, word: &str) -> bool { self.is(&self.features, word) }     
 
     //---------------------
     //  version()
@@ -317,12 +386,120 @@ impl Transl {
     pub fn version(&self) -> String { self.to_str(&self.version) }       
 
     //---------------------
-    //  v()
+    //  is_version()
     //--------------------- 
-    pub fn v(&self) -> String { self.to_str(&self.v) }           
+    pub fn is_version(&self, word: &str) -> bool { self.is(&self.version, word) }     
 }
 
 
+//================
+//   Transl
+//  conf.seen ->
+//    profiles 
+//================
+impl Transl {
+
+    //---------------------
+    //  profiles()
+    //--------------------- 
+    pub fn profiles(&self) -> String { self.to_str(&self.profiles) }     
+
+    //---------------------
+    //  is_profiles()
+    //--------------------- 
+    pub fn is_profiles(&self, word: &str) -> bool { self.is(&self.profiles, word) }     
+
+    //---------------------
+    //  release()
+    //--------------------- 
+    pub fn release(&self) -> String { self.to_str(&self.release) }     
+
+    //---------------------
+    //  is_release()
+    //--------------------- 
+    pub fn is_release(&self, word: &str) -> bool { self.is(&self.release, word) }     
+
+    //---------------------
+    //  dev()
+    //--------------------- 
+    pub fn dev(&self) -> String { self.to_str(&self.dev) }     
+
+    //---------------------
+    //  is_dev()
+    //--------------------- 
+    pub fn is_dev(&self, word: &str) -> bool { self.is(&self.dev, word) }     
+
+    //---------------------
+    //  inherits()
+    //--------------------- 
+    pub fn inherits(&self) -> String { self.to_str(&self.inherits) }     
+
+    //---------------------
+    //  is_inherits()
+    //--------------------- 
+    pub fn is_inherits(&self, word: &str) -> bool { self.is(&self.inherits, word) }     
+
+    //---------------------
+    //  opt_level()
+    //--------------------- 
+    pub fn opt_level(&self) -> String { self.to_str(&self.opt_level) }     
+
+    //---------------------
+    //  is_opt_level()
+    //--------------------- 
+    pub fn is_opt_level(&self, word: &str) -> bool { self.is(&self.opt_level, word) }     
+
+    //---------------------
+    //  lto()
+    //--------------------- 
+    pub fn lto(&self) -> String { self.to_str(&self.lto) }     
+
+    //---------------------
+    //  is_lto()
+    //--------------------- 
+    pub fn is_lto(&self, word: &str) -> bool { self.is(&self.lto, word) }     
+
+    //---------------------
+    //  strip()
+    //--------------------- 
+    pub fn strip(&self) -> String { self.to_str(&self.strip) }     
+
+    //---------------------
+    //  is_strip()
+    //--------------------- 
+    pub fn is_strip(&self, word: &str) -> bool { self.is(&self.strip, word) }     
+
+    //---------------------
+    //  debug()
+    //--------------------- 
+    pub fn debug(&self) -> String { self.to_str(&self.debug) }     
+
+    //---------------------
+    //  is_debug()
+    //--------------------- 
+    pub fn is_debug(&self, word: &str) -> bool { self.is(&self.debug, word) }     
+
+    //---------------------
+    //  panic()
+    //--------------------- 
+    pub fn panic(&self) -> String { self.to_str(&self.panic) }     
+
+    //---------------------
+    //  is_panic()
+    //--------------------- 
+    pub fn is_panic(&self, word: &str) -> bool { self.is(&self.panic, word) }     
+
+    //---------------------
+    //  codegen_units()
+    //--------------------- 
+    pub fn codegen_units(&self) -> String { self.to_str(&self.codegen_units) }     
+
+    //---------------------
+    //  is_codegen_units()
+    //--------------------- 
+    pub fn is_codegen_units(&self, word: &str) -> bool { self.is(&self.codegen_units, word) }     
+}
+
 //================
 //   Transl
 //  conf.seen ->
@@ -470,3 +647,50 @@ impl Transl {
     //--------------------- 
     pub fn label(&self) -> String { self.to_str(&self.label) }     
 }
+
+
+//================
+//   Transl
+//    cargo
+//================
+impl Transl {
+
+    //---------------------
+    //  error()
+    //--------------------- 
+    pub fn error(&self) -> String { self.to_str(&self.error) }     
+
+    //---------------------
+    //  warning()
+    //--------------------- 
+    pub fn warning(&self) -> String { self.to_str(&self.warning) }     
+
+    //---------------------
+    //  level()
+    //--------------------- 
+    // the label of a rustc diagnostic level, `error: internal compiler error` is an error
+    pub fn level(&self, level: &str) -> String {
+        match level {
+            "warning" => self.warning(),
+            "note" => self.to_str(&self.note),
+            "help" => self.to_str(&self.help),
+            "failure-note" => self.to_str(&self.failure_note),
+            _ => self.error()
+        }
+    }
+
+    //---------------------
+    //  compiling()
+    //--------------------- 
+    pub fn compiling(&self) -> String { self.to_str(&self.compiling) }     
+
+    //---------------------
+    //  build_done()
+    //--------------------- 
+    pub fn build_done(&self) -> String { self.to_str(&self.build_done) }     
+
+    //---------------------
+    //  build_failed()
+    //--------------------- 
+    pub fn build_failed(&self) -> String { self.to_str(&self.build_failed) }     
+}
@@ -14,6 +14,14 @@ pub fn is_western_num(num : &String) -> bool {
     }
 }
 
+//================
+//   is_eastern_digit()
+//================
+// Eastern Arabic (٠ - ٩) or Extended Arabic-Indic (۰ - ۹) used by Persian and Urdu
+pub fn is_eastern_digit(c: char) -> bool {
+    matches!(c, '٠'..='٩' | '۰'..='۹')
+}
+
 //================
 //   to_western_num()
 //================
@@ -23,17 +31,8 @@ pub fn to_western_num(num : &String) -> String {
         let mut res = String::new();
         for c in num.chars() {
             let _ = match c {
-                '٠' => write!(res, "0"),
-                '١' => write!(res, "1"),
-                '٢' => write!(res, "2"),
-                '٣' => write!(res, "3"),
-                '٤' => write!(res, "4"),
-                '٥' => write!(res, "5"),
-                '٦' => write!(res, "6"),
-                '٧' => write!(res, "7"),
-                '٨' => write!(res, "8"),
-                '٩' => write!(res, "9"),
-                '٫' => write!(res, "."),
+                c if is_eastern_digit(c) => write!(res, "{}", to_western_digit(c)),
+                '٫' | ',' => write!(res, "."),
                 _ => panic!()
             };
         }
@@ -48,16 +47,16 @@ pub fn to_western_num(num : &String) -> String {
 //================
 pub fn to_western_digit(c : char) -> char {
     match c {
-        '٠' => '0',
-        '١' => '1',
-        '٢' => '2',
-        '٣' => '3',
-        '٤' => '4',
-        '٥' => '5',
-        '٦' => '6',
-        '٧' => '7',
-        '٨' => '8',
-        '٩' => '9',
+        '٠' | '۰' => '0',
+        '١' | '۱' => '1',
+        '٢' | '۲' => '2',
+        '٣' | '۳' => '3',
+        '٤' | '۴' => '4',
+        '٥' | '۵' => '5',
+        '٦' | '۶' => '6',
+        '٧' | '۷' => '7',
+        '٨' | '۸' => '8',
+        '٩' | '۹' => '9',
         _ => panic!()
     }
 }
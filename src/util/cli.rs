@@ -16,6 +16,8 @@ use clap::{
 use crate::lang::{
 	Lang,
 	compiler,
+	attrs::AttrRegistry,
+	passes::PassOptions,
 	syntax_tree::ast::{
 		ModElement,
 		Fn,
@@ -32,7 +34,10 @@ use crate::project::{
 	build
 };
 
-use crate::transl::transl::Transl;
+use crate::transl::{
+	transl::Transl,
+	lexicon
+};
 
 use crate::tool::cargo::*;
 
@@ -85,6 +90,8 @@ pub enum Commands {
   Update(Update),
   /// Check
   Check(Check),
+  /// Print the bilingual keywords and builtins table
+  Lexicon(Lexicon),
   // Launch the UI Seen Editor
   Editor(Editor),
 }
@@ -99,6 +106,10 @@ pub struct New {
     #[arg(long)]
     pub ar: bool,
 
+    /// Set language to Persian
+    #[arg(long, conflicts_with = "ar")]
+    pub fa: bool,
+
     /// The project name:
     pub name: String,
 
@@ -114,6 +125,7 @@ impl New {
     //---------------------
 	pub fn exec(
 		ar: bool,
+		fa: bool,
 		name: String,
 		path: Option<String>,
 		template: Option<String>
@@ -127,7 +139,7 @@ impl New {
 		check_dir_not_exists(&proj_path)?;
 
 		fs::create_dir_all(&proj_path).expect("");
-		let _ = Init::exec(ar, Some(proj_path), template); 
+		let _ = Init::exec(ar, fa, Some(proj_path), template); 
 
 		Ok(())
 	}
@@ -143,6 +155,10 @@ pub struct Init {
     #[arg(long)]
     pub ar: bool,
 
+    /// Set language to Persian
+    #[arg(long, conflicts_with = "ar")]
+    pub fa: bool,
+
     /// The project template:
     /// e.g:
     /// 	`web_server`
@@ -155,6 +171,7 @@ impl Init {
     //---------------------	
 	pub fn exec(
 		ar: bool,
+		fa: bool,
 		path: Option<PathBuf>,
 		template: Option<String>
 	) -> Result<(), String> {
@@ -165,7 +182,7 @@ impl Init {
 
 		check_dir_empty(&home)?;
 
-		let lang = if ar { Lang::Ar} else { Lang::En};
+		let lang = if ar { Lang::Ar } else if fa { Lang::Fa } else { Lang::En };
 		let mut proj = proj_dir::ProjDir::new(lang, Some(home));
 
 		match template {
@@ -192,20 +209,33 @@ impl Init {
 //================
 #[derive(Parser,Debug)]
 #[command()]
-pub struct Compile {}
+pub struct Compile {
+    /// Turn off a middle-end pass, can be repeated:
+    /// 	`unreachable`, `const_fold`, `dead_branch`, `unused_fn`
+    #[arg(long = "no-pass")]
+    pub no_pass: Vec<String>,
+
+    /// Print the IR before and after each pass
+    #[arg(long)]
+    pub dump_ir: bool,
+}
 
 impl Compile {
     //---------------------
     //  exec()
     //---------------------		
-	pub fn exec(path: Option<PathBuf>) {
+	pub fn exec(
+		path: Option<PathBuf>,
+		pass_options: &PassOptions
+	) {
 		let home = match path {
 			None => std::env::current_dir().unwrap(),
 			Some(path) => path
 		};
 		
 		let lang = conf::proj_lang(&home).expect("");
-		let transl = Transl::new(&lang);
+		let lexicon = conf::lexicon(&home, &lang);
+		let transl = Transl::new(&lang, &lexicon);
 		let proj_name = conf::proj_name(&transl, &home);
 		let build_path = build::build_path(&transl, &home, &proj_name);
 		let out = Some(format!("{}", build_path.display()));
@@ -213,8 +243,9 @@ impl Compile {
 		let paths = Compile::src_paths(&transl, &home, &proj_name);
 		let main_mods = Compile::main_mods(&transl, &home, &proj_name);
 	
-		if let Err(err) = compiler::compile(lang, &transl, &home, &proj_name, out, paths, main_mods ) {
-			panic!("{}", err);
+		if let Err(err) = compiler::compile(lang, &transl, &home, &proj_name, out, paths, main_mods, &AttrRegistry::builtin(), pass_options) {
+			eprintln!("{}", err);
+			std::process::exit(1);
 		  }  	
 		
 		println!("{} built successfully.", proj_name);
@@ -276,8 +307,8 @@ impl Compile {
 				cp_dir(&path, &build_src_path, &mut seen_files);
 			} else { 
 				let path =  el.path();			
-				if let Some(ext) = path.extension() {				
-					if ext == "seen" || ext == "س" {	// FIXME: hardcoded
+				if path.extension().is_some() {				
+					if is_seen_file(&path) {
 						seen_files.push(format!("{}", path.display()));
 					} else {
 						let mut to = build_src_path.clone();
@@ -315,8 +346,8 @@ impl Compile {
 				// cp_dir(&entry.path(), &dir.join(entry.file_name()), seen_files);
 				cp_dir(&entry.path(), &dir, seen_files);
 			} else {
-				if let Some(ext) = entry.path().extension() {
-					if ext == "seen" || ext == "س"  {
+				if entry.path().extension().is_some() {
+					if is_seen_file(&entry.path()) {
 						seen_files.push(format!("{}", entry.path().display()));
 					} else {
 						match std::fs::copy(entry.path(), dir.join(entry.file_name())) {
@@ -354,6 +385,14 @@ pub struct Build {
       /// Destination directory
       #[arg(short, long)]
       pub output: Option<String>, */
+
+    /// Build with the `release` profile
+    #[arg(long)]
+    pub release: bool,
+
+    /// Build with a profile of conf.seen, e.g. `--profile fast`
+    #[arg(long, conflicts_with = "release")]
+    pub profile: Option<String>,
 }
 
 impl Build {
@@ -362,6 +401,7 @@ impl Build {
     //---------------------			
 	pub fn exec(
 		path: Option<PathBuf>,
+		profile: Option<String>,
 		redirect: bool
 	) -> Option<Child> {
 		let home = match path {
@@ -369,21 +409,48 @@ impl Build {
 			Some(path) => path
 		};
 		let lang = conf::proj_lang(&home).expect("");
-		let transl = Transl::new(&lang);
+		let lexicon = conf::lexicon(&home, &lang);
+		let transl = Transl::new(&lang, &lexicon);
 		let proj_name = conf::proj_name(&transl, &home);
 		let build_path = build::build_path(&transl, &home, &proj_name);
 		let work_dir = format!("{}", build_path.display());
-		Compile::exec(Some(home));
-		Cargo::new().build(&work_dir, redirect)
+		let profile = profile.map(|name| conf::profile_name(&transl, &name));
+		Compile::exec(Some(home), &PassOptions::default());
+		Cargo::new(transl).build(&work_dir, profile.as_deref(), redirect)
 	}	
 }
 
+//================
+//   profile()
+//================
+// `--release` is `--profile release`
+pub fn profile(
+	release: bool,
+	profile: Option<String>
+) -> Option<String> {
+	if release { Some(String::from("release")) } else { profile }
+}
+
 //================
 //   Run
 //================
 #[derive(Parser,Debug)]
 #[command()]
-pub struct Run {}
+pub struct Run {
+    /// The arguments given to the program, after `--`:
+    /// e.g:
+    /// 	`seen run -- input.txt -v`
+    #[arg(last = true)]
+    pub args: Vec<String>,
+
+    /// Run with the `release` profile
+    #[arg(long)]
+    pub release: bool,
+
+    /// Run with a profile of conf.seen, e.g. `--profile fast`
+    #[arg(long, conflicts_with = "release")]
+    pub profile: Option<String>,
+}
 
 impl Run {
     //---------------------
@@ -391,22 +458,23 @@ impl Run {
     //---------------------		
 	pub fn exec(
 		path: Option<PathBuf>,
+		cli_args: &Vec<String>,
+		profile: Option<String>,
 		redirect: bool
 	) -> Option<Child> {
-		let mut cli_args : Vec<String> = std::env::args().collect();
-		cli_args.remove(0);
-
 		let home = match path {
 			None => std::env::current_dir().unwrap(),
 			Some(path) => path
 		};
 		let lang = conf::proj_lang(&home).expect("");
-		let transl = Transl::new(&lang);
+		let lexicon = conf::lexicon(&home, &lang);
+		let transl = Transl::new(&lang, &lexicon);
 		let proj_name = conf::proj_name(&transl, &home);
 		let build_path = build::build_path(&transl, &home, &proj_name);
 		let work_dir = format!("{}", build_path.display());
-		Compile::exec(Some(home));
-		Cargo::new().run(&work_dir, &cli_args, redirect)
+		let profile = profile.map(|name| conf::profile_name(&transl, &name));
+		Compile::exec(Some(home), &PassOptions::default());
+		Cargo::new(transl).run(&work_dir, cli_args, profile.as_deref(), redirect)
 	}	
 }
 
@@ -432,6 +500,37 @@ pub struct Update {}
 pub struct Check {}
 
 
+//================
+//   Lexicon
+//================
+#[derive(Parser,Debug)]
+#[command()]
+pub struct Lexicon {}
+
+impl Lexicon {
+    //---------------------
+    //  exec()
+    //---------------------		
+	pub fn exec(path: Option<PathBuf>) {
+		let home = match path {
+			None => std::env::current_dir().unwrap(),
+			Some(path) => path
+		};
+		// the project's lexicon when run inside a project, the embedded one otherwise
+		let lexicon = match conf::proj_lang(&home) {
+			Ok(lang) => lexicon::Lexicon::load(&home, &lang),
+			Err(_) => Ok(lexicon::Lexicon::embedded().clone())
+		};
+		match lexicon {
+			Ok(lexicon) => print!("{}", lexicon.doc()),
+			Err(err) => {
+				eprintln!("{}", err);
+				std::process::exit(1);
+			}
+		}
+	}
+}
+
 //================
 //   Editor
 //================
@@ -462,6 +561,13 @@ impl Editor {
 	}
 }
 
+//================
+//   is_seen_file()
+//================
+fn is_seen_file(path: &PathBuf) -> bool {
+	Lang::try_from_ext(&format!("{}", path.display())).is_some()
+}
+
 //================
 //   check_dir_not_exists()
 //================